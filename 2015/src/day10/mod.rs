@@ -0,0 +1,39 @@
+pub fn run(input: &str) {
+    let mut sequence = input.trim().to_owned();
+
+    for round in 1..51 {
+        sequence = look_and_say(&sequence);
+        if round == 40 || round == 50 {
+            println!("After {} rounds the length is {}", round, sequence.len());
+        }
+    }
+}
+
+fn look_and_say(sequence: &str) -> String {
+    let mut result = String::with_capacity(sequence.len() * 2);
+    let mut chars = sequence.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let mut count = 1;
+        while chars.peek() == Some(&c) {
+            chars.next();
+            count += 1;
+        }
+        result.push_str(&count.to_string());
+        result.push(c);
+    }
+
+    result
+}
+
+#[test]
+fn test_look_and_say() {
+    let mut sequence = "1".to_owned();
+    let mut seen = vec![];
+    for _ in 0..5 {
+        sequence = look_and_say(&sequence);
+        seen.push(sequence.clone());
+    }
+
+    assert_eq!(seen, vec!["11", "21", "1211", "111221", "312211"]);
+}
@@ -0,0 +1,71 @@
+pub fn run(input: &str) {
+    let first = next_password(input.trim());
+    println!("Santa's next password is {}", first);
+
+    let second = next_password(&first);
+    println!("And the one after that is {}", second);
+}
+
+/// The next password after `current` which meets the security elf's rules.
+fn next_password(current: &str) -> String {
+    let mut password = current.as_bytes().to_vec();
+
+    loop {
+        increment(&mut password);
+        if is_valid(&password) {
+            return String::from_utf8(password).unwrap();
+        }
+    }
+}
+
+fn increment(password: &mut [u8]) {
+    // skipping straight past a banned letter also resets everything after it, which saves
+    // working through all the passwords which would contain it
+    if let Some(banned) = password.iter().position(|c| is_banned(*c)) {
+        password[banned] += 1;
+        for c in password[banned + 1..].iter_mut() {
+            *c = b'a';
+        }
+        return;
+    }
+
+    for c in password.iter_mut().rev() {
+        if *c == b'z' {
+            *c = b'a';
+        } else {
+            *c += 1;
+            if is_banned(*c) {
+                *c += 1;
+            }
+            return;
+        }
+    }
+}
+
+fn is_banned(c: u8) -> bool {
+    c == b'i' || c == b'o' || c == b'l'
+}
+
+fn is_valid(password: &[u8]) -> bool {
+    let has_straight = password.windows(3).any(|w| w[0] + 1 == w[1] && w[1] + 1 == w[2]);
+    let no_banned = !password.iter().any(|c| is_banned(*c));
+
+    let mut pairs = password.windows(2).filter(|w| w[0] == w[1]).map(|w| w[0]).collect::<Vec<_>>();
+    pairs.dedup();
+
+    has_straight && no_banned && pairs.len() >= 2
+}
+
+#[test]
+fn test_rules() {
+    assert!(!is_valid(b"hijklmmn"));
+    assert!(!is_valid(b"abbceffg"));
+    assert!(!is_valid(b"abbcegjk"));
+    assert!(is_valid(b"abcdffaa"));
+}
+
+#[test]
+fn test_next_password() {
+    assert_eq!(next_password("abcdefgh"), "abcdffaa");
+    assert_eq!(next_password("ghijklmn"), "ghjaabcc");
+}
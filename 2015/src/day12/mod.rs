@@ -0,0 +1,56 @@
+mod parser;
+
+use self::parser::{parse_json, Json};
+
+pub fn run(input: &str) {
+    let json = parse_json(input).unwrap_or_else(|e| panic!("{}", e));
+
+    println!("The numbers add up to {}", sum_numbers(&json, false));
+    println!("Leaving out anything red they add up to {}", sum_numbers(&json, true));
+}
+
+/// Adds up every number in the document, optionally skipping any object with a "red" value
+/// along with everything inside it.
+fn sum_numbers(json: &Json, ignore_red: bool) -> i64 {
+    match *json {
+        Json::Number(n) => n,
+        Json::String(_) => 0,
+        Json::Array(ref values) => values.iter().map(|v| sum_numbers(v, ignore_red)).sum(),
+        Json::Object(ref members) => {
+            let red = members.iter().any(|(_, v)| *v == Json::String("red".to_owned()));
+            if ignore_red && red {
+                0
+            } else {
+                members.iter().map(|(_, v)| sum_numbers(v, ignore_red)).sum()
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sum_numbers() {
+    let examples = [("[1,2,3]", 6),
+                    (r#"{"a":2,"b":4}"#, 6),
+                    ("[[[3]]]", 3),
+                    (r#"{"a":{"b":4},"c":-1}"#, 3),
+                    (r#"{"a":[-1,1]}"#, 0),
+                    (r#"[-1,{"a":1}]"#, 0),
+                    ("[]", 0),
+                    ("{}", 0)];
+
+    for &(input, sum) in examples.iter() {
+        assert_eq!(sum_numbers(&parse_json(input).unwrap(), false), sum, "{}", input);
+    }
+}
+
+#[test]
+fn test_sum_numbers_ignoring_red() {
+    let examples = [("[1,2,3]", 6),
+                    (r#"[1,{"c":"red","b":2},3]"#, 4),
+                    (r#"{"d":"red","e":[1,2,3,4],"f":5}"#, 0),
+                    (r#"[1,"red",5]"#, 6)];
+
+    for &(input, sum) in examples.iter() {
+        assert_eq!(sum_numbers(&parse_json(input).unwrap(), true), sum, "{}", input);
+    }
+}
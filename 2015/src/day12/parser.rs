@@ -0,0 +1,60 @@
+use pest::Parser;
+use pest;
+use std::str::FromStr;
+
+#[cfg(debug_assertions)]
+const _GRAMMAR: &str = include_str!("grammar.pest");
+
+#[derive(Parser)]
+#[grammar = "day12/grammar.pest"]
+struct JsonParser;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+pub fn parse_json(input: &str) -> Result<Json, String> {
+    let mut pairs = JsonParser::parse(Rule::document, input.trim()).map_err(|e| format!("{}", e))?;
+
+    Ok(to_json(pairs.next().unwrap()))
+}
+
+fn to_json(pair: pest::iterators::Pair<Rule>) -> Json {
+    match pair.as_rule() {
+        Rule::number => Json::Number(i64::from_str(pair.as_str()).expect("Number should fit in an i64")),
+        Rule::string => Json::String(unquote(pair.as_str())),
+        Rule::array => Json::Array(pair.into_inner().map(to_json).collect()),
+        Rule::object => {
+            Json::Object(pair.into_inner()
+                .map(|member| {
+                    let mut inner = member.into_inner();
+                    let key = unquote(inner.next().unwrap().as_str());
+                    let value = to_json(inner.next().unwrap());
+                    (key, value)
+                })
+                .collect())
+        }
+        rule => unreachable!("{:?} isn't a value", rule),
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s[1..s.len() - 1].to_owned()
+}
+
+#[test]
+fn test_parse() {
+    let json = parse_json(r#"{"a": [1, -2, "red"], "b": {}}"#);
+
+    assert_eq!(json,
+               Ok(Json::Object(vec![("a".to_owned(),
+                                     Json::Array(vec![Json::Number(1),
+                                                      Json::Number(-2),
+                                                      Json::String("red".to_owned())])),
+                                    ("b".to_owned(), Json::Object(vec![]))])));
+    assert!(parse_json("[1, 2").is_err());
+}
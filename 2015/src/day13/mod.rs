@@ -0,0 +1,91 @@
+use permutations::permutations;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let mut preferences = parse_preferences(input).expect("Preferences should parse");
+
+    println!("The best seating plan has happiness {}", best_arrangement(&preferences));
+
+    let guests = guests(&preferences);
+    for guest in guests {
+        preferences.insert(("me".to_owned(), guest.clone()), 0);
+        preferences.insert((guest, "me".to_owned()), 0);
+    }
+
+    println!("Including me it has happiness {}", best_arrangement(&preferences));
+}
+
+/// How much happier the first person is sitting next to the second.
+type Preferences = HashMap<(String, String), i32>;
+
+fn parse_preferences(input: &str) -> Option<Preferences> {
+    lazy_static! {
+        static ref PREFERENCE: Regex =
+            Regex::new(r"^(\w+) would (gain|lose) (\d+) happiness units? by sitting next to (\w+)\.$").unwrap();
+    }
+
+    let mut preferences = HashMap::new();
+
+    for line in input.lines() {
+        let caps = PREFERENCE.captures(line.trim())?;
+        let amount = i32::from_str(caps.get(3).unwrap().as_str()).ok()?;
+        let amount = if caps.get(2).unwrap().as_str() == "lose" { -amount } else { amount };
+        preferences.insert((caps.get(1).unwrap().as_str().to_owned(),
+                            caps.get(4).unwrap().as_str().to_owned()),
+                           amount);
+    }
+
+    Some(preferences)
+}
+
+fn guests(preferences: &Preferences) -> Vec<String> {
+    preferences.keys().map(|(guest, _)| guest.clone()).collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+/// The total change in happiness of the best arrangement around a round table.
+fn best_arrangement(preferences: &Preferences) -> i32 {
+    let guests = guests(preferences);
+    if guests.len() < 2 {
+        return 0;
+    }
+
+    // the table is round, so fixing where the first guest sits doesn't lose any arrangements
+    permutations(&guests[1..])
+        .into_iter()
+        .map(|rest| {
+            let mut table = vec![guests[0].clone()];
+            table.extend(rest);
+            (0..table.len())
+                .map(|i| {
+                    let a = &table[i];
+                    let b = &table[(i + 1) % table.len()];
+                    preferences[&(a.clone(), b.clone())] + preferences[&(b.clone(), a.clone())]
+                })
+                .sum()
+        })
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn test_example() {
+    let input = "Alice would gain 54 happiness units by sitting next to Bob.
+Alice would lose 79 happiness units by sitting next to Carol.
+Alice would lose 2 happiness units by sitting next to David.
+Bob would gain 83 happiness units by sitting next to Alice.
+Bob would lose 7 happiness units by sitting next to Carol.
+Bob would lose 63 happiness units by sitting next to David.
+Carol would lose 62 happiness units by sitting next to Alice.
+Carol would gain 60 happiness units by sitting next to Bob.
+Carol would gain 55 happiness units by sitting next to David.
+David would gain 46 happiness units by sitting next to Alice.
+David would lose 7 happiness units by sitting next to Bob.
+David would gain 41 happiness units by sitting next to Carol.";
+
+    let preferences = parse_preferences(input).unwrap();
+
+    assert_eq!(preferences.len(), 12);
+    assert_eq!(best_arrangement(&preferences), 330);
+}
@@ -0,0 +1,98 @@
+use regex::Regex;
+use std::cmp;
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let reindeer = input.lines()
+        .map(|l| Reindeer::from_str(l).expect("All reindeer should parse"))
+        .collect::<Vec<_>>();
+
+    let furthest = reindeer.iter().map(|r| r.distance_after(2503)).max().unwrap_or(0);
+    println!("The winning reindeer has gone {} km", furthest);
+
+    let points = race_for_points(&reindeer, 2503);
+    println!("The winning reindeer has {} points", points.iter().max().unwrap_or(&0));
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Reindeer {
+    name: String,
+    speed: u32,
+    flying_time: u32,
+    resting_time: u32,
+}
+
+impl Reindeer {
+    fn distance_after(&self, seconds: u32) -> u32 {
+        let cycle = self.flying_time + self.resting_time;
+        let whole_cycles = seconds / cycle;
+        let remainder = seconds % cycle;
+
+        self.speed * (whole_cycles * self.flying_time + cmp::min(remainder, self.flying_time))
+    }
+}
+
+impl FromStr for Reindeer {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref REINDEER: Regex = Regex::new(
+                r"^(\w+) can fly (\d+) km/s for (\d+) seconds?, but then must rest for (\d+) seconds?\.$").unwrap();
+        }
+
+        let caps = REINDEER.captures(s.trim()).ok_or(())?;
+        let number = |i| u32::from_str(caps.get(i).unwrap().as_str()).map_err(|_| ());
+
+        Ok(Reindeer {
+            name: caps.get(1).unwrap().as_str().to_owned(),
+            speed: number(2)?,
+            flying_time: number(3)?,
+            resting_time: number(4)?,
+        })
+    }
+}
+
+/// The points each reindeer has after `seconds`, where every second all the reindeer in the
+/// lead get a point.
+fn race_for_points(reindeer: &[Reindeer], seconds: u32) -> Vec<u32> {
+    let mut points = vec![0; reindeer.len()];
+
+    for second in 1..seconds + 1 {
+        let distances = reindeer.iter().map(|r| r.distance_after(second)).collect::<Vec<_>>();
+        let lead = *distances.iter().max().unwrap_or(&0);
+        for (i, distance) in distances.iter().enumerate() {
+            if *distance == lead {
+                points[i] += 1;
+            }
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+fn example() -> Vec<Reindeer> {
+    "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
+Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds."
+        .lines()
+        .map(|l| Reindeer::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_distance() {
+    let reindeer = example();
+
+    assert_eq!(reindeer[0].name, "Comet");
+    assert_eq!(reindeer[0].distance_after(1), 14);
+    assert_eq!(reindeer[1].distance_after(10), 160);
+    assert_eq!(reindeer[0].distance_after(1000), 1120);
+    assert_eq!(reindeer[1].distance_after(1000), 1056);
+}
+
+#[test]
+fn test_points() {
+    assert_eq!(race_for_points(&example(), 1), vec![0, 1]);
+    assert_eq!(race_for_points(&example(), 1000), vec![312, 689]);
+}
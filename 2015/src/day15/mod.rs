@@ -0,0 +1,98 @@
+use regex::Regex;
+use std::cmp;
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let ingredients = input.lines()
+        .map(|l| Ingredient::from_str(l).expect("All ingredients should parse"))
+        .collect::<Vec<_>>();
+
+    println!("The best cookie scores {}", best_score(&ingredients, None));
+    println!("The best 500 calorie cookie scores {}", best_score(&ingredients, Some(500)));
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Ingredient {
+    // capacity, durability, flavour and texture
+    properties: [i64; 4],
+    calories: i64,
+}
+
+impl FromStr for Ingredient {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref INGREDIENT: Regex = Regex::new(
+                r"^\w+: capacity (-?\d+), durability (-?\d+), flavor (-?\d+), texture (-?\d+), calories (-?\d+)$").unwrap();
+        }
+
+        let caps = INGREDIENT.captures(s.trim()).ok_or(())?;
+        let number = |i| i64::from_str(caps.get(i).unwrap().as_str()).map_err(|_| ());
+
+        Ok(Ingredient {
+            properties: [number(1)?, number(2)?, number(3)?, number(4)?],
+            calories: number(5)?,
+        })
+    }
+}
+
+/// The score of a cookie made with `amounts[i]` teaspoons of `ingredients[i]`, or `None` if
+/// it doesn't have the calories wanted.
+fn score(ingredients: &[Ingredient], amounts: &[i64], calories: Option<i64>) -> Option<i64> {
+    let total_calories = ingredients.iter().zip(amounts).map(|(i, a)| i.calories * a).sum::<i64>();
+    if calories.map(|c| c != total_calories).unwrap_or(false) {
+        return None;
+    }
+
+    Some((0..4)
+        .map(|p| cmp::max(0, ingredients.iter().zip(amounts).map(|(i, a)| i.properties[p] * a).sum()))
+        .product())
+}
+
+/// Tries every way of sharing 100 teaspoons between the ingredients.
+fn best_score(ingredients: &[Ingredient], calories: Option<i64>) -> i64 {
+    fn best_from(ingredients: &[Ingredient], calories: Option<i64>, amounts: &mut Vec<i64>, left: i64) -> i64 {
+        if amounts.len() + 1 == ingredients.len() {
+            amounts.push(left);
+            let result = score(ingredients, amounts, calories).unwrap_or(0);
+            amounts.pop();
+            return result;
+        }
+
+        let mut best = 0;
+        for amount in 0..left + 1 {
+            amounts.push(amount);
+            best = cmp::max(best, best_from(ingredients, calories, amounts, left - amount));
+            amounts.pop();
+        }
+        best
+    }
+
+    if ingredients.is_empty() {
+        return 0;
+    }
+    best_from(ingredients, calories, &mut vec![], 100)
+}
+
+#[cfg(test)]
+fn example() -> Vec<Ingredient> {
+    "Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3"
+        .lines()
+        .map(|l| Ingredient::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_score() {
+    assert_eq!(score(&example(), &[44, 56], None), Some(62842880));
+    assert_eq!(score(&example(), &[40, 60], Some(500)), Some(57600000));
+    assert_eq!(score(&example(), &[44, 56], Some(500)), None);
+}
+
+#[test]
+fn test_best_score() {
+    assert_eq!(best_score(&example(), None), 62842880);
+    assert_eq!(best_score(&example(), Some(500)), 57600000);
+}
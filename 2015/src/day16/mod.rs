@@ -0,0 +1,87 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let aunts = input.lines()
+        .map(|l| parse_aunt(l).expect("All aunts should parse"))
+        .collect::<Vec<_>>();
+
+    let readings = mfcsam_readings();
+
+    for &(number, ref things) in aunts.iter() {
+        if matches_exactly(things, &readings) {
+            println!("The gift was from Aunt Sue {}", number);
+        }
+    }
+
+    for &(number, ref things) in aunts.iter() {
+        if matches_ranges(things, &readings) {
+            println!("With the outdated retroencabulator it was from Aunt Sue {}", number);
+        }
+    }
+}
+
+type Things = HashMap<String, u32>;
+
+fn mfcsam_readings() -> Things {
+    [("children", 3),
+     ("cats", 7),
+     ("samoyeds", 2),
+     ("pomeranians", 3),
+     ("akitas", 0),
+     ("vizslas", 0),
+     ("goldfish", 5),
+     ("trees", 3),
+     ("cars", 2),
+     ("perfumes", 1)]
+        .iter()
+        .map(|&(thing, count)| (thing.to_owned(), count))
+        .collect()
+}
+
+/// Reads lines like `Sue 1: goldfish: 9, cars: 0, samoyeds: 9`.
+fn parse_aunt(line: &str) -> Option<(u32, Things)> {
+    lazy_static! {
+        static ref SUE: Regex = Regex::new(r"^Sue (\d+): (.*)$").unwrap();
+        static ref THING: Regex = Regex::new(r"(\w+): (\d+)").unwrap();
+    }
+
+    let caps = SUE.captures(line.trim())?;
+    let number = u32::from_str(caps.get(1).unwrap().as_str()).ok()?;
+    let things = THING.captures_iter(caps.get(2).unwrap().as_str())
+        .map(|t| (t.get(1).unwrap().as_str().to_owned(), u32::from_str(t.get(2).unwrap().as_str()).unwrap()))
+        .collect();
+
+    Some((number, things))
+}
+
+fn matches_exactly(things: &Things, readings: &Things) -> bool {
+    things.iter().all(|(thing, count)| readings.get(thing) == Some(count))
+}
+
+/// Cats and trees read low and pomeranians and goldfish read high, everything else exactly.
+fn matches_ranges(things: &Things, readings: &Things) -> bool {
+    things.iter().all(|(thing, count)| {
+        match (thing.as_ref(), readings.get(thing)) {
+            ("cats", Some(reading)) | ("trees", Some(reading)) => count > reading,
+            ("pomeranians", Some(reading)) | ("goldfish", Some(reading)) => count < reading,
+            (_, reading) => reading == Some(count),
+        }
+    })
+}
+
+#[test]
+fn test_matching() {
+    let readings = mfcsam_readings();
+    let (number, things) = parse_aunt("Sue 12: cars: 2, cats: 7, goldfish: 5").unwrap();
+
+    assert_eq!(number, 12);
+    assert_eq!(things.len(), 3);
+    assert!(matches_exactly(&things, &readings));
+    assert!(!matches_ranges(&things, &readings));
+
+    let (_, things) = parse_aunt("Sue 40: cats: 9, goldfish: 1, perfumes: 1").unwrap();
+    assert!(!matches_exactly(&things, &readings));
+    assert!(matches_ranges(&things, &readings));
+}
@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let containers = input.lines()
+        .map(|l| u32::from_str(l.trim()).expect("Container sizes should be numbers"))
+        .collect::<Vec<_>>();
+
+    let by_count = combinations_by_count(&containers, 150);
+
+    println!("{} combinations of containers hold 150 litres", by_count.iter().sum::<usize>());
+    if let Some(fewest) = by_count.iter().find(|c| **c > 0) {
+        println!("{} of them use the fewest containers", fewest);
+    }
+}
+
+/// How many combinations of the containers hold exactly `litres`, grouped by how many
+/// containers they use: the count at index `n` is for combinations of `n` containers.
+fn combinations_by_count(containers: &[u32], litres: u32) -> Vec<usize> {
+    // ways[l][n] is how many ways n of the containers seen so far can hold l litres
+    let litres = litres as usize;
+    let mut ways = vec![vec![0; containers.len() + 1]; litres + 1];
+    ways[0][0] = 1;
+
+    for &container in containers {
+        let container = container as usize;
+        for l in (container..litres + 1).rev() {
+            for n in (1..containers.len() + 1).rev() {
+                ways[l][n] += ways[l - container][n - 1];
+            }
+        }
+    }
+
+    ways.swap_remove(litres)
+}
+
+#[test]
+fn test_example() {
+    let by_count = combinations_by_count(&[20, 15, 10, 5, 5], 25);
+
+    assert_eq!(by_count, vec![0, 0, 3, 1, 0, 0]);
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let start = Lights::from_str(input).expect("Lights should parse");
+
+    let mut lights = start.clone();
+    for _ in 0..100 {
+        lights = lights.step();
+    }
+    println!("After 100 steps {} lights are on", lights.count_on());
+
+    let mut lights = start.with_corners_stuck();
+    for _ in 0..100 {
+        lights = lights.step();
+    }
+    println!("With the corners stuck on {} lights are on", lights.count_on());
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Lights {
+    width: usize,
+    height: usize,
+    on: Vec<bool>,
+    corners_stuck: bool,
+}
+
+impl Lights {
+    fn is_on(&self, x: usize, y: usize) -> bool {
+        self.on[y * self.width + x]
+    }
+
+    fn count_on(&self) -> usize {
+        self.on.iter().filter(|l| **l).count()
+    }
+
+    fn is_corner(&self, x: usize, y: usize) -> bool {
+        (x == 0 || x + 1 == self.width) && (y == 0 || y + 1 == self.height)
+    }
+
+    fn with_corners_stuck(&self) -> Lights {
+        let mut result = self.clone();
+        result.corners_stuck = true;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_corner(x, y) {
+                    result.on[y * self.width + x] = true;
+                }
+            }
+        }
+        result
+    }
+
+    fn neighbours_on(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                if (nx, ny) != (x, y) && self.is_on(nx, ny) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn step(&self) -> Lights {
+        let mut on = Vec::with_capacity(self.on.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let neighbours = self.neighbours_on(x, y);
+                on.push((self.corners_stuck && self.is_corner(x, y)) || neighbours == 3 ||
+                        (neighbours == 2 && self.is_on(x, y)));
+            }
+        }
+
+        Lights { on, ..*self }
+    }
+}
+
+impl FromStr for Lights {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut on = vec![];
+
+        for line in input.trim().lines() {
+            let line = line.trim();
+            if height > 0 && line.len() != width {
+                return Err(format!("Line {} is a different length", height + 1));
+            }
+            width = line.len();
+            height += 1;
+
+            for c in line.chars() {
+                match c {
+                    '#' => on.push(true),
+                    '.' => on.push(false),
+                    _ => return Err(format!("Unexpected character {}", c)),
+                }
+            }
+        }
+
+        Ok(Lights { width, height, on, corners_stuck: false })
+    }
+}
+
+impl fmt::Display for Lights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.is_on(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = ".#.#.#
+...##.
+#....#
+..#...
+#.#..#
+####..";
+
+#[test]
+fn test_example() {
+    let mut lights = Lights::from_str(EXAMPLE).unwrap();
+
+    lights = lights.step();
+    assert_eq!(lights.to_string(),
+               "..##..
+..##.#
+...##.
+......
+#.....
+#.##..
+");
+
+    for _ in 1..4 {
+        lights = lights.step();
+    }
+    assert_eq!(lights.count_on(), 4);
+}
+
+#[test]
+fn test_corners_stuck() {
+    let mut lights = Lights::from_str(EXAMPLE).unwrap().with_corners_stuck();
+
+    for _ in 0..5 {
+        lights = lights.step();
+    }
+    assert_eq!(lights.count_on(), 17);
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+pub fn run(input: &str) {
+    let (replacements, molecule) = parse(input).expect("Input should parse");
+
+    println!("{} distinct molecules can be made", one_step_molecules(&replacements, &molecule).len());
+
+    match fewest_steps_from_electron(&replacements, &molecule) {
+        Some(steps) => println!("The medicine takes {} steps to make", steps),
+        None => println!("I couldn't find a way to make the medicine"),
+    }
+}
+
+type Replacement = (String, String);
+
+/// Reads the `from => to` replacements, then a blank line and the molecule.
+fn parse(input: &str) -> Option<(Vec<Replacement>, String)> {
+    let mut replacements = vec![];
+    let mut lines = input.lines().map(|l| l.trim());
+
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        let mut parts = line.split(" => ");
+        let from = parts.next()?;
+        let to = parts.next()?;
+        replacements.push((from.to_owned(), to.to_owned()));
+    }
+
+    let molecule = lines.find(|l| !l.is_empty())?;
+    Some((replacements, molecule.to_owned()))
+}
+
+fn one_step_molecules(replacements: &[Replacement], molecule: &str) -> HashSet<String> {
+    let mut result = HashSet::new();
+
+    for (from, to) in replacements {
+        for (index, _) in molecule.match_indices(from.as_str()) {
+            let mut made = molecule[..index].to_owned();
+            made.push_str(to);
+            made.push_str(&molecule[index + from.len()..]);
+            result.insert(made);
+        }
+    }
+
+    result
+}
+
+/// Works backwards from the molecule, undoing replacements until there's only an electron
+/// left. Undoing the longest replacements first gets there almost every time, and when it gets
+/// stuck we shuffle the order and try again.
+fn fewest_steps_from_electron(replacements: &[Replacement], molecule: &str) -> Option<usize> {
+    let mut order = replacements.to_vec();
+    order.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.cmp(b)));
+    let mut seed = 0x2015_1219u32;
+
+    for _ in 0..1000 {
+        if let Some(steps) = reduce_to_electron(&order, molecule) {
+            return Some(steps);
+        }
+
+        // a Fisher-Yates shuffle driven by xorshift, so every run tries the same orders
+        for i in (1..order.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            order.swap(i, seed as usize % (i + 1));
+        }
+    }
+
+    None
+}
+
+fn reduce_to_electron(order: &[Replacement], molecule: &str) -> Option<usize> {
+    let mut current = molecule.to_owned();
+    let mut steps = 0;
+
+    while current != "e" {
+        let undo = order.iter().find(|&(from, to)| {
+            if from == "e" {
+                current == *to
+            } else {
+                current.contains(to.as_str())
+            }
+        })?;
+        current = current.replacen(undo.1.as_str(), &undo.0, 1);
+        steps += 1;
+    }
+
+    Some(steps)
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "e => H
+e => O
+H => HO
+H => OH
+O => HH
+
+HOH";
+
+#[test]
+fn test_one_step() {
+    let (replacements, molecule) = parse(EXAMPLE).unwrap();
+
+    assert_eq!(molecule, "HOH");
+    assert_eq!(one_step_molecules(&replacements, "HOH").len(), 4);
+    assert_eq!(one_step_molecules(&replacements, "HOHOHO").len(), 7);
+}
+
+#[test]
+fn test_fewest_steps() {
+    let (replacements, _) = parse(EXAMPLE).unwrap();
+
+    assert_eq!(fewest_steps_from_electron(&replacements, "HOH"), Some(3));
+    assert_eq!(fewest_steps_from_electron(&replacements, "HOHOHO"), Some(6));
+}
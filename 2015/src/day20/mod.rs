@@ -0,0 +1,47 @@
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let target = usize::from_str(input.trim()).expect("Input should be a number");
+
+    match first_house_with(target, 10, None) {
+        Some(house) => println!("House {} is the first to get {} presents", house, target),
+        None => println!("No house gets {} presents", target),
+    }
+
+    match first_house_with(target, 11, Some(50)) {
+        Some(house) => println!("When elves stop after 50 houses it's house {}", house),
+        None => println!("When elves stop after 50 houses no house gets {} presents", target),
+    }
+}
+
+/// The first house to get at least `target` presents when elf `n` leaves `n * per_house`
+/// presents at every `n`th house, and optionally gives up after visiting `limit` houses.
+fn first_house_with(target: usize, per_house: usize, limit: Option<usize>) -> Option<usize> {
+    // elf n alone brings house n enough once n * per_house reaches the target
+    let houses = target / per_house + 1;
+    let mut presents = vec![0; houses + 1];
+
+    for elf in 1..houses + 1 {
+        let visits = limit.unwrap_or(houses);
+        for house in (1..visits + 1).map(|v| v * elf).take_while(|h| *h <= houses) {
+            presents[house] += elf * per_house;
+        }
+    }
+
+    presents.iter().position(|p| *p >= target)
+}
+
+#[test]
+fn test_example() {
+    assert_eq!(first_house_with(10, 10, None), Some(1));
+    assert_eq!(first_house_with(70, 10, None), Some(4));
+    assert_eq!(first_house_with(120, 10, None), Some(6));
+    assert_eq!(first_house_with(130, 10, None), Some(8));
+}
+
+#[test]
+fn test_lazy_elves() {
+    // elf 1 stops after house 2, so house 3 only gets presents from elf 3
+    assert_eq!(first_house_with(40, 11, Some(2)), Some(4));
+    assert_eq!(first_house_with(40, 11, None), Some(3));
+}
@@ -0,0 +1,130 @@
+use std::cmp;
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let boss = parse_boss(input).expect("Boss stats should parse");
+
+    let outcomes = loadouts()
+        .into_iter()
+        .map(|items| {
+            let player = Fighter::equipped(100, &items);
+            (items.iter().map(|i| i.cost).sum::<u32>(), player.beats(&boss))
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(cheapest) = outcomes.iter().filter(|o| o.1).map(|o| o.0).min() {
+        println!("The least gold needed to win is {}", cheapest);
+    }
+    if let Some(dearest) = outcomes.iter().filter(|o| !o.1).map(|o| o.0).max() {
+        println!("The most gold the shopkeeper can make me lose with is {}", dearest);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Fighter {
+    pub hit_points: u32,
+    pub damage: u32,
+    pub armor: u32,
+}
+
+impl Fighter {
+    fn equipped(hit_points: u32, items: &[Item]) -> Fighter {
+        Fighter {
+            hit_points,
+            damage: items.iter().map(|i| i.damage).sum(),
+            armor: items.iter().map(|i| i.armor).sum(),
+        }
+    }
+
+    fn turns_to_kill(&self, other: &Fighter) -> u32 {
+        let per_hit = cmp::max(1, self.damage.saturating_sub(other.armor));
+        other.hit_points.div_ceil(per_hit)
+    }
+
+    /// Whether this fighter wins when it goes first.
+    fn beats(&self, other: &Fighter) -> bool {
+        self.turns_to_kill(other) <= other.turns_to_kill(self)
+    }
+}
+
+/// Reads the `Hit Points`, `Damage` and, if it's there, `Armor` lines. Day 22's boss uses this
+/// too.
+pub fn parse_boss(input: &str) -> Option<Fighter> {
+    let mut boss = Fighter { hit_points: 0, damage: 0, armor: 0 };
+
+    for line in input.lines().filter(|l| !l.trim().is_empty()) {
+        let mut parts = line.split(':');
+        let stat = parts.next()?.trim();
+        let value = u32::from_str(parts.next()?.trim()).ok()?;
+        match stat {
+            "Hit Points" => boss.hit_points = value,
+            "Damage" => boss.damage = value,
+            "Armor" => boss.armor = value,
+            _ => return None,
+        }
+    }
+
+    Some(boss)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Item {
+    cost: u32,
+    damage: u32,
+    armor: u32,
+}
+
+const fn item(cost: u32, damage: u32, armor: u32) -> Item {
+    Item { cost, damage, armor }
+}
+
+const WEAPONS: [Item; 5] = [item(8, 4, 0), item(10, 5, 0), item(25, 6, 0), item(40, 7, 0), item(74, 8, 0)];
+
+const ARMOR: [Item; 5] = [item(13, 0, 1), item(31, 0, 2), item(53, 0, 3), item(75, 0, 4), item(102, 0, 5)];
+
+const RINGS: [Item; 6] = [item(25, 1, 0), item(50, 2, 0), item(100, 3, 0), item(20, 0, 1), item(40, 0, 2),
+                          item(80, 0, 3)];
+
+/// Every allowed set of items: one weapon, up to one piece of armor and up to two different
+/// rings.
+fn loadouts() -> Vec<Vec<Item>> {
+    let armors = ARMOR.iter().map(|a| vec![*a]).chain(Some(vec![])).collect::<Vec<_>>();
+
+    let mut ring_sets = vec![vec![]];
+    for (i, a) in RINGS.iter().enumerate() {
+        ring_sets.push(vec![*a]);
+        for b in RINGS[i + 1..].iter() {
+            ring_sets.push(vec![*a, *b]);
+        }
+    }
+
+    let mut result = vec![];
+    for weapon in WEAPONS.iter() {
+        for armor in armors.iter() {
+            for rings in ring_sets.iter() {
+                let mut items = vec![*weapon];
+                items.extend(armor);
+                items.extend(rings);
+                result.push(items);
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn test_example_fight() {
+    let player = Fighter { hit_points: 8, damage: 5, armor: 5 };
+    let boss = parse_boss("Hit Points: 12\nDamage: 7\nArmor: 2").unwrap();
+
+    assert_eq!(boss, Fighter { hit_points: 12, damage: 7, armor: 2 });
+    assert_eq!(player.turns_to_kill(&boss), 4);
+    assert_eq!(boss.turns_to_kill(&player), 4);
+    assert!(player.beats(&boss));
+}
+
+#[test]
+fn test_loadouts() {
+    // 5 weapons, 6 choices of armor and 1 + 6 + 15 choices of rings
+    assert_eq!(loadouts().len(), 5 * 6 * 22);
+}
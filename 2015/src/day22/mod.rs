@@ -0,0 +1,237 @@
+use day21::parse_boss;
+use std::cmp::{self, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+pub fn run(input: &str) {
+    let boss = parse_boss(input).expect("Boss stats should parse");
+    let start = Battle::new(50, 500, boss.hit_points, boss.damage);
+
+    match least_mana_to_win(start, false) {
+        Some(mana) => println!("The least mana needed to win is {}", mana),
+        None => println!("The boss can't be beaten"),
+    }
+    match least_mana_to_win(start, true) {
+        Some(mana) => println!("On hard it's {}", mana),
+        None => println!("The boss can't be beaten on hard"),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+enum Spell {
+    MagicMissile,
+    Drain,
+    Shield,
+    Poison,
+    Recharge,
+}
+
+const SPELLS: [Spell; 5] = [Spell::MagicMissile, Spell::Drain, Spell::Shield, Spell::Poison, Spell::Recharge];
+
+impl Spell {
+    fn cost(&self) -> u32 {
+        match *self {
+            Spell::MagicMissile => 53,
+            Spell::Drain => 73,
+            Spell::Shield => 113,
+            Spell::Poison => 173,
+            Spell::Recharge => 229,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Outcome {
+    Won,
+    Lost,
+    Continues(Battle),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+struct Battle {
+    hit_points: i32,
+    mana: u32,
+    boss_hit_points: i32,
+    boss_damage: i32,
+    shield_timer: u32,
+    poison_timer: u32,
+    recharge_timer: u32,
+}
+
+impl Battle {
+    fn new(hit_points: u32, mana: u32, boss_hit_points: u32, boss_damage: u32) -> Battle {
+        Battle {
+            hit_points: hit_points as i32,
+            mana,
+            boss_hit_points: boss_hit_points as i32,
+            boss_damage: boss_damage as i32,
+            shield_timer: 0,
+            poison_timer: 0,
+            recharge_timer: 0,
+        }
+    }
+
+    /// Runs the effects at the start of a turn, returning the player's armor for the turn.
+    fn apply_effects(&mut self) -> i32 {
+        let armor = if self.shield_timer > 0 { 7 } else { 0 };
+        if self.poison_timer > 0 {
+            self.boss_hit_points -= 3;
+        }
+        if self.recharge_timer > 0 {
+            self.mana += 101;
+        }
+        self.shield_timer = self.shield_timer.saturating_sub(1);
+        self.poison_timer = self.poison_timer.saturating_sub(1);
+        self.recharge_timer = self.recharge_timer.saturating_sub(1);
+        armor
+    }
+
+    /// The start of the player's turn, when hard mode takes its toll and the effects tick.
+    fn start_turn(&self, hard: bool) -> Outcome {
+        let mut next = *self;
+
+        if hard {
+            next.hit_points -= 1;
+            if next.hit_points <= 0 {
+                return Outcome::Lost;
+            }
+        }
+
+        next.apply_effects();
+        if next.boss_hit_points <= 0 {
+            Outcome::Won
+        } else {
+            Outcome::Continues(next)
+        }
+    }
+
+    /// Once the turn has started, the player casts `spell` and then the boss has its turn.
+    /// Returns `None` if the spell can't be cast.
+    fn cast(&self, spell: Spell) -> Option<Outcome> {
+        let mut next = *self;
+
+        if spell.cost() > next.mana {
+            return None;
+        }
+        next.mana -= spell.cost();
+        match spell {
+            Spell::MagicMissile => next.boss_hit_points -= 4,
+            Spell::Drain => {
+                next.boss_hit_points -= 2;
+                next.hit_points += 2;
+            }
+            Spell::Shield if next.shield_timer == 0 => next.shield_timer = 6,
+            Spell::Poison if next.poison_timer == 0 => next.poison_timer = 6,
+            Spell::Recharge if next.recharge_timer == 0 => next.recharge_timer = 5,
+            _ => return None,
+        }
+
+        let armor = next.apply_effects();
+        if next.boss_hit_points <= 0 {
+            return Some(Outcome::Won);
+        }
+
+        next.hit_points -= cmp::max(1, next.boss_damage - armor);
+        if next.hit_points <= 0 {
+            Some(Outcome::Lost)
+        } else {
+            Some(Outcome::Continues(next))
+        }
+    }
+
+    /// A whole round: the player's turn casting `spell`, then the boss's turn.
+    #[cfg(test)]
+    fn round(&self, spell: Spell, hard: bool) -> Option<Outcome> {
+        match self.start_turn(hard) {
+            Outcome::Continues(next) => next.cast(spell),
+            outcome => Some(outcome),
+        }
+    }
+}
+
+/// Dijkstra's algorithm over the states of the battle, ordered by the mana spent so far.
+fn least_mana_to_win(start: Battle, hard: bool) -> Option<u32> {
+    let mut queue = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((spent, battle))) = queue.pop() {
+        if battle.boss_hit_points <= 0 {
+            return Some(spent);
+        }
+        if !seen.insert(battle) {
+            continue;
+        }
+
+        let turn = match battle.start_turn(hard) {
+            // nothing is spent on a turn the effects win before anything's cast
+            Outcome::Won => return Some(spent),
+            Outcome::Lost => continue,
+            Outcome::Continues(turn) => turn,
+        };
+
+        for spell in SPELLS.iter() {
+            let spent = spent + spell.cost();
+            match turn.cast(*spell) {
+                // this state will be popped again with less spent if that's possible
+                Some(Outcome::Won) => queue.push(Reverse((spent, Battle { boss_hit_points: 0, ..turn }))),
+                Some(Outcome::Continues(next)) => queue.push(Reverse((spent, next))),
+                Some(Outcome::Lost) | None => {}
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+fn play(mut battle: Battle, spells: &[Spell]) -> Outcome {
+    for spell in spells {
+        match battle.round(*spell, false) {
+            Some(Outcome::Continues(next)) => battle = next,
+            Some(outcome) => return outcome,
+            None => panic!("Couldn't cast {:?}", spell),
+        }
+    }
+    Outcome::Continues(battle)
+}
+
+#[test]
+fn test_examples() {
+    let first = Battle::new(10, 250, 13, 8);
+    assert_eq!(play(first, &[Spell::Poison, Spell::MagicMissile]), Outcome::Won);
+    assert_eq!(least_mana_to_win(first, false), Some(173 + 53));
+
+    let second = Battle::new(10, 250, 14, 8);
+    let spells = [Spell::Recharge, Spell::Shield, Spell::Drain, Spell::Poison, Spell::MagicMissile];
+    assert_eq!(play(second, &spells), Outcome::Won);
+    assert_eq!(play(second, &spells[..4]),
+               Outcome::Continues(Battle {
+                   hit_points: 1,
+                   mana: 167,
+                   boss_hit_points: 9,
+                   boss_damage: 8,
+                   shield_timer: 1,
+                   poison_timer: 5,
+                   recharge_timer: 0,
+               }));
+}
+
+#[test]
+fn test_poison_wins_at_start_of_turn() {
+    // three ticks of poison with a magic missile in between finish the boss at the start of the
+    // player's next turn, so nothing more needs casting
+    let battle = Battle {
+        hit_points: 10,
+        mana: 60,
+        boss_hit_points: 13,
+        boss_damage: 8,
+        shield_timer: 0,
+        poison_timer: 4,
+        recharge_timer: 0,
+    };
+    assert_eq!(least_mana_to_win(battle, false), Some(53));
+
+    let finished = Battle { boss_hit_points: 3, poison_timer: 1, ..battle };
+    assert_eq!(finished.start_turn(false), Outcome::Won);
+    assert_eq!(least_mana_to_win(finished, false), Some(0));
+}
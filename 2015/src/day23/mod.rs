@@ -0,0 +1,55 @@
+mod parser;
+
+use self::parser::{parse_line, Instruction, Register};
+
+pub fn run(input: &str) {
+    let program = input.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| parse_line(l).unwrap_or_else(|e| panic!("{}", e)))
+        .collect::<Vec<_>>();
+
+    println!("Register b ends up as {}", execute(&program, 0).1);
+    println!("Starting with a as 1, register b ends up as {}", execute(&program, 1).1);
+}
+
+/// Runs the program until it jumps outside itself, returning the final values of a and b.
+fn execute(program: &[Instruction], a: u64) -> (u64, u64) {
+    let mut registers = [a, 0];
+    let mut pc = 0isize;
+
+    fn index(register: Register) -> usize {
+        match register {
+            Register::A => 0,
+            Register::B => 1,
+        }
+    }
+
+    while pc >= 0 && (pc as usize) < program.len() {
+        pc += match program[pc as usize] {
+            Instruction::Half(r) => {
+                registers[index(r)] /= 2;
+                1
+            }
+            Instruction::Triple(r) => {
+                registers[index(r)] *= 3;
+                1
+            }
+            Instruction::Increment(r) => {
+                registers[index(r)] += 1;
+                1
+            }
+            Instruction::Jump(offset) => offset,
+            Instruction::JumpIfEven(r, offset) => if registers[index(r)].is_multiple_of(2) { offset } else { 1 },
+            Instruction::JumpIfOne(r, offset) => if registers[index(r)] == 1 { offset } else { 1 },
+        };
+    }
+
+    (registers[0], registers[1])
+}
+
+#[test]
+fn test_example() {
+    let program = "inc a\njio a, +2\ntpl a\ninc a".lines().map(|l| parse_line(l).unwrap()).collect::<Vec<_>>();
+
+    assert_eq!(execute(&program, 0), (2, 0));
+}
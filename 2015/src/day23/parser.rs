@@ -0,0 +1,68 @@
+use pest::Parser;
+use pest;
+use std::str::FromStr;
+
+#[cfg(debug_assertions)]
+const _GRAMMAR: &str = include_str!("grammar.pest");
+
+#[derive(Parser)]
+#[grammar = "day23/grammar.pest"]
+struct InstructionParser;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    A,
+    B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Half(Register),
+    Triple(Register),
+    Increment(Register),
+    Jump(isize),
+    JumpIfEven(Register, isize),
+    JumpIfOne(Register, isize),
+}
+
+pub fn parse_line(line: &str) -> Result<Instruction, String> {
+    fn next_register<R: pest::RuleType>(pairs: &mut pest::iterators::Pairs<R>) -> Register {
+        match pairs.next().unwrap().as_str() {
+            "a" => Register::A,
+            _ => Register::B,
+        }
+    }
+
+    fn next_offset<R: pest::RuleType>(pairs: &mut pest::iterators::Pairs<R>) -> isize {
+        isize::from_str(pairs.next().unwrap().as_str()).expect("Offset should fit in an isize")
+    }
+
+    let mut pairs = InstructionParser::parse(Rule::instruction, line.trim()).map_err(|e| format!("{}", e))?;
+    let pair = pairs.next().unwrap().into_inner().next().unwrap();
+    let rule = pair.as_rule();
+    let mut inner = pair.into_inner();
+
+    Ok(match rule {
+        Rule::hlf => Instruction::Half(next_register(&mut inner)),
+        Rule::tpl => Instruction::Triple(next_register(&mut inner)),
+        Rule::inc => Instruction::Increment(next_register(&mut inner)),
+        Rule::jmp => Instruction::Jump(next_offset(&mut inner)),
+        Rule::jie => {
+            let register = next_register(&mut inner);
+            Instruction::JumpIfEven(register, next_offset(&mut inner))
+        }
+        Rule::jio => {
+            let register = next_register(&mut inner);
+            Instruction::JumpIfOne(register, next_offset(&mut inner))
+        }
+        rule => unreachable!("{:?} isn't an instruction", rule),
+    })
+}
+
+#[test]
+fn test_parse_line() {
+    assert_eq!(parse_line("hlf b"), Ok(Instruction::Half(Register::B)));
+    assert_eq!(parse_line("jmp -7"), Ok(Instruction::Jump(-7)));
+    assert_eq!(parse_line("jio a, +19"), Ok(Instruction::JumpIfOne(Register::A, 19)));
+    assert!(parse_line("jmp 7").is_err());
+}
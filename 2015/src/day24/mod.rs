@@ -0,0 +1,108 @@
+pub fn run(input: &str) {
+    let weights = input.split_whitespace()
+        .map(|w| w.parse::<u64>().expect("Weights should be numbers"))
+        .collect::<Vec<_>>();
+
+    match best_entanglement(&weights, 3) {
+        Some(qe) => println!("With three groups the best quantum entanglement is {}", qe),
+        None => println!("The packages can't be split into three groups"),
+    }
+    match best_entanglement(&weights, 4) {
+        Some(qe) => println!("With four groups it's {}", qe),
+        None => println!("The packages can't be split into four groups"),
+    }
+}
+
+/// The smallest quantum entanglement of the fewest packages which can go in the passenger
+/// compartment, while the rest still split into equal groups.
+fn best_entanglement(weights: &[u64], groups: u64) -> Option<u64> {
+    let total = weights.iter().sum::<u64>();
+    if total % groups != 0 {
+        return None;
+    }
+    let target = total / groups;
+
+    for size in 1..weights.len() + 1 {
+        let mut best = None;
+        for chosen in subsets_of_size(weights.len(), size) {
+            if chosen.iter().map(|i| weights[*i]).sum::<u64>() != target {
+                continue;
+            }
+            let qe = chosen.iter().map(|i| weights[*i]).product::<u64>();
+            if best.is_none_or(|b| qe < b) {
+                let rest = (0..weights.len())
+                    .filter(|i| !chosen.contains(i))
+                    .map(|i| weights[i])
+                    .collect::<Vec<_>>();
+                if can_split(&rest, groups - 1, target) {
+                    best = Some(qe);
+                }
+            }
+        }
+        if best.is_some() {
+            return best;
+        }
+    }
+
+    None
+}
+
+/// Whether `weights` can be split into `groups` groups which each weigh `target`.
+fn can_split(weights: &[u64], groups: u64, target: u64) -> bool {
+    fn fill(weights: &[u64], loads: &mut Vec<u64>, target: u64) -> bool {
+        match weights.split_first() {
+            None => loads.iter().all(|l| *l == target),
+            Some((first, rest)) => {
+                for g in 0..loads.len() {
+                    // groups which are still empty are all alike, so only try the first
+                    if loads[g] + first > target || (loads[g] == 0 && loads[..g].contains(&0)) {
+                        continue;
+                    }
+                    loads[g] += *first;
+                    let done = fill(rest, loads, target);
+                    loads[g] -= *first;
+                    if done {
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    fill(weights, &mut vec![0; groups as usize], target)
+}
+
+/// Every way of picking `size` of the indices `0..n`, in increasing order.
+fn subsets_of_size(n: usize, size: usize) -> Vec<Vec<usize>> {
+    fn extend(start: usize, n: usize, size: usize, current: &mut Vec<usize>, result: &mut Vec<Vec<usize>>) {
+        if current.len() == size {
+            result.push(current.clone());
+            return;
+        }
+        for i in start..n {
+            current.push(i);
+            extend(i + 1, n, size, current, result);
+            current.pop();
+        }
+    }
+
+    let mut result = vec![];
+    extend(0, n, size, &mut vec![], &mut result);
+    result
+}
+
+#[test]
+fn test_examples() {
+    let weights = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+    assert_eq!(best_entanglement(&weights, 3), Some(99));
+    assert_eq!(best_entanglement(&weights, 4), Some(44));
+}
+
+#[test]
+fn test_subsets() {
+    assert_eq!(subsets_of_size(4, 2).len(), 6);
+    assert!(can_split(&[1, 2, 3, 4], 2, 5));
+    assert!(!can_split(&[1, 1, 4], 2, 3));
+}
@@ -0,0 +1,37 @@
+use aoc_common::numtheory::mod_pow;
+use regex::Regex;
+use std::str::FromStr;
+
+const FIRST_CODE: u64 = 20151125;
+const MULTIPLIER: u64 = 252533;
+const MODULUS: u64 = 33554393;
+
+pub fn run(input: &str) {
+    lazy_static! {
+        static ref POSITION: Regex = Regex::new(r"row (\d+), column (\d+)").unwrap();
+    }
+
+    let caps = POSITION.captures(input).expect("Input should give a row and column");
+    let row = u64::from_str(caps.get(1).unwrap().as_str()).unwrap();
+    let column = u64::from_str(caps.get(2).unwrap().as_str()).unwrap();
+
+    println!("The code for the machine is {}", code_at(row, column));
+}
+
+/// The code at a position in the manual's table, which is filled in along diagonals starting
+/// from the top left.
+fn code_at(row: u64, column: u64) -> u64 {
+    let diagonal = row + column - 1;
+    let position = diagonal * (diagonal - 1) / 2 + column;
+
+    FIRST_CODE * mod_pow(MULTIPLIER, position - 1, MODULUS) % MODULUS
+}
+
+#[test]
+fn test_examples() {
+    assert_eq!(code_at(1, 1), 20151125);
+    assert_eq!(code_at(2, 1), 31916031);
+    assert_eq!(code_at(1, 2), 18749137);
+    assert_eq!(code_at(6, 6), 27995004);
+    assert_eq!(code_at(4, 3), 21345942);
+}
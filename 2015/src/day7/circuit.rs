@@ -0,0 +1,283 @@
+use super::instruction::Instruction;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    /// A wire was read but nothing ever drives it.
+    Undefined(String),
+    /// More than one instruction drives the same wire.
+    DrivenTwice(String),
+    /// The wires depend on each other in a loop, starting and ending with the same wire.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CircuitError::Undefined(ref wire) => write!(f, "Wire {} has no signal", wire),
+            CircuitError::DrivenTwice(ref wire) => {
+                write!(f, "Wire {} has more than one signal", wire)
+            }
+            CircuitError::Cycle(ref wires) => {
+                write!(f, "Wires depend on each other: {}", wires.join(" -> "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Input {
+    Wire(String),
+    Value(u16),
+}
+
+impl From<&String> for Input {
+    fn from(operand: &String) -> Input {
+        match u16::from_str(operand) {
+            Ok(value) => Input::Value(value),
+            Err(_) => Input::Wire(operand.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Gate {
+    Connect(Input),
+    And(Input, Input),
+    Or(Input, Input),
+    LeftShift(Input, u32),
+    RightShift(Input, u32),
+    Not(Input),
+}
+
+impl Gate {
+    fn from_instruction(instruction: &Instruction) -> (String, Gate) {
+        match *instruction {
+            Instruction::Signal { value, ref target } => {
+                (target.clone(), Gate::Connect(Input::Value(value)))
+            }
+            Instruction::Connect { ref input, ref target } => {
+                (target.clone(), Gate::Connect(Input::from(input)))
+            }
+            Instruction::And { ref a, ref b, ref target } => {
+                (target.clone(), Gate::And(Input::from(a), Input::from(b)))
+            }
+            Instruction::Or { ref a, ref b, ref target } => {
+                (target.clone(), Gate::Or(Input::from(a), Input::from(b)))
+            }
+            Instruction::Shift { ref input, distance, ref target } => {
+                let gate = if distance < 0 {
+                    Gate::LeftShift(Input::from(input), distance.unsigned_abs())
+                } else {
+                    Gate::RightShift(Input::from(input), distance as u32)
+                };
+                (target.clone(), gate)
+            }
+            Instruction::Not { ref input, ref target } => {
+                (target.clone(), Gate::Not(Input::from(input)))
+            }
+        }
+    }
+
+    fn inputs(&self) -> Vec<&Input> {
+        match *self {
+            Gate::Connect(ref input) |
+            Gate::LeftShift(ref input, _) |
+            Gate::RightShift(ref input, _) |
+            Gate::Not(ref input) => vec![input],
+            Gate::And(ref a, ref b) |
+            Gate::Or(ref a, ref b) => vec![a, b],
+        }
+    }
+}
+
+/// The wires and gates, with signals worked out only when they're asked for and remembered
+/// until something they depend on changes.
+pub struct Circuit {
+    gates: HashMap<String, Gate>,
+    // for each wire, the wires whose gates read it
+    dependents: HashMap<String, Vec<String>>,
+    signals: HashMap<String, u16>,
+    evaluations: usize,
+}
+
+impl Circuit {
+    pub fn new(instructions: &[Instruction]) -> Result<Circuit, CircuitError> {
+        let mut circuit = Circuit {
+            gates: HashMap::new(),
+            dependents: HashMap::new(),
+            signals: HashMap::new(),
+            evaluations: 0,
+        };
+
+        for instruction in instructions {
+            let (wire, gate) = Gate::from_instruction(instruction);
+            if circuit.gates.contains_key(&wire) {
+                return Err(CircuitError::DrivenTwice(wire));
+            }
+            circuit.add_gate(wire, gate);
+        }
+
+        Ok(circuit)
+    }
+
+    /// The signal on `wire`.
+    pub fn signal(&mut self, wire: &str) -> Result<u16, CircuitError> {
+        let mut path = Vec::new();
+        self.evaluate(wire, &mut path)
+    }
+
+    /// Replaces whatever drives `wire` with a fixed signal. Only the wires which depend on it
+    /// will be worked out again.
+    pub fn override_wire(&mut self, wire: &str, value: u16) {
+        if let Some(gate) = self.gates.remove(wire) {
+            for input in gate.inputs() {
+                if let Input::Wire(ref name) = *input {
+                    if let Some(dependents) = self.dependents.get_mut(name) {
+                        dependents.retain(|d| d != wire);
+                    }
+                }
+            }
+        }
+        self.add_gate(wire.to_owned(), Gate::Connect(Input::Value(value)));
+        self.invalidate(wire);
+    }
+
+    /// How many gates have been worked out so far, counting each time one has to be redone.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn add_gate(&mut self, wire: String, gate: Gate) {
+        for input in gate.inputs() {
+            if let Input::Wire(ref name) = *input {
+                self.dependents.entry(name.clone()).or_default().push(wire.clone());
+            }
+        }
+        self.gates.insert(wire, gate);
+    }
+
+    fn invalidate(&mut self, wire: &str) {
+        let mut stack = vec![wire.to_owned()];
+        while let Some(wire) = stack.pop() {
+            if self.signals.remove(&wire).is_some() {
+                if let Some(dependents) = self.dependents.get(&wire) {
+                    stack.extend(dependents.iter().cloned());
+                }
+            }
+        }
+    }
+
+    // `path` holds the wires we're part way through working out, so finding one of them again
+    // means we've gone round a loop
+    fn evaluate(&mut self, wire: &str, path: &mut Vec<String>) -> Result<u16, CircuitError> {
+        if let Some(signal) = self.signals.get(wire) {
+            return Ok(*signal);
+        }
+
+        if let Some(start) = path.iter().position(|w| w == wire) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(wire.to_owned());
+            return Err(CircuitError::Cycle(cycle));
+        }
+
+        let gate = match self.gates.get(wire) {
+            Some(gate) => gate.clone(),
+            None => return Err(CircuitError::Undefined(wire.to_owned())),
+        };
+
+        path.push(wire.to_owned());
+        let signal = match gate {
+            Gate::Connect(ref input) => self.input(input, path)?,
+            Gate::And(ref a, ref b) => self.input(a, path)? & self.input(b, path)?,
+            Gate::Or(ref a, ref b) => self.input(a, path)? | self.input(b, path)?,
+            Gate::LeftShift(ref input, distance) => {
+                self.input(input, path)?.checked_shl(distance).unwrap_or(0)
+            }
+            Gate::RightShift(ref input, distance) => {
+                self.input(input, path)?.checked_shr(distance).unwrap_or(0)
+            }
+            Gate::Not(ref input) => !self.input(input, path)?,
+        };
+        path.pop();
+
+        self.evaluations += 1;
+        self.signals.insert(wire.to_owned(), signal);
+        Ok(signal)
+    }
+
+    fn input(&mut self, input: &Input, path: &mut Vec<String>) -> Result<u16, CircuitError> {
+        match *input {
+            Input::Value(value) => Ok(value),
+            Input::Wire(ref wire) => self.evaluate(wire, path),
+        }
+    }
+}
+
+#[cfg(test)]
+fn wire(target: &str, a: &str, b: &str) -> Instruction {
+    Instruction::And { a: a.to_owned(), b: b.to_owned(), target: target.to_owned() }
+}
+
+#[test]
+fn test_wires_defined_after_use() {
+    let instructions = vec![
+        wire("c", "a", "b"),
+        Instruction::Connect { input: "d".to_owned(), target: "a".to_owned() },
+        Instruction::Signal { value: 12, target: "b".to_owned() },
+        Instruction::Signal { value: 10, target: "d".to_owned() },
+        wire("e", "1", "c"),
+    ];
+    let mut circuit = Circuit::new(&instructions).unwrap();
+
+    assert_eq!(circuit.signal("c"), Ok(8));
+    assert_eq!(circuit.signal("e"), Ok(0));
+    assert_eq!(circuit.signal("z"), Err(CircuitError::Undefined("z".to_owned())));
+}
+
+#[test]
+fn test_errors() {
+    let instructions = vec![
+        wire("a", "b", "x"),
+        wire("b", "c", "x"),
+        wire("c", "a", "x"),
+        wire("d", "y", "1"),
+    ];
+    let mut circuit = Circuit::new(&instructions).unwrap();
+
+    assert_eq!(circuit.signal("b"),
+               Err(CircuitError::Cycle(vec!["b".to_owned(),
+                                            "c".to_owned(),
+                                            "a".to_owned(),
+                                            "b".to_owned()])));
+    assert_eq!(circuit.signal("d"), Err(CircuitError::Undefined("y".to_owned())));
+
+    let twice = vec![wire("a", "1", "1"), wire("a", "2", "2")];
+    assert_eq!(Circuit::new(&twice).err(), Some(CircuitError::DrivenTwice("a".to_owned())));
+}
+
+#[test]
+fn test_override_only_redoes_affected_wires() {
+    let instructions = vec![
+        Instruction::Signal { value: 3, target: "x".to_owned() },
+        Instruction::Signal { value: 5, target: "y".to_owned() },
+        wire("p", "x", "1"),
+        wire("q", "y", "y"),
+        Instruction::Or { a: "p".to_owned(), b: "q".to_owned(), target: "r".to_owned() },
+    ];
+    let mut circuit = Circuit::new(&instructions).unwrap();
+
+    assert_eq!(circuit.signal("r"), Ok(5));
+    assert_eq!(circuit.evaluations(), 5);
+
+    circuit.override_wire("x", 2);
+    assert_eq!(circuit.signal("r"), Ok(5));
+    assert_eq!(circuit.signal("p"), Ok(0));
+    assert_eq!(circuit.evaluations(), 8, "only x, p and r should be worked out again");
+
+    circuit.override_wire("p", 8);
+    assert_eq!(circuit.signal("r"), Ok(13));
+    assert_eq!(circuit.evaluations(), 10);
+}
@@ -1,6 +1,9 @@
+/// Operands other than shift distances may be wires or numbers; numbers are left as they were
+/// written for the circuit to make sense of.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Instruction {
     Signal { value: u16, target: String },
+    Connect { input: String, target: String },
     And {
         a: String,
         b: String,
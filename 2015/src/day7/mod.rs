@@ -1,8 +1,8 @@
 mod instruction;
 mod parser;
-mod evaluate;
+mod circuit;
 
-use self::evaluate::evaluate;
+use self::circuit::Circuit;
 use self::parser::parse_line;
 
 pub fn run() {
@@ -10,9 +10,15 @@ pub fn run() {
 
     let instructions = input.lines().map(|line| parse_line(line).unwrap()).collect::<Vec<_>>();
 
-    let final_states = evaluate(&instructions);
+    let mut circuit = Circuit::new(&instructions).unwrap_or_else(|e| panic!("{}", e));
 
-    println!("Wire a has value {}", final_states.get("a").unwrap());
+    let a = circuit.signal("a").unwrap_or_else(|e| panic!("{}", e));
+    let first_evaluations = circuit.evaluations();
+    println!("Wire a has value {}, after working out {} gates", a, first_evaluations);
+
+    circuit.override_wire("b", a);
+    let new_a = circuit.signal("a").unwrap_or_else(|e| panic!("{}", e));
+    println!("With b set to {}, wire a has value {}, after working out {} gates again", a, new_a, circuit.evaluations() - first_evaluations);
 }
 
 
@@ -29,14 +35,14 @@ NOT y -> i";
 
     let instructions = input.lines().map(|line| parse_line(line).unwrap()).collect::<Vec<_>>();
 
-    let states = evaluate(&instructions);
-
-    assert_eq!(states.get("d"), Some(&72));
-    assert_eq!(states.get("e"), Some(&507));
-    assert_eq!(states.get("f"), Some(&492));
-    assert_eq!(states.get("g"), Some(&114));
-    assert_eq!(states.get("h"), Some(&65412));
-    assert_eq!(states.get("i"), Some(&65079));
-    assert_eq!(states.get("x"), Some(&123));
-    assert_eq!(states.get("y"), Some(&456));
-}
\ No newline at end of file
+    let mut circuit = Circuit::new(&instructions).unwrap();
+
+    assert_eq!(circuit.signal("d"), Ok(72));
+    assert_eq!(circuit.signal("e"), Ok(507));
+    assert_eq!(circuit.signal("f"), Ok(492));
+    assert_eq!(circuit.signal("g"), Ok(114));
+    assert_eq!(circuit.signal("h"), Ok(65412));
+    assert_eq!(circuit.signal("i"), Ok(65079));
+    assert_eq!(circuit.signal("x"), Ok(123));
+    assert_eq!(circuit.signal("y"), Ok(456));
+}
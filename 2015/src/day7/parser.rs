@@ -11,15 +11,15 @@ const _GRAMMAR: &'static str = include_str!("grammar.pest");
 struct InstructionParser;
 
 pub fn parse_line(line: &str) -> Option<Instruction> {
-    fn next_as_string<R: pest::RuleType, I: pest::inputs::Input>(line: &mut pest::iterators::Pairs<R, I>) -> String {
+    fn next_as_string<R: pest::RuleType>(line: &mut pest::iterators::Pairs<R>) -> String {
         line.next().unwrap().as_str().trim().to_owned()
     }
 
-    fn next_as_u16<R: pest::RuleType, I: pest::inputs::Input>(line: &mut pest::iterators::Pairs<R, I>) -> u16 {
+    fn next_as_u16<R: pest::RuleType>(line: &mut pest::iterators::Pairs<R>) -> u16 {
         u16::from_str(line.next().unwrap().as_str().trim()).expect("Value should be a u16")
     }
 
-    let pairs = InstructionParser::parse_str(Rule::instruction, line)
+    let pairs = InstructionParser::parse(Rule::instruction, line)
         .unwrap_or_else(|e| panic!("{}", e));
 
     for pair in pairs {
@@ -34,6 +34,15 @@ pub fn parse_line(line: &str) -> Option<Instruction> {
                     let instruction = Instruction::Signal { value, target };
                     return Some(instruction)
                 }
+                Rule::connect => {
+                    let mut inner = inner_pair.into_inner();
+
+                    let input = next_as_string(&mut inner);
+                    let target = next_as_string(&mut inner);
+
+                    let instruction = Instruction::Connect { input, target };
+                    return Some(instruction)
+                }
                 Rule::and => {
                     let mut inner = inner_pair.into_inner();
 
@@ -104,7 +113,9 @@ x OR y -> e
 x LSHIFT 2 -> f
 y RSHIFT 2 -> g
 NOT x -> h
-NOT y -> i";
+NOT y -> i
+1 AND x -> j
+y -> k";
 
     let mut instructions = vec![];
 
@@ -120,7 +131,9 @@ NOT y -> i";
         Some(Instruction::Shift { input: "x".to_owned(), distance: -2, target: "f".to_owned() }),
         Some(Instruction::Shift { input: "y".to_owned(), distance: 2, target: "g".to_owned() }),
         Some(Instruction::Not { input: "x".to_owned(), target: "h".to_owned() }),
-        Some(Instruction::Not { input: "y".to_owned(), target: "i".to_owned() })
+        Some(Instruction::Not { input: "y".to_owned(), target: "i".to_owned() }),
+        Some(Instruction::And { a: "1".to_owned(), b: "x".to_owned(), target: "j".to_owned() }),
+        Some(Instruction::Connect { input: "y".to_owned(), target: "k".to_owned() })
     ];
 
     assert_eq!(instructions, expected);
@@ -0,0 +1,49 @@
+pub fn run(input: &str) {
+    let code = input.lines().map(|l| l.len()).sum::<usize>();
+    let memory = input.lines().map(decoded_length).sum::<usize>();
+    let encoded = input.lines().map(encoded_length).sum::<usize>();
+
+    println!("{} characters of code less {} in memory is {}", code, memory, code - memory);
+    println!("{} characters encoded less {} of code is {}", encoded, code, encoded - code);
+}
+
+/// How many characters the string literal `literal` holds once its escapes are worked out.
+fn decoded_length(literal: &str) -> usize {
+    let inner = &literal.as_bytes()[1..literal.len() - 1];
+    let mut count = 0;
+    let mut i = 0;
+
+    while i < inner.len() {
+        i += match (inner[i], inner.get(i + 1)) {
+            (b'\\', Some(&b'x')) => 4,
+            (b'\\', _) => 2,
+            _ => 1,
+        };
+        count += 1;
+    }
+
+    count
+}
+
+/// How long `literal` becomes when written as a string literal itself.
+fn encoded_length(literal: &str) -> usize {
+    let escaped = literal.chars().filter(|c| *c == '"' || *c == '\\').count();
+    literal.len() + escaped + 2
+}
+
+#[cfg(test)]
+const EXAMPLE: [&str; 4] = [r#""""#, r#""abc""#, r#""aaa\"aaa""#, r#""\x27""#];
+
+#[test]
+fn test_decoded_length() {
+    let lengths = EXAMPLE.iter().map(|l| (l.len(), decoded_length(l))).collect::<Vec<_>>();
+
+    assert_eq!(lengths, vec![(2, 0), (5, 3), (10, 7), (6, 1)]);
+}
+
+#[test]
+fn test_encoded_length() {
+    let lengths = EXAMPLE.iter().map(|l| encoded_length(l)).collect::<Vec<_>>();
+
+    assert_eq!(lengths, vec![6, 9, 16, 11]);
+}
@@ -0,0 +1,59 @@
+use permutations::permutations;
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+pub fn run(input: &str) {
+    let distances = parse_distances(input).expect("Distances should parse");
+
+    let (shortest, longest) = route_lengths(&distances);
+
+    println!("The shortest route is {}", shortest);
+    println!("The longest route is {}", longest);
+}
+
+type Distances = HashMap<(String, String), u32>;
+
+/// Reads lines like `London to Dublin = 464`, recording the distance both ways round.
+fn parse_distances(input: &str) -> Option<Distances> {
+    let mut distances = HashMap::new();
+
+    for line in input.lines() {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.len() != 5 || words[1] != "to" || words[3] != "=" {
+            return None;
+        }
+        let distance = u32::from_str(words[4]).ok()?;
+        distances.insert((words[0].to_owned(), words[2].to_owned()), distance);
+        distances.insert((words[2].to_owned(), words[0].to_owned()), distance);
+    }
+
+    Some(distances)
+}
+
+/// The lengths of the shortest and longest routes which visit every place exactly once.
+fn route_lengths(distances: &Distances) -> (u32, u32) {
+    let places = distances.keys().map(|(from, _)| from.clone()).collect::<BTreeSet<_>>();
+    let places = places.into_iter().collect::<Vec<_>>();
+
+    let lengths = permutations(&places)
+        .into_iter()
+        .filter_map(|route| {
+            route.windows(2)
+                .map(|leg| distances.get(&(leg[0].clone(), leg[1].clone())))
+                .sum::<Option<u32>>()
+        })
+        .collect::<Vec<_>>();
+
+    (*lengths.iter().min().unwrap_or(&0), *lengths.iter().max().unwrap_or(&0))
+}
+
+#[test]
+fn test_example() {
+    let input = "London to Dublin = 464
+London to Belfast = 518
+Dublin to Belfast = 141";
+
+    let distances = parse_distances(input).unwrap();
+
+    assert_eq!(route_lengths(&distances), (605, 982));
+}
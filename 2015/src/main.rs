@@ -6,6 +6,7 @@ extern crate regex;
 extern crate pest;
 #[macro_use]
 extern crate pest_derive;
+extern crate aoc_common;
 
 mod day1;
 mod day2;
@@ -14,11 +15,31 @@ mod day4;
 mod day5;
 mod day6;
 mod day7;
+mod day8;
+mod day9;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
+mod permutations;
 
+use aoc_common::input::read_input;
 use clap::{Arg, App};
 use std::str::FromStr;
 
-fn main() {
+fn main() -> Result<(), String> {
     let matches = App::new("Advent of Code 2015")
         .version("1.0")
         .author("Matthew Walton")
@@ -41,10 +62,35 @@ fn main() {
         5 => day5::run(),
         6 => day6::run(),
         7 => day7::run(),
+        8 => day8::run(&puzzle_input(day)?),
+        9 => day9::run(&puzzle_input(day)?),
+        10 => day10::run(&puzzle_input(day)?),
+        11 => day11::run(&puzzle_input(day)?),
+        12 => day12::run(&puzzle_input(day)?),
+        13 => day13::run(&puzzle_input(day)?),
+        14 => day14::run(&puzzle_input(day)?),
+        15 => day15::run(&puzzle_input(day)?),
+        16 => day16::run(&puzzle_input(day)?),
+        17 => day17::run(&puzzle_input(day)?),
+        18 => day18::run(&puzzle_input(day)?),
+        19 => day19::run(&puzzle_input(day)?),
+        20 => day20::run(&puzzle_input(day)?),
+        21 => day21::run(&puzzle_input(day)?),
+        22 => day22::run(&puzzle_input(day)?),
+        23 => day23::run(&puzzle_input(day)?),
+        24 => day24::run(&puzzle_input(day)?),
+        25 => day25::run(&puzzle_input(day)?),
         _ => panic!("Unknown day"),
     }
+
+    Ok(())
 }
 
 fn print_day_header(day: u32) {
     println!("Day {} coming right up...\n", day);
 }
+
+/// The puzzle input for `day`, from `src/dayN/input.txt`.
+fn puzzle_input(day: u8) -> Result<String, String> {
+    read_input(env!("CARGO_MANIFEST_DIR"), &format!("day{}", day))
+}
@@ -0,0 +1,34 @@
+/// Every ordering of `items`, using Heap's algorithm.
+pub fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut items = items.to_vec();
+    let mut result = vec![items.clone()];
+    let mut counters = vec![0; items.len()];
+
+    let mut i = 1;
+    while i < items.len() {
+        if counters[i] < i {
+            if i % 2 == 0 {
+                items.swap(0, i);
+            } else {
+                items.swap(counters[i], i);
+            }
+            result.push(items.clone());
+            counters[i] += 1;
+            i = 1;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+
+    result
+}
+
+#[test]
+fn test_permutations() {
+    let mut all = permutations(&[1, 2, 3]);
+    all.sort();
+    assert_eq!(all, vec![vec![1, 2, 3], vec![1, 3, 2], vec![2, 1, 3], vec![2, 3, 1], vec![3, 1, 2], vec![3, 2, 1]]);
+    assert_eq!(permutations(&[0; 6]).len(), 720);
+    assert_eq!(permutations::<u8>(&[]), vec![vec![]]);
+}
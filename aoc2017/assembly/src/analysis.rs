@@ -0,0 +1,494 @@
+//! Works out the shape of a program from its jumps, so that loops it's known to spend forever
+//! in can be replaced by what they calculate.
+
+use std::collections::{BTreeSet, HashMap};
+use {parse_program, Handler, Instruction, Machine, Parameter, Program, RegisterName, State};
+
+/// A run of instructions which is only ever entered at the top and left at the bottom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+    pub start: usize,
+    /// One past the last instruction in the block.
+    pub end: usize,
+    /// The blocks control can go to next, by number.
+    pub successors: Vec<usize>,
+    /// Whether control can leave the program from the end of this block.
+    pub exits: bool,
+}
+
+/// A natural loop: everything which can reach the `latch` block without going through the
+/// `header`, which the latch jumps back to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loop {
+    pub header: usize,
+    pub latch: usize,
+    pub blocks: BTreeSet<usize>,
+}
+
+impl Loop {
+    pub fn contains(&self, other: &Loop) -> bool {
+        other.blocks.is_subset(&self.blocks)
+    }
+}
+
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+}
+
+enum Flow {
+    Next,
+    Jump(isize),
+    Branch(isize),
+}
+
+fn flow(instruction: &Instruction) -> Option<Flow> {
+    let (taken, offset) = match *instruction {
+        Instruction::JumpNotZero(check, offset) => (
+            match check {
+                Parameter::Value(v) => Some(v != 0),
+                Parameter::Register(_) => None,
+            },
+            offset,
+        ),
+        Instruction::JumpGreaterThanZero(check, offset) => (
+            match check {
+                Parameter::Value(v) => Some(v > 0),
+                Parameter::Register(_) => None,
+            },
+            offset,
+        ),
+        _ => return Some(Flow::Next),
+    };
+
+    // a jump by a register's value could go anywhere
+    let offset = match offset {
+        Parameter::Value(v) => v as isize,
+        Parameter::Register(_) => return None,
+    };
+
+    Some(match taken {
+        Some(true) => Flow::Jump(offset),
+        Some(false) => Flow::Next,
+        None => Flow::Branch(offset),
+    })
+}
+
+impl ControlFlowGraph {
+    /// Splits the program into basic blocks, or gives up if any jump's distance comes from a
+    /// register.
+    pub fn build(program: &Program) -> Option<ControlFlowGraph> {
+        let instructions = &program.0;
+        let len = instructions.len() as isize;
+        let flows = instructions.iter().map(flow).collect::<Option<Vec<_>>>()?;
+
+        let mut leaders = BTreeSet::new();
+        leaders.insert(0);
+        for (i, f) in flows.iter().enumerate() {
+            match *f {
+                Flow::Jump(offset) | Flow::Branch(offset) => {
+                    let target = i as isize + offset;
+                    if target >= 0 && target < len {
+                        leaders.insert(target as usize);
+                    }
+                    leaders.insert(i + 1);
+                }
+                Flow::Next => {}
+            }
+        }
+        leaders.retain(|l| *l < instructions.len());
+
+        let starts = leaders.into_iter().collect::<Vec<_>>();
+        let block_at = starts
+            .iter()
+            .enumerate()
+            .map(|(b, s)| (*s as isize, b))
+            .collect::<HashMap<_, _>>();
+
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(b, &start)| {
+                let end = starts.get(b + 1).cloned().unwrap_or(instructions.len());
+                let last = end - 1;
+                let targets = match flows[last] {
+                    Flow::Next => vec![end as isize],
+                    Flow::Jump(offset) => vec![last as isize + offset],
+                    Flow::Branch(offset) => vec![last as isize + offset, end as isize],
+                };
+                let mut successors = targets
+                    .iter()
+                    .filter_map(|t| block_at.get(t).cloned())
+                    .collect::<Vec<_>>();
+                successors.dedup();
+                BasicBlock {
+                    start,
+                    end,
+                    exits: targets.iter().any(|t| *t < 0 || *t >= len),
+                    successors,
+                }
+            })
+            .collect();
+
+        Some(ControlFlowGraph { blocks })
+    }
+
+    /// For each block, the blocks which every path from the start of the program to it goes
+    /// through. Blocks which can't be reached have none.
+    fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let count = self.blocks.len();
+        let mut predecessors = vec![vec![]; count];
+        for (b, block) in self.blocks.iter().enumerate() {
+            for s in &block.successors {
+                predecessors[*s].push(b);
+            }
+        }
+
+        let mut reachable = vec![false; count];
+        let mut stack = vec![0];
+        while let Some(b) = stack.pop() {
+            if !reachable[b] {
+                reachable[b] = true;
+                stack.extend(self.blocks[b].successors.iter().cloned());
+            }
+        }
+
+        let everything = (0..count)
+            .filter(|b| reachable[*b])
+            .collect::<BTreeSet<_>>();
+        let mut dominators = (0..count)
+            .map(|b| {
+                if b == 0 {
+                    Some(0).into_iter().collect()
+                } else if reachable[b] {
+                    everything.clone()
+                } else {
+                    BTreeSet::new()
+                }
+            })
+            .collect::<Vec<BTreeSet<usize>>>();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for b in 1..count {
+                if !reachable[b] {
+                    continue;
+                }
+                let mut new = predecessors[b]
+                    .iter()
+                    .filter(|p| reachable[**p])
+                    .map(|p| dominators[*p].clone())
+                    .fold(None, |acc: Option<BTreeSet<usize>>, d| match acc {
+                        None => Some(d),
+                        Some(acc) => Some(acc.intersection(&d).cloned().collect()),
+                    })
+                    .unwrap_or_default();
+                new.insert(b);
+                if new != dominators[b] {
+                    dominators[b] = new;
+                    changed = true;
+                }
+            }
+        }
+
+        dominators
+    }
+
+    /// Every natural loop, one for each jump back to a block which dominates the jump.
+    pub fn loops(&self) -> Vec<Loop> {
+        let dominators = self.dominators();
+        let mut loops = vec![];
+
+        for (latch, block) in self.blocks.iter().enumerate() {
+            for &header in &block.successors {
+                if !dominators[latch].contains(&header) {
+                    continue;
+                }
+
+                let mut blocks = BTreeSet::new();
+                blocks.insert(header);
+                let mut stack = vec![latch];
+                while let Some(b) = stack.pop() {
+                    if blocks.insert(b) {
+                        stack.extend(
+                            (0..self.blocks.len())
+                                .filter(|p| self.blocks[*p].successors.contains(&b)),
+                        );
+                    }
+                }
+                loops.push(Loop {
+                    header,
+                    latch,
+                    blocks,
+                });
+            }
+        }
+
+        loops
+    }
+}
+
+/// Counting how many of `start, start + step, ..., end` aren't prime, the slow way: testing
+/// every pair of factors with two nested loops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeCount {
+    /// Where the outer loop starts. The registers hold the bounds once control first gets here.
+    pub header: usize,
+    pub candidate: RegisterName,
+    pub limit: RegisterName,
+    pub counter: RegisterName,
+    pub step: i64,
+}
+
+/// The loops as they appear in day 23's programs. Capital letters stand for whichever register
+/// or value the program uses in their place, which has to be the same everywhere they appear.
+const COMPOSITE_COUNT: &str = "set F 1
+set D 2
+set E 2
+set G D
+mul G E
+sub G B
+jnz G 2
+set F 0
+sub E -1
+set G E
+sub G B
+jnz G -8
+sub D -1
+set G D
+sub G B
+jnz G -13
+jnz F 2
+sub H -1
+set G B
+sub G C
+jnz G 2
+jnz 1 3
+sub B N
+jnz 1 -23";
+
+struct Bindings(HashMap<RegisterName, Parameter>);
+
+impl Bindings {
+    fn bind(&mut self, pattern: Parameter, actual: Parameter) -> bool {
+        match pattern {
+            Parameter::Register(p) if p.is_ascii_uppercase() => {
+                // two placeholders can't both be the same register
+                let clash = self
+                    .0
+                    .iter()
+                    .any(|(other, bound)| *other != p && *bound == actual);
+                if clash {
+                    return false;
+                }
+                *self.0.entry(p).or_insert(actual) == actual
+            }
+            _ => pattern == actual,
+        }
+    }
+
+    fn bind_register(&mut self, pattern: RegisterName, actual: RegisterName) -> bool {
+        self.bind(Parameter::Register(pattern), Parameter::Register(actual))
+    }
+
+    fn matches(&mut self, pattern: &Instruction, actual: &Instruction) -> bool {
+        use Instruction::*;
+        match (*pattern, *actual) {
+            (Set(pr, pp), Set(ar, ap))
+            | (Add(pr, pp), Add(ar, ap))
+            | (Sub(pr, pp), Sub(ar, ap))
+            | (Multiply(pr, pp), Multiply(ar, ap))
+            | (Modulo(pr, pp), Modulo(ar, ap)) => self.bind_register(pr, ar) && self.bind(pp, ap),
+            (Sound(p), Sound(a)) => self.bind(p, a),
+            (RecoverWhenNotZero(p), RecoverWhenNotZero(a)) => self.bind_register(p, a),
+            (JumpNotZero(pc, po), JumpNotZero(ac, ao))
+            | (JumpGreaterThanZero(pc, po), JumpGreaterThanZero(ac, ao)) => {
+                self.bind(pc, ac) && self.bind(po, ao)
+            }
+            _ => false,
+        }
+    }
+
+    fn register(&self, placeholder: RegisterName) -> Option<RegisterName> {
+        match self.0.get(&placeholder) {
+            Some(Parameter::Register(r)) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+impl CompositeCount {
+    /// Looks for three nested loops which match the idiom exactly.
+    pub fn find(program: &Program) -> Option<CompositeCount> {
+        let cfg = ControlFlowGraph::build(program)?;
+        let loops = cfg.loops();
+
+        let outer = loops.iter().find(|outer| {
+            loops.iter().any(|middle| {
+                middle != *outer
+                    && outer.contains(middle)
+                    && loops
+                        .iter()
+                        .any(|inner| inner != middle && middle.contains(inner))
+            })
+        })?;
+
+        let start = cfg.blocks[outer.header].start;
+        let end = outer.blocks.iter().map(|b| cfg.blocks[*b].end).max()?;
+        let pattern = parse_program(COMPOSITE_COUNT).expect("Pattern should parse");
+        if end - start != pattern.0.len() {
+            return None;
+        }
+
+        let mut bindings = Bindings(HashMap::new());
+        let matched = pattern
+            .0
+            .iter()
+            .zip(program.0[start..end].iter())
+            .all(|(p, a)| bindings.matches(p, a));
+        if !matched {
+            return None;
+        }
+
+        let step = match bindings.0.get(&'N') {
+            Some(Parameter::Value(v)) if *v < 0 => -v,
+            _ => return None,
+        };
+
+        Some(CompositeCount {
+            header: start,
+            candidate: bindings.register('B')?,
+            limit: bindings.register('C')?,
+            counter: bindings.register('H')?,
+            step,
+        })
+    }
+
+    /// Runs the program up to the loops, then works out what they'd leave in the counter. Gives
+    /// up if it doesn't get there quickly, or if the loops would never finish.
+    pub fn evaluate<H: Handler>(&self, program: &Program, handler: H) -> Option<i64> {
+        let mut machine = Machine::new(program, handler);
+        let mut steps = 0;
+        while machine.instruction_pointer() != self.header as isize {
+            steps += 1;
+            if steps > 10_000 || machine.step() != State::Running {
+                return None;
+            }
+        }
+
+        let start = machine.register(self.candidate);
+        let end = machine.register(self.limit);
+        if start < 2 || end < start || (end - start) % self.step != 0 {
+            return None;
+        }
+
+        let composites = (0..=(end - start) / self.step)
+            .map(|i| start + i * self.step)
+            .filter(|n| !is_prime(*n))
+            .count();
+        Some(machine.register(self.counter) + composites as i64)
+    }
+}
+
+fn is_prime(n: i64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {run_program, Coprocessor};
+
+    const DAY_23: &str = "set b 79
+set c b
+jnz a 2
+jnz 1 5
+mul b 100
+sub b -100000
+set c b
+sub c -17000
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+jnz f 2
+sub h -1
+set g b
+sub g c
+jnz g 2
+jnz 1 3
+sub b -17
+jnz 1 -23";
+
+    #[test]
+    fn test_blocks_and_loops() {
+        let program = parse_program(DAY_23).unwrap();
+        let cfg = ControlFlowGraph::build(&program).unwrap();
+
+        let starts = cfg.blocks.iter().map(|b| b.start).collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            vec![0, 3, 4, 8, 10, 11, 15, 16, 20, 24, 25, 26, 29, 30]
+        );
+        assert!(cfg.blocks[12].exits);
+
+        let mut loops = cfg
+            .loops()
+            .iter()
+            .map(|l| (cfg.blocks[l.header].start, l.blocks.len()))
+            .collect::<Vec<_>>();
+        loops.sort();
+        assert_eq!(loops, vec![(8, 10), (10, 5), (11, 3)]);
+    }
+
+    #[test]
+    fn test_finds_idiom() {
+        let program = parse_program(DAY_23).unwrap();
+        assert_eq!(
+            CompositeCount::find(&program),
+            Some(CompositeCount {
+                header: 8,
+                candidate: 'b',
+                limit: 'c',
+                counter: 'h',
+                step: 17,
+            })
+        );
+
+        let sample = parse_program("snd 1\nsnd 2\nsnd p\nrcv a\nrcv b\nrcv c\nrcv d\n").unwrap();
+        assert_eq!(CompositeCount::find(&sample), None);
+    }
+
+    #[test]
+    fn test_shortcut_matches_interpreter() {
+        // different registers and a small enough range to interpret: 10, 27 and 44 are all
+        // composite
+        let program = parse_program(
+            &DAY_23
+                .replace("set b 79", "set b 10")
+                .replace("mul b 100\n", "mul b 1\n")
+                .replace("sub b -100000", "sub b 0")
+                .replace("sub c -17000", "sub c -34")
+                .replace(" g", " x"),
+        )
+        .unwrap();
+
+        let idiom = CompositeCount::find(&program).unwrap();
+        assert_eq!(idiom.candidate, 'b');
+        let handler = Coprocessor { debug: false };
+        assert_eq!(idiom.evaluate(&program, handler.clone()), Some(3));
+        assert_eq!(run_program(&program, handler).register('h'), 3);
+    }
+}
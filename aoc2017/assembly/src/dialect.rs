@@ -0,0 +1,115 @@
+//! The instructions whose meaning changes between puzzles. The machine asks a `Handler` what
+//! to do about them, so a new dialect only needs a new handler.
+
+use std::collections::VecDeque;
+use RegisterName;
+
+/// What a `rcv` instruction does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Receive {
+    /// Nothing, and carry on.
+    Ignore,
+    /// Put this value in the register and carry on.
+    Store(i64),
+    /// Don't run the instruction yet, because there's nothing to receive.
+    Wait,
+    /// Stop the program, with this as its answer.
+    Stop(i64),
+}
+
+pub trait Handler {
+    /// Registers which start with something other than zero.
+    fn initial_registers(&self) -> Vec<(RegisterName, i64)> {
+        Vec::new()
+    }
+
+    /// Called for `snd` with the value of its parameter.
+    fn sound(&mut self, _value: i64) {}
+
+    /// Called for `rcv` with the current value of its register.
+    fn receive(&mut self, _current: i64) -> Receive {
+        Receive::Ignore
+    }
+}
+
+/// Day 18 part one, where `snd` plays a sound and `rcv` recovers the last one played as long as
+/// its register isn't zero.
+#[derive(Debug, Clone, Default)]
+pub struct SoundCard {
+    last_sound: i64,
+}
+
+impl Handler for SoundCard {
+    fn sound(&mut self, value: i64) {
+        self.last_sound = value;
+    }
+
+    fn receive(&mut self, current: i64) -> Receive {
+        if current != 0 {
+            Receive::Stop(self.last_sound)
+        } else {
+            Receive::Ignore
+        }
+    }
+}
+
+/// Day 18 part two, where `snd` sends a value to another program and `rcv` waits for one. The
+/// program's `p` register holds its id.
+#[derive(Debug, Clone, Default)]
+pub struct Duet {
+    id: i64,
+    inbox: VecDeque<i64>,
+    outbox: Vec<i64>,
+}
+
+impl Duet {
+    pub fn new(id: i64) -> Duet {
+        Duet {
+            id,
+            ..Duet::default()
+        }
+    }
+
+    /// Queues values for the program to receive.
+    pub fn deliver<I: IntoIterator<Item = i64>>(&mut self, values: I) {
+        self.inbox.extend(values);
+    }
+
+    /// The values sent since the last time this was called.
+    pub fn take_output(&mut self) -> Vec<i64> {
+        self.outbox.drain(..).collect()
+    }
+}
+
+impl Handler for Duet {
+    fn initial_registers(&self) -> Vec<(RegisterName, i64)> {
+        vec![('p', self.id)]
+    }
+
+    fn sound(&mut self, value: i64) {
+        self.outbox.push(value);
+    }
+
+    fn receive(&mut self, _current: i64) -> Receive {
+        match self.inbox.pop_front() {
+            Some(value) => Receive::Store(value),
+            None => Receive::Wait,
+        }
+    }
+}
+
+/// Day 23, which has no `snd` or `rcv`. Turning off debug mode sets `a` to 1.
+#[derive(Debug, Clone)]
+pub struct Coprocessor {
+    pub debug: bool,
+}
+
+impl Handler for Coprocessor {
+    fn initial_registers(&self) -> Vec<(RegisterName, i64)> {
+        if self.debug {
+            vec![]
+        } else {
+            vec![('a', 1)]
+        }
+    }
+}
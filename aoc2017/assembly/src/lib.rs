@@ -1,181 +1,270 @@
-use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::iter::Sum;
 use std::str::FromStr;
-use std::collections::HashMap;
-use std::time::Duration;
-use std::thread;
-use std::io;
-use std::io::BufRead;
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum RunMode {
-    Day18PartOne,
-    Day18PartTwo,
-    Day23PartOne,
-    Day23PartTwo,
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+pub mod analysis;
+pub mod dialect;
+mod scheduler;
+mod stepper;
+
+pub use dialect::{Coprocessor, Duet, Handler, Receive, SoundCard};
+pub use scheduler::{
+    run_together, run_together_with_limit, DuetReport, Outcome, ProgramStats, TURN_LIMIT,
+};
+pub use stepper::{step_through, Stepper};
+
+/// The value of the recovered frequency the first time `rcv` runs with a non-zero register.
+pub fn run_for_day_18_part1(source: &str) -> Result<i64, ()> {
+    let program = parse_program(source)?;
+    match run_program(&program, SoundCard::default()).state {
+        State::Stopped(frequency) => Ok(frequency),
+        _ => Err(()),
+    }
 }
 
 pub fn run_for_day_23_part_one(source: &str) -> Result<usize, ()> {
     let program = parse_program(source)?;
-    let (tx, rx) = channel();
-    run_program(&program, RunMode::Day23PartOne, rx, tx, 0, false).map(|x| x as usize)
+    let run = run_program(&program, Coprocessor { debug: true });
+    Ok(run.stats.count("mul"))
 }
 
+/// Takes a shortcut through the loops if they're the ones we know about, and otherwise runs
+/// the program as it is, which could take a very long time.
 pub fn run_for_day_23_part_two(source: &str) -> Result<i64, ()> {
     let program = parse_program(source)?;
-    let (tx, rx) = channel();
-    run_program(&program, RunMode::Day23PartTwo, rx, tx, 0, true)
+    let handler = Coprocessor { debug: false };
+    let shortcut = analysis::CompositeCount::find(&program)
+        .and_then(|idiom| idiom.evaluate(&program, handler.clone()));
+    match shortcut {
+        Some(h) => Ok(h),
+        None => Ok(run_program(&program, handler).register('h')),
+    }
 }
 
+/// How many values program 1 sends before both programs are either finished or stuck waiting
+/// for each other. Fails if either of them gets stuck in a loop instead.
 pub fn run_for_day_18_part2(source: &str) -> Result<i64, ()> {
     let program = parse_program(source)?;
-    let program1 = program.clone();
-    let program2 = program.clone();
-    let (tx0, rx1) = channel();
-    let (tx1, rx0) = channel();
-
-    let prog0 = thread::spawn(move || {
-        run_program(&program1, RunMode::Day18PartTwo, rx0, tx0, 0, false)
-    });
-    let prog1 = thread::spawn(move || {
-        run_program(&program2, RunMode::Day18PartTwo, rx1, tx1, 1, false)
-    });
-
-    let _ = prog0.join();
-    let prog1result = prog1.join();
-
-    match prog1result {
-        Ok(r) => r,
-        Err(_) => Err(()),
+    let report = run_together(&program, 2);
+    match report.outcome {
+        Outcome::Livelocked(_) => Err(()),
+        _ => Ok(report.stats[1].sent as i64),
     }
 }
 
-fn read_enter() {
-    let mut buffer = String::new();
-    let stdin = io::stdin();
-    let mut handle = stdin.lock();
-    let _ = handle.read_line(&mut buffer);
+/// Runs a program on its own until it halts, waits or is stopped by its handler.
+pub fn run_program<H: Handler>(program: &Program, handler: H) -> Run<H> {
+    let mut machine = Machine::new(program, handler);
+    let state = machine.run();
+    machine.finish(state)
+}
+
+/// Where a program got to when it stopped running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    /// It can carry on.
+    Running,
+    /// It needs a value and nothing has been sent to it.
+    Waiting,
+    /// It jumped outside itself.
+    Halted,
+    /// Its handler stopped it with an answer.
+    Stopped(i64),
+}
+
+/// How many of each kind of instruction a program has run, by mnemonic.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    counts: BTreeMap<&'static str, usize>,
 }
 
-pub fn run_program(
-    program: &Program,
-    mode: RunMode,
-    rx: Receiver<i64>,
-    tx: Sender<i64>,
-    prog_id: i64,
-    step_mode: bool,
-) -> Result<i64, ()> {
-    let mut instructionp: isize = 0;
-    let mut registers = HashMap::new();
-    let mut last_sound = 0;
-    let mut did_jump = false;
-
-    if mode == RunMode::Day18PartTwo {
-        set_register_value('p', prog_id, &mut registers);
+impl Stats {
+    pub fn count(&self, mnemonic: &str) -> usize {
+        self.counts.get(mnemonic).cloned().unwrap_or(0)
     }
 
-    if mode == RunMode::Day23PartTwo {
-        set_register_value('a', 1, &mut registers);
+    pub fn counts(&self) -> &BTreeMap<&'static str, usize> {
+        &self.counts
     }
 
-    let mut send_counter = 0;
-    let mut multiply_counter = 0;
+    /// How many instructions have run altogether.
+    pub fn executed(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
 
-    while instructionp >= 0 && instructionp < program.0.len() as isize {
-        if step_mode {
-            println!("Registers: {:?}", registers);
-            println!(
-                "Execute [{}] {:?}?",
-                instructionp,
-                program.0[instructionp as usize]
-            );
-            //read_enter();
+/// Everything left behind by a finished run.
+#[derive(Debug, Clone)]
+pub struct Run<H> {
+    pub state: State,
+    pub registers: HashMap<RegisterName, i64>,
+    pub stats: Stats,
+    pub handler: H,
+}
+
+impl<H> Run<H> {
+    pub fn register(&self, register: RegisterName) -> i64 {
+        get_register_value(register, &self.registers)
+    }
+}
+
+/// One program part way through running, with its own registers. The handler decides what
+/// `snd` and `rcv` mean.
+pub struct Machine<'a, H> {
+    program: &'a Program,
+    handler: H,
+    instructionp: isize,
+    registers: HashMap<RegisterName, i64>,
+    stats: Stats,
+}
+
+impl<'a, H: Handler> Machine<'a, H> {
+    pub fn new(program: &'a Program, handler: H) -> Machine<'a, H> {
+        let mut registers = HashMap::new();
+        for (register, value) in handler.initial_registers() {
+            set_register_value(register, value, &mut registers);
+        }
+
+        Machine {
+            program,
+            handler,
+            instructionp: 0,
+            registers,
+            stats: Stats::default(),
         }
-        match program.0[instructionp as usize] {
-            Instruction::Set(target, value) => set_register_value(
-                target,
-                get_parameter_value(value, &registers),
-                &mut registers,
-            ),
+    }
+
+    pub fn instruction_pointer(&self) -> isize {
+        self.instructionp
+    }
+
+    pub fn register(&self, register: RegisterName) -> i64 {
+        get_register_value(register, &self.registers)
+    }
+
+    pub fn set_register(&mut self, register: RegisterName, value: i64) {
+        set_register_value(register, value, &mut self.registers);
+    }
+
+    /// The registers which have been used, in order.
+    pub fn registers(&self) -> BTreeMap<RegisterName, i64> {
+        self.registers.iter().map(|(r, v)| (*r, *v)).collect()
+    }
+
+    /// The instruction which runs next, as it would be written in the program.
+    pub fn next_instruction(&self) -> Option<String> {
+        if self.instructionp < 0 {
+            return None;
+        }
+        self.program
+            .0
+            .get(self.instructionp as usize)
+            .map(|i| i.to_string())
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    pub fn finish(self, state: State) -> Run<H> {
+        Run {
+            state,
+            registers: self.registers,
+            stats: self.stats,
+            handler: self.handler,
+        }
+    }
+
+    /// Runs until the program halts, waits or is stopped.
+    pub fn run(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Running => {}
+                state => return state,
+            }
+        }
+    }
+
+    /// Runs one instruction, unless the program can't go any further.
+    pub fn step(&mut self) -> State {
+        if self.instructionp < 0 || self.instructionp >= self.program.0.len() as isize {
+            return State::Halted;
+        }
+
+        let instruction = self.program.0[self.instructionp as usize];
+
+        let registers = &mut self.registers;
+        let mut did_jump = false;
+
+        match instruction {
+            Instruction::Set(target, value) => {
+                let value = get_parameter_value(value, registers);
+                set_register_value(target, value, registers)
+            }
             Instruction::Add(target, value) => {
                 let new_value =
-                    get_register_value(target, &registers) + get_parameter_value(value, &registers);
-                set_register_value(target, new_value, &mut registers);
+                    get_register_value(target, registers) + get_parameter_value(value, registers);
+                set_register_value(target, new_value, registers);
             }
             Instruction::Sub(target, value) => {
                 let new_value =
-                    get_register_value(target, &registers) - get_parameter_value(value, &registers);
-                set_register_value(target, new_value, &mut registers);
+                    get_register_value(target, registers) - get_parameter_value(value, registers);
+                set_register_value(target, new_value, registers);
             }
             Instruction::Multiply(target, value) => {
                 let new_value =
-                    get_register_value(target, &registers) * get_parameter_value(value, &registers);
-                set_register_value(target, new_value, &mut registers);
-                multiply_counter += 1;
+                    get_register_value(target, registers) * get_parameter_value(value, registers);
+                set_register_value(target, new_value, registers);
             }
             Instruction::Modulo(target, value) => {
                 let new_value =
-                    get_register_value(target, &registers) % get_parameter_value(value, &registers);
-                set_register_value(target, new_value, &mut registers);
+                    get_register_value(target, registers) % get_parameter_value(value, registers);
+                set_register_value(target, new_value, registers);
             }
-            Instruction::Sound(frequency) => match mode {
-                RunMode::Day18PartOne => {
-                    last_sound = get_parameter_value(frequency, &registers);
-                }
-                RunMode::Day18PartTwo => {
-                    tx.send(get_parameter_value(frequency, &registers))
-                        .map_err(|_e| {
-                            #[cfg(test)]
-                            println!("Send error in {} {}", prog_id, _e);
-                            ()
-                        })?;
-                    send_counter += 1;
+            Instruction::Sound(frequency) => {
+                self.handler.sound(get_parameter_value(frequency, registers));
+            }
+            Instruction::RecoverWhenNotZero(register) => {
+                match self.handler.receive(get_register_value(register, registers)) {
+                    Receive::Ignore => {}
+                    Receive::Store(value) => set_register_value(register, value, registers),
+                    // try this instruction again once something arrives
+                    Receive::Wait => return State::Waiting,
+                    Receive::Stop(value) => {
+                        *self.stats.counts.entry(instruction.mnemonic()).or_insert(0) += 1;
+                        return State::Stopped(value);
+                    }
                 }
-                _ => {}
-            },
-            Instruction::RecoverWhenNotZero(register) => match mode {
-                RunMode::Day18PartOne => if get_register_value(register, &registers) != 0 {
-                    set_register_value(register, last_sound, &mut registers);
-                    return Ok(last_sound);
-                },
-                RunMode::Day18PartTwo => match rx.recv_timeout(Duration::from_secs(3)) {
-                    Ok(val) => set_register_value(register, val, &mut registers),
-                    Err(e) => match e {
-                        RecvTimeoutError::Timeout => {
-                            println!("Program {} timeout", prog_id);
-                            return Ok(send_counter);
-                        }
-                        _ => return Err(()),
-                    },
-                },
-                _ => {}
-            },
+            }
             Instruction::JumpGreaterThanZero(check, offset) => {
-                if get_parameter_value(check, &registers) > 0 {
-                    instructionp += get_parameter_value(offset, &registers) as isize;
+                if get_parameter_value(check, registers) > 0 {
+                    self.instructionp += get_parameter_value(offset, registers) as isize;
                     did_jump = true;
                 }
             }
             Instruction::JumpNotZero(check, offset) => {
-                if get_parameter_value(check, &registers) != 0 {
-                    instructionp += get_parameter_value(offset, &registers) as isize;
+                if get_parameter_value(check, registers) != 0 {
+                    self.instructionp += get_parameter_value(offset, registers) as isize;
                     did_jump = true;
                 }
             }
         }
 
         if !did_jump {
-            instructionp += 1;
+            self.instructionp += 1;
         }
 
-        did_jump = false;
-    }
-
-    match mode {
-        RunMode::Day18PartOne => Err(()),
-        RunMode::Day18PartTwo => Ok(send_counter),
-        RunMode::Day23PartOne => Ok(multiply_counter),
-        RunMode::Day23PartTwo => Ok(get_register_value('h', &registers)),
+        *self.stats.counts.entry(instruction.mnemonic()).or_insert(0) += 1;
+        State::Running
     }
 }
 
@@ -204,7 +293,7 @@ enum Parameter {
     Value(i64),
 }
 
-type RegisterName = char;
+pub type RegisterName = char;
 
 #[derive(Clone, Debug, PartialEq, Copy)]
 enum Instruction {
@@ -219,6 +308,49 @@ enum Instruction {
     Sub(RegisterName, Parameter),
 }
 
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Parameter::Register(r) => write!(f, "{}", r),
+            Parameter::Value(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic())?;
+        match *self {
+            Instruction::Set(r, p)
+            | Instruction::Add(r, p)
+            | Instruction::Sub(r, p)
+            | Instruction::Multiply(r, p)
+            | Instruction::Modulo(r, p) => write!(f, " {} {}", r, p),
+            Instruction::Sound(p) => write!(f, " {}", p),
+            Instruction::RecoverWhenNotZero(r) => write!(f, " {}", r),
+            Instruction::JumpGreaterThanZero(c, o) | Instruction::JumpNotZero(c, o) => {
+                write!(f, " {} {}", c, o)
+            }
+        }
+    }
+}
+
+impl Instruction {
+    fn mnemonic(&self) -> &'static str {
+        match *self {
+            Instruction::Set(..) => "set",
+            Instruction::Sound(..) => "snd",
+            Instruction::Add(..) => "add",
+            Instruction::Multiply(..) => "mul",
+            Instruction::Modulo(..) => "mod",
+            Instruction::RecoverWhenNotZero(..) => "rcv",
+            Instruction::JumpGreaterThanZero(..) => "jgz",
+            Instruction::JumpNotZero(..) => "jnz",
+            Instruction::Sub(..) => "sub",
+        }
+    }
+}
+
 fn parse_register(s: &str) -> Result<RegisterName, ()> {
     char::from_str(s).map_err(|_| ())
 }
@@ -233,7 +365,7 @@ fn parse_parameter(s: &str) -> Result<Parameter, ()> {
     }
 }
 
-fn parse_program(code: &str) -> Result<Program, ()> {
+pub fn parse_program(code: &str) -> Result<Program, ()> {
     code.lines()
         .map(|line| line.trim())
         .filter(|line| line.len() > 0)
@@ -380,11 +512,12 @@ jgz a -2
 ";
             let program = parse_program(code);
             assert!(program.is_ok());
-            let (tx, rx) = channel();
-            assert_eq!(
-                run_program(&program.unwrap(), RunMode::Day18PartOne, rx, tx, 0),
-                Ok(4)
-            );
+            let run = run_program(&program.unwrap(), SoundCard::default());
+            assert_eq!(run.state, State::Stopped(4));
+            assert_eq!(run.register('a'), 1);
+            assert_eq!(run.stats.count("rcv"), 2);
+            assert_eq!(run.stats.count("jgz"), 3);
+            assert_eq!(run.stats.executed(), 12);
         }
 
         #[test]
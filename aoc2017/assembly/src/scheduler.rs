@@ -0,0 +1,150 @@
+use {Duet, Machine, Program, State, Stats};
+
+/// What one program did while running alongside the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramStats {
+    pub id: i64,
+    pub sent: usize,
+    pub received: usize,
+    pub state: State,
+    /// Every instruction it ran, by kind.
+    pub instructions: Stats,
+}
+
+/// Why the programs stopped running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// Every program halted or was stopped by its handler.
+    Finished,
+    /// Every program still running was waiting on an empty queue.
+    Deadlocked,
+    /// The program with this id used up a whole turn without halting or waiting, so it's
+    /// probably looping forever without talking to the others.
+    Livelocked(i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuetReport {
+    /// One entry per program, in order of their ids.
+    pub stats: Vec<ProgramStats>,
+    pub outcome: Outcome,
+}
+
+/// How many instructions a program may run in one turn before it's given up on.
+pub const TURN_LIMIT: usize = 10_000_000;
+
+/// Runs `count` copies of the program with the `Duet` handler, each with its `p` register set
+/// to its id, taking turns on this thread. Each program sends to the next one along, with the
+/// last sending back to the first, so with two they send to each other.
+///
+/// A program keeps its turn until it halts or needs a value that hasn't been sent yet. Once a
+/// full round passes in which no program could run an instruction they're all finished or
+/// deadlocked, which is decided by the queues rather than by how long anything has waited.
+/// A program which runs for `TURN_LIMIT` instructions without either stops everything.
+pub fn run_together(program: &Program, count: usize) -> DuetReport {
+    run_together_with_limit(program, count, TURN_LIMIT)
+}
+
+/// As `run_together`, giving each program at most `turn_limit` instructions per turn.
+pub fn run_together_with_limit(program: &Program, count: usize, turn_limit: usize) -> DuetReport {
+    let mut machines = (0..count)
+        .map(|id| Machine::new(program, Duet::new(id as i64)))
+        .collect::<Vec<_>>();
+    let mut states = vec![State::Running; count];
+    let mut livelocked = None;
+
+    while livelocked.is_none() {
+        let mut progress = false;
+
+        for id in 0..count {
+            if states[id] != State::Running && states[id] != State::Waiting {
+                continue;
+            }
+
+            let before = machines[id].stats().executed();
+            states[id] = (0..turn_limit)
+                .map(|_| machines[id].step())
+                .find(|state| *state != State::Running)
+                .unwrap_or(State::Running);
+            progress |= machines[id].stats().executed() != before;
+
+            let sent = machines[id].handler_mut().take_output();
+            machines[(id + 1) % count].handler_mut().deliver(sent);
+
+            if states[id] == State::Running {
+                livelocked = Some(id as i64);
+                break;
+            }
+        }
+
+        if !progress {
+            break;
+        }
+    }
+
+    DuetReport {
+        stats: machines
+            .iter()
+            .enumerate()
+            .map(|(id, machine)| ProgramStats {
+                id: id as i64,
+                sent: machine.stats().count("snd"),
+                received: machine.stats().count("rcv"),
+                state: states[id],
+                instructions: machine.stats().clone(),
+            })
+            .collect(),
+        outcome: match livelocked {
+            Some(id) => Outcome::Livelocked(id),
+            None if states.contains(&State::Waiting) => Outcome::Deadlocked,
+            None => Outcome::Finished,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parse_program;
+
+    #[test]
+    fn test_pair_deadlocks() {
+        let code = "snd 1\nsnd 2\nsnd p\nrcv a\nrcv b\nrcv c\nrcv d\n";
+        let report = run_together(&parse_program(code).unwrap(), 2);
+
+        assert_eq!(report.outcome, Outcome::Deadlocked);
+        for (id, stats) in report.stats.iter().enumerate() {
+            assert_eq!(stats.id, id as i64);
+            assert_eq!(stats.sent, 3);
+            assert_eq!(stats.received, 3);
+            assert_eq!(stats.state, State::Waiting);
+        }
+    }
+
+    #[test]
+    fn test_ring_halts_without_deadlock() {
+        // each program passes on one more than it got, with program 0 starting things off by
+        // sending 1 first
+        let code = "jgz p 2\nsnd 1\nrcv a\nadd a 1\nsnd a\n";
+        let report = run_together(&parse_program(code).unwrap(), 3);
+
+        assert_eq!(report.outcome, Outcome::Finished);
+        let sent = report.stats.iter().map(|s| s.sent).collect::<Vec<_>>();
+        let received = report.stats.iter().map(|s| s.received).collect::<Vec<_>>();
+        assert_eq!(sent, vec![2, 1, 1]);
+        assert_eq!(received, vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_busy_loop_livelocks() {
+        // program 1 sends one value and then spins, leaving program 0 waiting for a second
+        let code = "jgz p 3\nrcv a\nrcv a\nsnd 1\nset a 1\njgz a 0\n";
+        let report = run_together_with_limit(&parse_program(code).unwrap(), 2, 1000);
+
+        assert_eq!(report.outcome, Outcome::Livelocked(1));
+        assert_eq!(report.stats[0].state, State::Waiting);
+        assert_eq!(report.stats[1].state, State::Running);
+        assert_eq!(report.stats[1].sent, 1);
+        assert_eq!(report.stats[1].instructions.executed(), 1000);
+    }
+}
@@ -0,0 +1,242 @@
+//! Runs a program one instruction at a time, asking what to do next whenever it pauses.
+
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use {Handler, Machine, Program, RegisterName, Run, State};
+
+const HELP: &str = "Commands:
+  s [N], step [N]     run N instructions (default 1), or just press enter for one
+  c, continue         run until a breakpoint or the end
+  b N, break N        set or clear a breakpoint on instruction N
+  set R V             put V in register R
+  q, quit             stop the program where it is
+  h, help             show this";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Step(usize),
+    Continue,
+    Break(usize),
+    Set(RegisterName, i64),
+    Quit,
+    Help,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Command, String> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let number = |word: Option<&&str>| {
+            word.ok_or_else(|| "Missing a number".to_owned())
+                .and_then(|w| i64::from_str(w).map_err(|_| format!("\"{}\" isn't a number", w)))
+        };
+
+        match words.first().cloned() {
+            None => Ok(Command::Step(1)),
+            Some("s") | Some("step") => match words.get(1) {
+                None => Ok(Command::Step(1)),
+                n => Ok(Command::Step(number(n)?.max(1) as usize)),
+            },
+            Some("c") | Some("continue") => Ok(Command::Continue),
+            Some("b") | Some("break") => {
+                let index = words
+                    .get(1)
+                    .ok_or_else(|| "Which instruction?".to_owned())?;
+                usize::from_str(index)
+                    .map(Command::Break)
+                    .map_err(|_| format!("\"{}\" isn't an instruction number", index))
+            }
+            Some("set") => {
+                let register = words
+                    .get(1)
+                    .and_then(|w| char::from_str(w).ok())
+                    .ok_or_else(|| "Which register?".to_owned())?;
+                Ok(Command::Set(register, number(words.get(2))?))
+            }
+            Some("q") | Some("quit") => Ok(Command::Quit),
+            Some("h") | Some("help") => Ok(Command::Help),
+            Some(other) => Err(format!("I don't know \"{}\", try \"help\"", other)),
+        }
+    }
+}
+
+/// Pauses the program before each instruction, showing the registers and the instruction about
+/// to run, and reads commands from `input` until told to carry on.
+pub struct Stepper<'a, H, R, W> {
+    machine: Machine<'a, H>,
+    input: R,
+    output: W,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl<'a, H: Handler, R: BufRead, W: Write> Stepper<'a, H, R, W> {
+    pub fn new(program: &'a Program, handler: H, input: R, output: W) -> Stepper<'a, H, R, W> {
+        Stepper {
+            machine: Machine::new(program, handler),
+            input,
+            output,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Runs the program to the end, unless it's stopped by `quit` or the input running out, in
+    /// which case it's left in the `Running` state.
+    pub fn run(mut self) -> io::Result<Run<H>> {
+        // how many instructions to run before pausing again
+        let mut steps_left = 0;
+
+        loop {
+            let position = self.machine.instruction_pointer();
+            let at_breakpoint = position >= 0 && self.breakpoints.contains(&(position as usize));
+            if steps_left == 0 || at_breakpoint {
+                if at_breakpoint {
+                    writeln!(self.output, "Breakpoint at {}", position)?;
+                }
+                match self.prompt()? {
+                    Some(steps) => steps_left = steps,
+                    None => return Ok(self.machine.finish(State::Running)),
+                }
+            }
+
+            let state = self.machine.step();
+            if state != State::Running {
+                writeln!(self.output, "Program finished: {:?}", state)?;
+                return Ok(self.machine.finish(state));
+            }
+            steps_left -= 1;
+        }
+    }
+
+    /// Shows where the program is and reads commands until one of them lets it run, returning
+    /// how many instructions to run, or `None` to stop.
+    fn prompt(&mut self) -> io::Result<Option<usize>> {
+        self.show()?;
+
+        loop {
+            write!(self.output, "> ")?;
+            self.output.flush()?;
+
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+
+            match Command::from_str(&line) {
+                Ok(Command::Step(n)) => return Ok(Some(n)),
+                Ok(Command::Continue) => return Ok(Some(usize::MAX)),
+                Ok(Command::Quit) => return Ok(None),
+                Ok(Command::Break(line)) => {
+                    if self.breakpoints.insert(line) {
+                        writeln!(self.output, "Breakpoint set at {}", line)?;
+                    } else {
+                        self.breakpoints.remove(&line);
+                        writeln!(self.output, "Breakpoint cleared at {}", line)?;
+                    }
+                }
+                Ok(Command::Set(register, value)) => {
+                    self.machine.set_register(register, value);
+                    self.show()?;
+                }
+                Ok(Command::Help) => writeln!(self.output, "{}", HELP)?,
+                Err(e) => writeln!(self.output, "{}", e)?,
+            }
+        }
+    }
+
+    fn show(&mut self) -> io::Result<()> {
+        let registers = self
+            .machine
+            .registers()
+            .iter()
+            .map(|(r, v)| format!("{}={}", r, v))
+            .collect::<Vec<_>>();
+        writeln!(self.output, "Registers: {}", registers.join(" "))?;
+        match self.machine.next_instruction() {
+            Some(instruction) => writeln!(
+                self.output,
+                "{:4}: {}",
+                self.machine.instruction_pointer(),
+                instruction
+            ),
+            None => writeln!(self.output, "  (end of program)"),
+        }
+    }
+}
+
+/// Steps through the program on the terminal.
+pub fn step_through<H: Handler>(program: &Program, handler: H) -> io::Result<Run<H>> {
+    let stdin = io::stdin();
+    Stepper::new(program, handler, stdin.lock(), io::stdout()).run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {parse_program, SoundCard};
+
+    const SAMPLE: &str =
+        "set a 1\nadd a 2\nmul a a\nmod a 5\nsnd a\nset a 0\nrcv a\njgz a -1\nset a 1\njgz a -2\n";
+
+    fn step(commands: &str) -> (Run<SoundCard>, String) {
+        let program = parse_program(SAMPLE).unwrap();
+        let mut output = Vec::new();
+        let run = Stepper::new(
+            &program,
+            SoundCard::default(),
+            commands.as_bytes(),
+            &mut output,
+        )
+        .run()
+        .unwrap();
+        (run, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::from_str("\n"), Ok(Command::Step(1)));
+        assert_eq!(Command::from_str("s 5"), Ok(Command::Step(5)));
+        assert_eq!(Command::from_str("break 7"), Ok(Command::Break(7)));
+        assert_eq!(
+            Command::from_str("b -1"),
+            Err("\"-1\" isn't an instruction number".to_owned())
+        );
+        assert!(Command::from_str("b").is_err());
+        assert_eq!(Command::from_str("set b -3"), Ok(Command::Set('b', -3)));
+        assert!(Command::from_str("set b").is_err());
+        assert!(Command::from_str("jump").is_err());
+    }
+
+    #[test]
+    fn test_step_and_quit() {
+        let (run, output) = step("\ns 3\nq\n");
+
+        assert_eq!(run.state, State::Running);
+        assert_eq!(run.stats.executed(), 4);
+        assert_eq!(run.register('a'), 4);
+        assert!(
+            output.starts_with("Registers: \n   0: set a 1\n> Registers: a=1\n   1: add a 2\n> ")
+        );
+        assert!(output.ends_with("Registers: a=4\n   4: snd a\n> "));
+    }
+
+    #[test]
+    fn test_breakpoint_and_set() {
+        let (run, output) = step("b 6\nc\nset a 5\nc\n");
+
+        // rcv would have seen the 0 and carried on, looping round before recovering the sound
+        assert_eq!(run.state, State::Stopped(4));
+        assert_eq!(run.stats.executed(), 7);
+        assert!(output.contains("Breakpoint at 6\nRegisters: a=0\n   6: rcv a\n"));
+        assert!(output.contains("Registers: a=5\n   6: rcv a\n> "));
+        assert!(output.ends_with("Program finished: Stopped(4)\n"));
+    }
+
+    #[test]
+    fn test_running_out_of_input_stops() {
+        let (run, _) = step("");
+        assert_eq!(run.state, State::Running);
+        assert_eq!(run.stats.executed(), 0);
+    }
+}
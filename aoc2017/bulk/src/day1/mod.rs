@@ -1,5 +1,4 @@
 use std::time::Instant;
-use util::asmillis::AsMillis;
 use util;
 
 pub fn go(count: usize) {
@@ -1,7 +1,7 @@
 use regex::Regex;
 use std::str::FromStr;
 use util::timed;
-use std::collections::HashSet;
+use aoc_common::cycle::nth_state;
 
 pub fn go() {
     let input = include_str!("input.txt");
@@ -37,63 +37,15 @@ fn dance_a_lot(
     original_dancers: &Vec<char>,
     iterations: usize,
 ) -> Vec<char> {
-    let mut dancers = Dancers {
+    // the dance soon comes back round to an order it's seen before, so we only have to dance
+    // the first time round
+    let dancers = Dancers {
         dancers: (*original_dancers).clone(),
     };
 
-    let mut seen_before = HashSet::new();
-    let mut previous_sequence = Vec::new();
-    let mut last_seen_index = None;
-    let mut sequence_size = None;
-
-    for iteration in 0..iterations {
-        dancers = dancers.dance(moves);
-        if !seen_before.contains(&dancers.dancers) {
-            seen_before.insert(dancers.dancers.clone());
-            previous_sequence.push(dancers.dancers.clone());
-        } else {
-            let index_of_previous_sighting = previous_sequence
-                .iter()
-                .enumerate()
-                .filter(|&(i, x)| x == &dancers.dancers)
-                .next()
-                .unwrap()
-                .0;
-
-            match last_seen_index {
-                None => last_seen_index = Some(index_of_previous_sighting),
-                Some(last_seen) => {
-                    // we've hit sequence mode!
-                    sequence_size = Some(previous_sequence.len());
-                    break;
-                }
-            }
-        }
-        if iteration % 1000 == 0 {
-            println!("After iteration {}: {:?}", iteration + 1, dancers.dancers);
-        }
-    }
-
-    if let Some(ss) = sequence_size {
-        println!("Detected a sequence of size {}", ss);
-
-        let actually_required_iterations = iterations % ss;
-        println!("Proceeding to dance {} times", actually_required_iterations);
-        dancers = Dancers {
-            dancers: (*original_dancers).clone(),
-        };
-
-        for _ in 0..actually_required_iterations {
-            dancers = dancers.dance(moves);
-        }
-    } else {
-        println!("No sequence detected");
-    }
-
-    dancers.dancers
+    nth_state(dancers, |d| d.dance(moves), iterations).dancers
 }
 
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DanceMove {
     Spin(usize),
@@ -101,7 +53,7 @@ enum DanceMove {
     Partner(char, char),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Dancers {
     dancers: Vec<char>,
 }
@@ -1,3 +1,4 @@
+use aoc_common::circular::CircularList;
 use util::timed;
 
 pub fn go() {
@@ -13,56 +14,37 @@ pub fn go() {
 }
 
 fn part1(steps: usize) -> u16 {
-    let mut buffer = CircularBuffer::new(0u16, 2018);
+    let mut buffer = CircularList::with_capacity(2018);
+    buffer.extend(vec![0u16]);
 
     for n in 1..2018 {
-        buffer.step_insert(steps, n);
+        buffer.move_by(steps as isize);
+        buffer.insert_after(n);
+        buffer.move_next();
     }
 
-    buffer.buffer[(buffer.position + 1) % buffer.buffer.len()]
+    buffer.move_next();
+    *buffer.current().unwrap()
 }
 
+/// 0 is always the first thing in the buffer, so rather than building the buffer we just
+/// need to keep track of the position and remember the last value inserted straight after 0
 fn part2(steps: usize) -> u32 {
-    let mut buffer = CircularBuffer::new(0u32, 50000001);
+    let mut position = 0;
+    let mut after_zero = 0;
 
     for n in 1..50000000 {
-        buffer.step_insert_only_after_zero(steps, n);
-    }
-
-    buffer.buffer[1]
-}
-
-struct CircularBuffer<T> {
-    buffer: Vec<T>,
-    position: usize,
-}
-
-impl<T> CircularBuffer<T> {
-    fn new(initial_value: T, desired_capacity: usize) -> CircularBuffer<T> {
-        let mut v = Vec::with_capacity(desired_capacity);
-        v.push(initial_value);
-        CircularBuffer {
-            buffer: v,
-            position: 0,
+        // there are n values in the buffer before inserting n
+        position = (position + steps) % n as usize + 1;
+        if position == 1 {
+            after_zero = n;
         }
     }
 
-    fn step_insert(&mut self, steps: usize, value: T) {
-        self.position = (self.position + steps) % self.buffer.len();
-        self.buffer.insert(self.position + 1, value);
-        self.position += 1;
-    }
+    after_zero
+}
 
-    /// Optimised `step_insert` which only actually inserts something if it's at position zero
-    /// which is the only one we're interested in for part two of the problem
-    fn step_insert_only_after_zero(&mut self, steps: usize, value: T) {
-        self.position = (self.position + steps) % self.buffer.len();
-        if self.position == 0 {
-            self.buffer.insert(self.position + 1, value);
-        } else {
-            // just put it on the end
-            self.buffer.push(value);
-        }
-        self.position += 1;
-    }
+#[test]
+fn test_part1_sample() {
+    assert_eq!(part1(3), 638);
 }
@@ -1,7 +1,6 @@
 use std::ops::Sub;
 use std::str::FromStr;
 use std::time::Instant;
-use util::asmillis::AsMillis;
 use util;
 use util::pairs::IntoPairs;
 
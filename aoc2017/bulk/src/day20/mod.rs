@@ -1,26 +1,93 @@
-use std::ops::Add;
-use std::str::FromStr;
 use regex::Regex;
+use std::collections::HashSet;
+use std::ops::Sub;
+use std::str::FromStr;
+use util::timed;
+
+pub fn go() {
+    let input = include_str!("input.txt");
+    let particles = parse_particles(input);
+
+    let (closest, time) = timed(|| closest_in_long_term(&particles));
+    println!(
+        "[{}ms] Particle {} stays closest to the origin",
+        time, closest
+    );
+
+    let (remaining, time) = timed(|| survivors(&particles));
+    println!(
+        "[{}ms] {} particles are left after collisions",
+        time, remaining
+    );
+}
+
+/// Which particle is nearest the origin once they've all been left to fly for long enough
+fn closest_in_long_term(particles: &[Particle]) -> usize {
+    particles
+        .iter()
+        .enumerate()
+        .min_by_key(|&(_, p)| p.long_term_distance())
+        .map(|(i, _)| i)
+        .expect("No particles")
+}
+
+/// How many particles are left once any which end up in the same place at the same time have
+/// been destroyed
+fn survivors(particles: &[Particle]) -> usize {
+    // every pair's first meeting, if they ever meet
+    let mut collisions = vec![];
+    for i in 0..particles.len() {
+        for j in (i + 1)..particles.len() {
+            if let Some(tick) = particles[i].first_collision(&particles[j]) {
+                collisions.push((tick, i, j));
+            }
+        }
+    }
+    collisions.sort();
+
+    // a meeting only happens if neither particle was destroyed on an earlier tick, but
+    // everything meeting on the same tick goes at once
+    let mut destroyed = HashSet::new();
+    for same_tick in collisions.chunk_by(|a, b| a.0 == b.0) {
+        let destroyed_now = same_tick
+            .iter()
+            .filter(|&&(_, i, j)| !destroyed.contains(&i) && !destroyed.contains(&j))
+            .flat_map(|&(_, i, j)| vec![i, j])
+            .collect::<Vec<_>>();
+        destroyed.extend(destroyed_now);
+    }
+
+    particles.len() - destroyed.len()
+}
 
-pub fn go() {}
+fn parse_particles(input: &str) -> Vec<Particle> {
+    input
+        .lines()
+        .map(|line| Particle::from_str(line).expect("Couldn't parse particle"))
+        .collect()
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Vector {
-    x: i32,
-    y: i32,
-    z: i32,
+    x: i64,
+    y: i64,
+    z: i64,
 }
 
 impl Vector {
-    fn new(x: i32, y: i32, z: i32) -> Vector {
-        Vector { x: x, y: y, z: z }
+    fn new(x: i64, y: i64, z: i64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    fn axes(&self) -> [i64; 3] {
+        [self.x, self.y, self.z]
     }
 }
 
-impl Add for Vector {
+impl Sub for Vector {
     type Output = Vector;
-    fn add(self, rhs: Vector) -> Vector {
-        Vector::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    fn sub(self, rhs: Vector) -> Vector {
+        Vector::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
     }
 }
 
@@ -40,15 +107,137 @@ impl Particle {
         }
     }
 
-    fn tick(&self) -> Particle {
-        let velocity = self.velocity + self.acceleration;
-        let position = self.position + self.velocity;
+    /// Where the particle is after `ticks` ticks. Velocity changes before position does, so
+    /// after t ticks it's moved vt + a(1 + 2 + ... + t).
+    #[cfg(test)]
+    fn position_at(&self, ticks: i64) -> Vector {
+        let at = |p: i64, v: i64, a: i64| p + v * ticks + a * ticks * (ticks + 1) / 2;
+        Vector::new(
+            at(self.position.x, self.velocity.x, self.acceleration.x),
+            at(self.position.y, self.velocity.y, self.acceleration.y),
+            at(self.position.z, self.velocity.z, self.acceleration.z),
+        )
+    }
+
+    /// Something to compare particles by which puts them in the order of their distances from
+    /// the origin once enough time has passed.
+    ///
+    /// Eventually each axis stops changing direction and heads away from the origin for good,
+    /// which way being decided by the acceleration, or the velocity if there's none, or the
+    /// position if that's all there is. From then on (doubling to keep it whole) the distance is
+    /// a polynomial in t whose coefficients come out of each axis with that sign applied, and
+    /// comparing those from the largest power down compares the distances.
+    fn long_term_distance(&self) -> (i64, i64, i64) {
+        let mut coefficients = (0, 0, 0);
+        for axis in 0..3 {
+            let (p, v, a) = (
+                self.position.axes()[axis],
+                self.velocity.axes()[axis],
+                self.acceleration.axes()[axis],
+            );
+            let sign = [a, v, p]
+                .iter()
+                .map(|n| n.signum())
+                .find(|&s| s != 0)
+                .unwrap_or(0);
+            coefficients.0 += sign * a;
+            coefficients.1 += sign * (2 * v + a);
+            coefficients.2 += sign * 2 * p;
+        }
 
-        Particle::new(position, self.acceleration, velocity)
+        coefficients
     }
 
-    fn distance_from_origin(&self) -> i32 {
-        self.position.x.abs() + self.position.y.abs() + self.position.z.abs()
+    /// The first tick after which both particles are in the same place, if there is one.
+    fn first_collision(&self, other: &Particle) -> Option<i64> {
+        let dp = self.position - other.position;
+        let dv = self.velocity - other.velocity;
+        let da = self.acceleration - other.acceleration;
+
+        // doubling the difference in position_at gives da t² + (2dv + da) t + 2dp on each axis,
+        // which must be zero on all three at once
+        let mut ticks: Option<Vec<i64>> = None;
+        for axis in 0..3 {
+            let (p, v, a) = (dp.axes()[axis], dv.axes()[axis], da.axes()[axis]);
+            let roots = match whole_roots(a, 2 * v + a, 2 * p) {
+                Roots::Always => continue,
+                Roots::Only(roots) => roots,
+            };
+            ticks = Some(match ticks {
+                None => roots,
+                Some(ticks) => ticks.into_iter().filter(|t| roots.contains(t)).collect(),
+            });
+        }
+
+        match ticks {
+            // the same motion from the same place, so they've collided already
+            None => Some(0),
+            Some(ticks) => ticks.into_iter().filter(|&t| t > 0).min(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Roots {
+    Always,
+    Only(Vec<i64>),
+}
+
+/// The non-negative whole numbers t for which at² + bt + c = 0
+fn whole_roots(a: i64, b: i64, c: i64) -> Roots {
+    if a == 0 && b == 0 {
+        return if c == 0 {
+            Roots::Always
+        } else {
+            Roots::Only(vec![])
+        };
+    }
+
+    let candidates = if a == 0 {
+        if c % b == 0 {
+            vec![-c / b]
+        } else {
+            vec![]
+        }
+    } else {
+        let discriminant = b * b - 4 * a * c;
+        match whole_sqrt(discriminant) {
+            None => vec![],
+            Some(root) => [-b - root, -b + root]
+                .iter()
+                .filter(|&&n| n % (2 * a) == 0)
+                .map(|&n| n / (2 * a))
+                .collect(),
+        }
+    };
+
+    let mut roots = candidates
+        .into_iter()
+        .filter(|&t| t >= 0)
+        .collect::<Vec<_>>();
+    roots.dedup();
+    Roots::Only(roots)
+}
+
+/// The square root of `n`, if it's a perfect square
+fn whole_sqrt(n: i64) -> Option<i64> {
+    if n < 0 {
+        return None;
+    }
+
+    // the float gets close, then make sure of it
+    let mut root = (n as f64).sqrt() as i64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+
+    if root * root == n {
+        Some(root)
+    } else {
+        None
     }
 }
 
@@ -57,27 +246,116 @@ impl FromStr for Particle {
 
     fn from_str(s: &str) -> Result<Particle, ()> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"p=<(-?\d+),(-?\d+),(-?\d+)>, v=<(-?\d+),(-?\d+),(-?\d+)>, a=<(-?\d+),(-?\d+),(-?\d+)>").unwrap();
+            static ref RE: Regex = Regex::new(r"p=<\s*(-?\d+),\s*(-?\d+),\s*(-?\d+)>, v=<\s*(-?\d+),\s*(-?\d+),\s*(-?\d+)>, a=<\s*(-?\d+),\s*(-?\d+),\s*(-?\d+)>").unwrap();
         }
 
         let caps = RE.captures(s);
         match caps {
             None => Err(()),
             Some(c) => {
-                let px = i32::from_str(&c[0]).map_err(|_| ())?;
-                let py = i32::from_str(&c[1]).map_err(|_| ())?;
-                let pz = i32::from_str(&c[2]).map_err(|_| ())?;
-
-                let vx = i32::from_str(&c[3]).map_err(|_| ())?;
-                let vy = i32::from_str(&c[4]).map_err(|_| ())?;
-                let vz = i32::from_str(&c[5]).map_err(|_| ())?;
+                let n = |i: usize| i64::from_str(&c[i]).map_err(|_| ());
 
-                let ax = i32::from_str(&c[6]).map_err(|_| ())?;
-                let ay = i32::from_str(&c[7]).map_err(|_| ())?;
-                let az = i32::from_str(&c[8]).map_err(|_| ())?;
-
-                Ok(Particle::new(Vector::new(px, py, pz), Vector::new(ax, ay, az), Vector::new(vx, vy, vz)))
+                Ok(Particle::new(
+                    Vector::new(n(1)?, n(2)?, n(3)?),
+                    Vector::new(n(7)?, n(8)?, n(9)?),
+                    Vector::new(n(4)?, n(5)?, n(6)?),
+                ))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_particle() {
+        let p = Particle::from_str("p=<3,0,-1>, v=<2,0,0>, a=<-1,0,4>").unwrap();
+        assert_eq!(p.position, Vector::new(3, 0, -1));
+        assert_eq!(p.velocity, Vector::new(2, 0, 0));
+        assert_eq!(p.acceleration, Vector::new(-1, 0, 4));
+
+        let spaced = Particle::from_str("p=< 3,0,-1>, v=< 2,0,0>, a=<-1,0, 4>").unwrap();
+        assert_eq!(spaced, p);
+    }
+
+    #[test]
+    fn test_position_at() {
+        let p = Particle::from_str("p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>").unwrap();
+        let xs = (0..5).map(|t| p.position_at(t).x).collect::<Vec<_>>();
+        assert_eq!(xs, vec![3, 4, 4, 3, 1]);
+    }
+
+    #[test]
+    fn test_whole_roots() {
+        // (t - 2)(t - 5)
+        assert_eq!(whole_roots(1, -7, 10), Roots::Only(vec![2, 5]));
+        // (2t - 3)(t - 4), so only the whole one
+        assert_eq!(whole_roots(2, -11, 12), Roots::Only(vec![4]));
+        // (t + 1)(t - 3)
+        assert_eq!(whole_roots(1, -2, -3), Roots::Only(vec![3]));
+        // (t - 3)²
+        assert_eq!(whole_roots(1, -6, 9), Roots::Only(vec![3]));
+        assert_eq!(whole_roots(0, 2, -8), Roots::Only(vec![4]));
+        assert_eq!(whole_roots(1, 0, 1), Roots::Only(vec![]));
+        assert_eq!(whole_roots(0, 0, 1), Roots::Only(vec![]));
+        assert_eq!(whole_roots(0, 0, 0), Roots::Always);
+    }
+
+    #[test]
+    fn test_first_collision() {
+        let particles = parse_particles(
+            "p=<-6,0,0>, v=<3,0,0>, a=<0,0,0>
+p=<-4,0,0>, v=<2,0,0>, a=<0,0,0>
+p=<5,0,0>, v=<-1,0,0>, a=<0,0,0>
+p=<0,0,0>, v=<0,0,0>, a=<1,0,0>
+p=<0,5,0>, v=<0,0,0>, a=<1,0,0>",
+        );
+        assert_eq!(particles[0].first_collision(&particles[1]), Some(2));
+        assert_eq!(particles[0].first_collision(&particles[2]), None);
+        assert_eq!(particles[1].first_collision(&particles[2]), Some(3));
+        // x = t(t+1)/2 meets x = 5 - t after the second tick
+        assert_eq!(particles[2].first_collision(&particles[3]), Some(2));
+        assert_eq!(particles[3].first_collision(&particles[4]), None);
+    }
+
+    #[test]
+    fn test_closest_in_long_term() {
+        let particles = parse_particles(
+            "p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>
+p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>",
+        );
+        assert_eq!(closest_in_long_term(&particles), 0);
+
+        // the first starts furthest away, but ends up a step behind the others for good
+        let particles = parse_particles(
+            "p=<100,0,0>, v=<-5,0,0>, a=<1,0,0>
+p=<0,0,0>, v=<0,0,0>, a=<1,0,0>
+p=<0,0,0>, v=<0,0,0>, a=<-1,0,0>",
+        );
+        assert_eq!(closest_in_long_term(&particles), 0);
+    }
+
+    #[test]
+    fn test_survivors() {
+        let particles = parse_particles(
+            "p=<-6,0,0>, v=<3,0,0>, a=<0,0,0>
+p=<-4,0,0>, v=<2,0,0>, a=<0,0,0>
+p=<-2,0,0>, v=<1,0,0>, a=<0,0,0>
+p=<3,0,0>, v=<-1,0,0>, a=<0,0,0>",
+        );
+        assert_eq!(survivors(&particles), 1);
+    }
+
+    #[test]
+    fn test_destroyed_particles_collide_no_more() {
+        // the first two meet after one tick, so the third never gets to hit the first
+        let particles = parse_particles(
+            "p=<0,0,0>, v=<1,0,0>, a=<0,0,0>
+p=<2,0,0>, v=<-1,0,0>, a=<0,0,0>
+p=<-2,0,0>, v=<2,0,0>, a=<0,0,0>",
+        );
+        assert_eq!(survivors(&particles), 1);
+    }
+}
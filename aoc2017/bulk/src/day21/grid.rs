@@ -1,3 +1,4 @@
+use aoc_common::transform::Transform;
 use std::str::FromStr;
 use std::fmt;
 
@@ -9,7 +10,7 @@ pub struct Grid {
 impl Grid {
     /// Test if a grid is a match over mirroring and rotation for another grid.
     pub fn is_match(&self, other: &Grid) -> bool {
-        self.clone().into_iter().any(|s| s == *other)
+        self.symmetries().iter().any(|s| s == other)
     }
 
     pub fn size(&self) -> usize {
@@ -17,11 +18,11 @@ impl Grid {
     }
 
     pub fn as_threes(&self) -> Option<Vec<Vec<Grid>>> {
-        self.get_squares(3)
+        self.split_blocks(3)
     }
 
     pub fn as_twos(&self) -> Option<Vec<Vec<Grid>>> {
-        self.get_squares(2)
+        self.split_blocks(2)
     }
 
     pub fn count_lit(&self) -> usize {
@@ -31,47 +32,10 @@ impl Grid {
             .sum()
     }
 
-    fn get_squares(&self, size: usize) -> Option<Vec<Vec<Grid>>> {
-        if self.size() % size != 0 {
-            return None;
-        }
-
-        let mut result = Vec::new();
-
-        let count = self.size() / size;
-        for ycount in 0..count {
-            let mut result_line = Vec::new();
-            for xcount in 0..count {
-                let mut output = Vec::new();
-                for y in 0..size {
-                    let mut line = Vec::new();
-                    for x in 0..size {
-                        let required_x = x + (xcount * size);
-                        let required_y = y + (ycount * size);
-
-                        line.push(self.get(required_x, required_y).expect(&format!(
-                            "Getting ({}, {}) should work",
-                            required_x,
-                            required_y
-                        )));
-                    }
-                    output.push(line);
-                }
-                result_line.push(Grid { lines: output });
-            }
-            result.push(result_line);
-        }
-
-        Some(result)
-    }
-
+    #[cfg(test)]
     pub fn lines(&self) -> &Vec<Vec<bool>> {
         &self.lines
     }
-
-    fn get(&self, x: usize, y: usize) -> Option<bool> {
-        self.lines.get(y).and_then(|line| line.get(x)).map(|a| *a)
-    }
 }
 
 impl fmt::Display for Grid {
@@ -89,191 +53,37 @@ impl fmt::Display for Grid {
     }
 }
 
-pub fn merge_grids(grids: &Vec<Vec<Grid>>) -> Option<Grid> {
-    if grids.len() == 0 {
-        return None;
-    }
+impl Transform for Grid {
+    type Cell = bool;
 
-    if grids.len() != grids[0].len() {
-        // not square
-        return None;
+    fn width(&self) -> usize {
+        self.size()
     }
 
-    let size = grids[0][0].lines.len();
-
-    let result = grids
-        .iter()
-        .flat_map(|squares_line| {
-            (0..size)
-                .map(|a| {
-                    squares_line
-                        .iter()
-                        .flat_map(|square| square.lines[a].clone())
-                        .collect::<Vec<bool>>()
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-
-    Some(Grid { lines: result })
-}
-
-
-/// Rotate a square grid 90 degrees
-fn rotate_grid(grid: &Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    let size = grid.len();
-    let mut output = Vec::new();
-    for x in 0..size {
-        let mut line = grid.iter().map(|l| l[x]).collect::<Vec<_>>();
-        line.reverse();
-        output.push(line);
+    fn height(&self) -> usize {
+        self.size()
     }
 
-    output
-}
-
-fn flip_grid_vertical(grid: &Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    let mut output = grid.clone();
-    output.reverse();
-    output
-}
-
-fn flip_grid_horizontal(grid: &Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    let mut output = Vec::new();
-    for mut line in grid.clone() {
-        line.reverse();
-        output.push(line);
+    fn cell(&self, x: usize, y: usize) -> &bool {
+        &self.lines[y][x]
     }
 
-    output
-}
-
-enum Permutation {
-    Original,
-    Rotate1,
-    Rotate2,
-    Rotate3,
-    FlipHorizontal,
-    FlipHorizontalRotate1,
-    FlipHorizontalRotate2,
-    FlipHorizontalRotate3,
-    FlipVertical,
-    FlipVerticalRotate1,
-    FlipVerticalRotate2,
-    FlipVerticalRotate3,
-    Complete,
-}
-
-pub struct GridPermutations {
-    current: Vec<Vec<bool>>,
-    permutation: Permutation,
-    original: Vec<Vec<bool>>,
-}
-
-impl<'a> IntoIterator for &'a Grid {
-    type Item = Grid;
-    type IntoIter = GridPermutations;
-
-    fn into_iter(self) -> GridPermutations {
-        GridPermutations {
-            current: self.lines.clone(),
-            permutation: Permutation::Original,
-            original: self.lines.clone(),
+    fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> bool) -> Grid {
+        Grid {
+            lines: (0..height)
+                .map(|y| (0..width).map(|x| f(x, y)).collect())
+                .collect(),
         }
     }
 }
 
-impl Iterator for GridPermutations {
-    type Item = Grid;
-
-    fn next(&mut self) -> Option<Grid> {
-        match self.permutation {
-            Permutation::Original => {
-                self.permutation = Permutation::Rotate1;
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::Rotate1 => {
-                self.permutation = Permutation::Rotate2;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::Rotate2 => {
-                self.permutation = Permutation::Rotate3;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::Rotate3 => {
-                self.permutation = Permutation::FlipHorizontal;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipHorizontal => {
-                self.permutation = Permutation::FlipHorizontalRotate1;
-                self.current = flip_grid_horizontal(&self.original);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipHorizontalRotate1 => {
-                self.permutation = Permutation::FlipHorizontalRotate2;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipHorizontalRotate2 => {
-                self.permutation = Permutation::FlipHorizontalRotate3;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipHorizontalRotate3 => {
-                self.permutation = Permutation::FlipVertical;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipVertical => {
-                self.permutation = Permutation::FlipVerticalRotate1;
-                self.current = flip_grid_vertical(&self.original);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipVerticalRotate1 => {
-                self.permutation = Permutation::FlipVerticalRotate2;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipVerticalRotate2 => {
-                self.permutation = Permutation::FlipVerticalRotate3;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::FlipVerticalRotate3 => {
-                self.permutation = Permutation::Complete;
-                self.current = rotate_grid(&self.current);
-                Some(Grid {
-                    lines: self.current.clone(),
-                })
-            }
-            Permutation::Complete => None,
-        }
+pub fn merge_grids(grids: &Vec<Vec<Grid>>) -> Option<Grid> {
+    if grids.len() != grids.first().map_or(0, |line| line.len()) {
+        // not square
+        return None;
     }
+
+    Grid::join_blocks(grids)
 }
 
 impl FromStr for Grid {
@@ -320,34 +130,34 @@ mod tests {
 
     #[test]
     fn can_rotate() {
-        let start = vec![vec![true, false], vec![false, false]];
+        let start = Grid::from_str("#./..").unwrap();
 
-        let one = rotate_grid(&start);
-        let two = rotate_grid(&one);
-        let three = rotate_grid(&two);
-        let four = rotate_grid(&three);
+        let one = start.rotate_90();
+        let two = one.rotate_90();
+        let three = two.rotate_90();
+        let four = three.rotate_90();
 
-        assert_eq!(one, vec![vec![false, true], vec![false, false]]);
-        assert_eq!(two, vec![vec![false, false], vec![false, true]]);
-        assert_eq!(three, vec![vec![false, false], vec![true, false]]);
+        assert_eq!(one.lines(), &vec![vec![false, true], vec![false, false]]);
+        assert_eq!(two.lines(), &vec![vec![false, false], vec![false, true]]);
+        assert_eq!(three.lines(), &vec![vec![false, false], vec![true, false]]);
         assert_eq!(four, start);
     }
 
     #[test]
     fn can_flip_vertical() {
-        let start = vec![vec![true, false], vec![false, false]];
+        let start = Grid::from_str("#./..").unwrap();
         assert_eq!(
-            flip_grid_vertical(&start),
-            vec![vec![false, false], vec![true, false]]
+            start.flip_vertical().lines(),
+            &vec![vec![false, false], vec![true, false]]
         );
     }
 
     #[test]
     fn can_flip_horizontal() {
-        let start = vec![vec![true, false], vec![false, false]];
+        let start = Grid::from_str("#./..").unwrap();
         assert_eq!(
-            flip_grid_horizontal(&start),
-            vec![vec![false, true], vec![false, false]]
+            start.flip_horizontal().lines(),
+            &vec![vec![false, true], vec![false, false]]
         );
     }
 
@@ -1,14 +1,12 @@
-extern crate util;
-
 mod grid;
 mod agent;
 
 use util::timed;
-use grid::Grid;
-use agent::Agent;
+use self::grid::Grid;
+use self::agent::Agent;
 use std::str::FromStr;
 
-fn main() {
+pub fn go() {
     let input = include_str!("input.txt");
 
     let (result, time) = timed(|| part1(input));
@@ -0,0 +1,40 @@
+use assembly::{self, Coprocessor};
+use util::timed;
+
+pub fn go(step: bool, part2: bool) {
+    let input = include_str!("input.txt");
+
+    if step {
+        step_through(input, part2);
+        return;
+    }
+
+    let (result, time) = timed(|| part1(input));
+    println!("[{}ms] mul was called {} times", time, result);
+
+    let (result, time) = timed(|| part2_result(input));
+    println!("[{}ms] value of h at end was {}", time, result);
+}
+
+fn part1(input: &str) -> usize {
+    // abstracting all of this was a huge waste of time!
+    assembly::run_for_day_23_part_one(input).unwrap()
+}
+
+fn part2_result(input: &str) -> i64 {
+    // the interpreter would take forever, so this relies on the loops being recognised
+    assembly::run_for_day_23_part_two(input).unwrap()
+}
+
+/// Steps through part one, or part two with `--part2` as well, which is the only way to watch
+/// what part two actually does.
+fn step_through(input: &str, part2: bool) {
+    let program = assembly::parse_program(input).unwrap();
+
+    let run = assembly::step_through(&program, Coprocessor { debug: !part2 }).unwrap();
+    println!(
+        "mul was called {} times and h is {}",
+        run.stats.count("mul"),
+        run.register('h')
+    );
+}
@@ -0,0 +1,158 @@
+use std::str::FromStr;
+use util::timed;
+
+pub fn go() {
+    let input = include_str!("input.txt");
+    let components = parse_components(input);
+
+    let (strength, time) = timed(|| strongest_bridge(&components));
+    println!("[{}ms] Strongest bridge is {}", time, strength);
+
+    let ((length, strength), time) = timed(|| longest_bridge(&components));
+    println!(
+        "[{}ms] Longest bridge has {} components and strength {}",
+        time, length, strength
+    );
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+struct Component(u32, u32);
+
+impl Component {
+    fn can_connect_to(&self, pin: u32) -> bool {
+        self.0 == pin || self.1 == pin
+    }
+
+    /// The pin left free when this is connected by `pin`
+    fn other_end(&self, pin: u32) -> u32 {
+        if self.0 == pin {
+            self.1
+        } else {
+            self.0
+        }
+    }
+
+    fn strength(&self) -> u32 {
+        self.0 + self.1
+    }
+}
+
+impl FromStr for Component {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Component, Self::Err> {
+        let parts = s.trim().split('/').collect::<Vec<_>>();
+        if parts.len() != 2 {
+            Err(())
+        } else {
+            let a = u32::from_str(parts[0]).map_err(|_| ())?;
+            let b = u32::from_str(parts[1]).map_err(|_| ())?;
+            Ok(Component(a, b))
+        }
+    }
+}
+
+/// Builds every bridge which can be made starting from a zero-pin port, calling `visit` with the
+/// length and strength of each one.
+fn each_bridge<F>(components: &[Component], visit: &mut F)
+where
+    F: FnMut(usize, u32),
+{
+    let mut used = vec![false; components.len()];
+    extend_bridge(components, &mut used, 0, 0, 0, visit);
+}
+
+fn extend_bridge<F>(
+    components: &[Component],
+    used: &mut [bool],
+    pin: u32,
+    length: usize,
+    strength: u32,
+    visit: &mut F,
+) where
+    F: FnMut(usize, u32),
+{
+    visit(length, strength);
+
+    for (i, &c) in components.iter().enumerate() {
+        if !used[i] && c.can_connect_to(pin) {
+            used[i] = true;
+            extend_bridge(
+                components,
+                used,
+                c.other_end(pin),
+                length + 1,
+                strength + c.strength(),
+                visit,
+            );
+            used[i] = false;
+        }
+    }
+}
+
+fn strongest_bridge(components: &[Component]) -> u32 {
+    let mut best = 0;
+    each_bridge(components, &mut |_, strength| best = best.max(strength));
+    best
+}
+
+/// The length and strength of the longest bridge, picking the strongest if there's a tie.
+fn longest_bridge(components: &[Component]) -> (usize, u32) {
+    let mut best = (0, 0);
+    each_bridge(components, &mut |length, strength| {
+        best = best.max((length, strength))
+    });
+    best
+}
+
+fn parse_components(input: &str) -> Vec<Component> {
+    input
+        .lines()
+        .filter_map(|line| Component::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "0/2
+2/2
+2/3
+3/4
+3/5
+0/1
+10/1
+9/10";
+
+    #[test]
+    fn test_parse_components() {
+        let components = parse_components(SAMPLE);
+        assert_eq!(components.len(), 8);
+        assert_eq!(components[0], Component(0, 2));
+        assert_eq!(components[6], Component(10, 1));
+    }
+
+    #[test]
+    fn test_each_bridge() {
+        let mut bridges = vec![];
+        each_bridge(&parse_components(SAMPLE), &mut |length, strength| {
+            bridges.push((length, strength))
+        });
+
+        // the empty bridge plus the eleven in the puzzle description
+        assert_eq!(bridges.len(), 12);
+        assert!(bridges.contains(&(3, 31)));
+        assert!(bridges.contains(&(4, 19)));
+    }
+
+    #[test]
+    fn test_strongest_bridge() {
+        assert_eq!(strongest_bridge(&parse_components(SAMPLE)), 31);
+    }
+
+    #[test]
+    fn test_longest_bridge() {
+        assert_eq!(longest_bridge(&parse_components(SAMPLE)), (4, 19));
+    }
+}
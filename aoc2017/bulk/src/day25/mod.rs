@@ -0,0 +1,34 @@
+use aoc_common::turing::{Blueprint, Machine};
+use std::fs;
+use std::str::FromStr;
+use util::timed;
+
+pub fn go() {
+    // there's no input checked in for this one, so drop it in alongside this file to run it
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day25/input.txt");
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(e) => {
+            println!("Couldn't read the blueprint from {}: {}", path, e);
+            return;
+        }
+    };
+    let blueprint = Blueprint::from_str(&input).unwrap_or_else(|e| panic!("{}", e));
+
+    let (machine, time) = timed(|| {
+        let mut machine = Machine::new(&blueprint);
+        machine.diagnose();
+        machine
+    });
+    println!(
+        "[{}ms] The diagnostic checksum is {}",
+        time,
+        machine.checksum()
+    );
+
+    let snapshot = machine.snapshot(8);
+    println!(
+        "After {} steps in state {}: {}",
+        snapshot.steps, snapshot.state, snapshot
+    );
+}
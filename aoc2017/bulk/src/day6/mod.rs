@@ -1,13 +1,13 @@
 use std::str::FromStr;
-use std::collections::HashSet;
+use std::iter::successors;
+use aoc_common::cycle::hashed;
 use util::timed_repeatedly;
 
 pub fn go(reps: usize) {
     let input = parse_input(include_str!("input.txt"));
 
     let ((part1, part2), time) = timed_repeatedly(reps, || {
-        let mut buckets = input.clone();
-        explore_loop(&mut buckets)
+        explore_loop(&input)
     });
     println!("[{}ms] {} steps until loop, {} steps in loop",
              time,
@@ -19,35 +19,16 @@ fn parse_input(input: &str) -> Vec<u32> {
     input.split_whitespace().filter_map(|x| u32::from_str(x).ok()).collect()
 }
 
-fn explore_loop(buckets: &mut Vec<u32>) -> (usize, usize) {
-    let mut loop_started = false;
-    let mut steps_until_repeat = 0;
-    let mut steps_in_loop = 0;
-    let mut states = HashSet::new();
-    states.insert(buckets.clone());
-    let mut first_repeated_state = buckets.clone();
-
-    loop {
-        let largest_index = largest_bucket_index(&buckets);
-
-        redistribute(buckets, largest_index);
-
-        if loop_started {
-            steps_in_loop += 1;
-            if *buckets == first_repeated_state {
-                break;
-            }
-        } else {
-            steps_until_repeat += 1;
-            if states.contains(buckets) {
-                loop_started = true;
-                first_repeated_state = buckets.clone();
-            }
-        }
-        states.insert(buckets.clone());
-    }
+fn explore_loop(buckets: &Vec<u32>) -> (usize, usize) {
+    let states = successors(Some(buckets.clone()), |b| {
+        let mut next = b.clone();
+        let largest_index = largest_bucket_index(&next);
+        redistribute(&mut next, largest_index);
+        Some(next)
+    });
+    let cycle = hashed(states).expect("Redistributing must loop eventually");
 
-    (steps_until_repeat, steps_in_loop)
+    (cycle.first_repeat(), cycle.period)
 }
 
 /// Conducts a single redistribute step
@@ -102,8 +83,8 @@ mod tests {
 
         #[test]
         fn full_run() {
-            let mut buckets = vec![0, 2, 7, 0];
-            let (until_loop, in_loop) = explore_loop(&mut buckets);
+            let buckets = vec![0, 2, 7, 0];
+            let (until_loop, in_loop) = explore_loop(&buckets);
             assert_eq!(until_loop, 5, "Steps until loop");
             assert_eq!(in_loop, 4, "Steps in loop");
         }
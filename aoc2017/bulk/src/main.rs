@@ -1,3 +1,5 @@
+extern crate aoc_common;
+extern crate assembly;
 extern crate clap;
 #[macro_use]
 extern crate lazy_static;
@@ -23,13 +25,16 @@ mod day16;
 mod day17;
 mod day18;
 mod day19;
-// mod day20;
+mod day20;
 mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 
 use clap::{App, Arg};
 use std::str::FromStr;
 use std::time::Instant;
-use util::asmillis::AsMillis;
 
 fn main() {
     let matches = App::new("Advent of Code 2017")
@@ -47,6 +52,17 @@ fn main() {
                 .required(false)
                 .index(2),
         )
+        .arg(
+            Arg::with_name("STEP")
+                .long("step")
+                .help("Steps through the program interactively, for the days which run one"),
+        )
+        .arg(
+            Arg::with_name("PART2")
+                .long("part2")
+                .requires("STEP")
+                .help("Steps through part two instead of part one"),
+        )
         .get_matches();
 
     let day = u8::from_str(matches.value_of("DAY").expect("Day must be specified"))
@@ -86,11 +102,15 @@ fn main() {
         15 => day15::go(),
         16 => day16::go(),
         17 => day17::go(),
-        18 => day18::go(),
+        18 => day18::go(matches.is_present("STEP")),
         19 => day19::go(),
-        // 20 => day20::go(),
+        20 => day20::go(),
         21 => day21::go(),
-        _ => println!("I don't know how to do that day yet"),
+        22 => day22::go(),
+        23 => day23::go(matches.is_present("STEP"), matches.is_present("PART2")),
+        24 => day24::go(),
+        25 => day25::go(),
+        _ => unreachable!(),
     }
 
     let time_taken = start.elapsed().as_millis();
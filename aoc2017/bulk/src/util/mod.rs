@@ -1,11 +1,9 @@
-pub mod asmillis;
 pub mod pairs;
 pub mod knothash;
 
 pub use self::knothash::knot_hash;
 
 use std::time::Instant;
-use self::asmillis::AsMillis;
 
 pub fn char_to_digit(c: char) -> Option<u8> {
     match c {
@@ -48,7 +46,7 @@ where
     W: Fn() -> R,
 {
     let timer = Instant::now();
-    (work(), timer.elapsed().as_millis())
+    (work(), timer.elapsed().as_millis() as u64)
 }
 
 pub fn timed_repeatedly<W, R>(count: usize, work: W) -> (R, u64)
@@ -56,5 +54,5 @@ where
     W: Fn() -> R,
 {
     let timer = Instant::now();
-    (repeatedly(count, || work()), timer.elapsed().as_millis())
+    (repeatedly(count, || work()), timer.elapsed().as_millis() as u64)
 }
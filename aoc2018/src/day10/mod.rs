@@ -1,5 +1,6 @@
 use crate::day::Day;
 use crate::util::ErrString;
+use aoc_common::ocr::recognise_points;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::fmt;
@@ -31,22 +32,26 @@ impl Day for Day10 {
 
         let mut iteration = 0;
         let mut last_width = i32::MAX;
-        let mut last_render = "".to_owned();
+        let mut last_positions = vec![];
 
         loop {
             points.step();
             if points.width() > last_width {
                 break;
             }
-            // no point wasting time rendering when it's still way too wide
+            // no point keeping hold of them when it's still way too wide to be the message
             if points.width() < 80 {
-                last_render = points.to_string();
+                last_positions = points.positions().collect();
             }
             iteration += 1;
             last_width = points.width();
         }
 
-        sender.send(format!("{}", last_render)).unwrap();
+        let message = match recognise_points(last_positions) {
+            Ok(message) => message,
+            Err(e) => format!("Unable to read the message: {}", e),
+        };
+        sender.send(message).unwrap();
 
         self.iteration = iteration;
     }
@@ -179,6 +184,12 @@ impl Points {
         (self.max_x - self.min_x).abs()
     }
 
+    fn positions(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.points
+            .iter()
+            .map(|p| (p.position.x as i64, p.position.y as i64))
+    }
+
     fn has_point_at(&self, x: i32, y: i32) -> bool {
         self.points
             .iter()
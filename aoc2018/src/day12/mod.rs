@@ -4,6 +4,7 @@ mod rule;
 use self::plants::Plants;
 use self::rule::Rule;
 use crate::day::Day;
+use aoc_common::cycle::nth_state_translated;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::mpsc::Sender;
@@ -38,8 +39,8 @@ impl Default for Day12 {
 
 impl Day for Day12 {
     fn part1(&mut self, sender: &Sender<String>) {
-        let result = run_until_generation(&self.initial_state, &self.rules, 20, sender)
-            .sum_all_pots_with_plant();
+        let result =
+            run_until_generation(&self.initial_state, &self.rules, 20).sum_all_pots_with_plant();
 
         sender
             .send(format!("Sum of all pots with plant {}", result))
@@ -47,7 +48,7 @@ impl Day for Day12 {
     }
 
     fn part2(&mut self, sender: &Sender<String>) {
-        let result = run_until_generation(&self.initial_state, &self.rules, 50_000_000_000, sender)
+        let result = run_until_generation(&self.initial_state, &self.rules, 50_000_000_000)
             .sum_all_pots_with_plant();
 
         sender
@@ -56,67 +57,44 @@ impl Day for Day12 {
     }
 }
 
+/// Plant patterns settle down into shapes which drift along the pots, so once a shape has been
+/// seen before we can jump straight to the generation we want.
 fn run_until_generation(
     plants: &Plants,
     rules: &HashMap<Vec<bool>, bool>,
     generation: usize,
-    sender: &Sender<String>,
 ) -> Plants {
-    let mut seen_at_generation: HashMap<String, (usize, Option<i64>)> = HashMap::new();
-    let mut new_plants: Plants = plants.clone();
-    let mut g: usize = 0;
-
-    loop {
-        new_plants = new_plants.apply_rules(rules);
-
-        let new_plants_str = new_plants.to_string();
-        let lowest_pot = new_plants.lowest_pot_with_plant();
-        if seen_at_generation.contains_key(&new_plants_str) {
-            let (last_seen, last_lowest_pot) = seen_at_generation[&new_plants_str];
-            let distance = g - last_seen;
-            let skip = usize::max(((generation - g) / distance) * distance, 1);
-            let shift = lowest_pot.unwrap_or(0) - last_lowest_pot.unwrap_or(0);
-            let total_shift = (shift * skip as i64) - 1;
-
-            sender
-                .send(format!(
-                    "Hey! I've seen this (generation {}) before! It was back in generation {}
-Then it started at {} but now it starts at {}
-I speculate that there's a {}-generation cycle with a {}-pot shift
-Skipping forward {} generations and shifting {}",
-                    g,
-                    last_seen,
-                    last_lowest_pot.unwrap_or(0),
-                    lowest_pot.unwrap_or(0),
-                    distance,
-                    shift,
-                    skip,
-                    total_shift
-                ))
-                .unwrap();
-
-            g += skip;
-            new_plants = new_plants.shift(total_shift);
-        } else {
-            seen_at_generation.insert(new_plants_str, (g, lowest_pot));
-            g += 1;
-        }
-
-        if g % 100_000 == 0 {
-            sender
-                .send(format!(
-                    "Generation {}/{}: {}%",
-                    g,
-                    generation,
-                    (g * 100) / generation
-                ))
-                .unwrap();
-        }
-
-        if g >= generation {
-            break;
-        }
-    }
+    nth_state_translated(
+        plants.clone(),
+        |p| p.apply_rules(rules),
+        generation,
+        Plants::normalised,
+        |p, shift| p.shift(shift),
+    )
+}
 
-    new_plants
+#[test]
+fn example_twenty_generations() {
+    let rules = "...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #"
+        .lines()
+        .map(|l| Rule::from_str(l).expect("Rule should parse"))
+        .map(|r| (r.pattern(), r.plant()))
+        .collect();
+    let plants = Plants::from_str("#..#.#..##......###...###").expect("Plants should parse");
+
+    let result = run_until_generation(&plants, &rules, 20);
+    assert_eq!(result.sum_all_pots_with_plant(), 325);
 }
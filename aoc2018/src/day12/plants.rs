@@ -41,6 +41,17 @@ impl Plants {
         Plants { plants: new_plants }
     }
 
+    /// The pots from the first plant to the last, and where the first plant is, so that the same
+    /// shape of plants compares equal wherever it's drifted to.
+    pub fn normalised(&self) -> (Vec<bool>, i64) {
+        let lowest_pot = self.lowest_pot_with_plant().unwrap_or(0);
+        let highest_pot = self.plants.iter().max().map_or(lowest_pot - 1, |x| *x);
+        let pots = (lowest_pot..=highest_pot)
+            .map(|pot| self.plants.contains(&pot))
+            .collect();
+        (pots, lowest_pot)
+    }
+
     fn get_pot_surround(&self, pot: i64) -> Vec<bool> {
         let mut result = vec![];
         for pot_number in pot - 2..=pot + 2 {
@@ -110,6 +121,17 @@ fn sum_pots() {
     assert_eq!(plants.sum_all_pots_with_plant(), 325);
 }
 
+#[test]
+fn normalised_ignores_drift() {
+    let plants = Plants::from_str("..#.##").expect("Should parse!");
+    assert_eq!(plants.normalised(), (vec![true, false, true, true], 2));
+    assert_eq!(
+        plants.shift(-5).normalised(),
+        (vec![true, false, true, true], -3)
+    );
+    assert_eq!(Plants::from_str("...").unwrap().normalised(), (vec![], 0));
+}
+
 #[test]
 fn pot_surround_all_positive_inside() {
     let plants = Plants {
@@ -5,11 +5,20 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::mpsc::Sender;
 
-pub struct Day13;
+pub struct Day13 {
+    /// A tick to draw once part two has run the carts to the end.
+    replay: Option<usize>,
+}
 
-impl Default for Day13 {
-    fn default() -> Day13 {
-        Day13 {}
+impl Day13 {
+    pub fn new(replay: Option<&str>) -> Result<Day13, String> {
+        let replay = match replay {
+            Some(tick) => Some(
+                usize::from_str(tick).map_err(|_| format!("\"{}\" isn't a tick number", tick))?,
+            ),
+            None => None,
+        };
+        Ok(Day13 { replay })
     }
 }
 
@@ -41,10 +50,29 @@ impl Day for Day13 {
             Err(msg) => sender.send(msg).unwrap(),
             Ok(pr) => {
                 let mut railway = Railway::from(pr);
-                let pos = railway.last_cart_left();
-                sender
-                    .send(format!("Last cart left at {},{}", pos.0, pos.1))
-                    .unwrap();
+                match railway.last_cart_left() {
+                    Some(pos) => sender
+                        .send(format!(
+                            "Last cart left at {},{} after {} ticks and {} collisions",
+                            pos.0,
+                            pos.1,
+                            railway.ticks(),
+                            railway.collisions().len()
+                        ))
+                        .unwrap(),
+                    None => sender.send("No carts survived".into()).unwrap(),
+                }
+
+                if let Some(tick) = self.replay {
+                    match railway.render(tick) {
+                        Some(frame) => sender
+                            .send(format!("After {} ticks:\n{}", tick, frame))
+                            .unwrap(),
+                        None => sender
+                            .send(format!("There were only {} ticks", railway.ticks()))
+                            .unwrap(),
+                    }
+                }
             }
         }
     }
@@ -89,6 +117,18 @@ enum Track {
     Intersection,
 }
 
+impl Track {
+    fn to_char(self) -> char {
+        match self {
+            Track::Vertical => '|',
+            Track::Horizontal => '-',
+            Track::CornerNE | Track::CornerSW => '\\',
+            Track::CornerNW | Track::CornerSE => '/',
+            Track::Intersection => '+',
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Direction {
     North,
@@ -98,6 +138,15 @@ enum Direction {
 }
 
 impl Direction {
+    fn to_char(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::South => 'v',
+            Direction::East => '>',
+            Direction::West => '<',
+        }
+    }
+
     fn turn(&self, turn: &IntersectionState) -> Direction {
         match (turn, *self) {
             (IntersectionState::Left, Direction::North) => Direction::West,
@@ -180,9 +229,31 @@ struct Cart {
     state: IntersectionState,
 }
 
+/// Where a cart was and what it was doing at the end of a tick.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct CartSnapshot {
+    id: usize,
+    position: (usize, usize),
+    facing: Direction,
+    state: IntersectionState,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Collision {
+    /// The tick it happened during, counting from 1.
+    tick: usize,
+    position: (usize, usize),
+    /// The ids of the cart which moved and the cart it hit.
+    carts: (usize, usize),
+}
+
 struct Railway {
     track: HashMap<(usize, usize), Track>,
-    carts: HashMap<(usize, usize), Cart>,
+    // carts are numbered in reading order of where they start
+    carts: HashMap<(usize, usize), (usize, Cart)>,
+    // the carts at the start and then after each tick
+    history: Vec<Vec<CartSnapshot>>,
+    collisions: Vec<Collision>,
 }
 
 impl Railway {
@@ -190,52 +261,113 @@ impl Railway {
         self.track.get(&pos).map(|x| x.clone())
     }
 
+    #[cfg(test)]
     fn cart_at(&self, pos: (usize, usize)) -> Option<Cart> {
-        self.carts.get(&pos).map(|x| x.clone())
+        self.carts.get(&pos).map(|(_, cart)| cart.clone())
+    }
+
+    /// How many ticks have been run so far.
+    fn ticks(&self) -> usize {
+        self.history.len() - 1
     }
 
-    /// All carts move one tick
+    fn collisions(&self) -> &[Collision] {
+        &self.collisions
+    }
+
+    /// The carts after `tick` ticks, in reading order.
+    fn carts_after(&self, tick: usize) -> Option<&[CartSnapshot]> {
+        self.history.get(tick).map(|carts| carts.as_slice())
+    }
+
+    /// All carts move one tick, and any which crash are taken off the track.
     /// Returns first collision location if there is one
-    fn step(&mut self, remove_on_collision: bool) -> Option<(usize, usize)> {
+    fn step(&mut self) -> Option<(usize, usize)> {
         #[cfg(test)]
         println!("Starting new step");
 
+        let tick = self.history.len();
+        let mut first_collision = None;
         let carts = self.carts_in_order();
 
         for cart_pos in carts {
-            let cart_record = self.cart_at(cart_pos);
-            if cart_record.is_none() {
+            let (id, cart) = match self.carts.remove(&cart_pos) {
+                Some(cart) => cart,
                 // this cart was removed in a collision already
-                continue;
-            }
-            let cart_record = cart_record.expect("There really should be a cart here");
+                None => continue,
+            };
 
             let (new_pos, new_direction, new_state) =
-                self.advance_position(cart_pos, cart_record.facing, cart_record.state);
-
-            self.carts.remove(&cart_pos);
-
-            if self
-                .carts
-                .insert(
-                    new_pos,
-                    Cart {
-                        facing: new_direction,
-                        state: new_state,
-                    },
-                )
-                .is_some()
-            {
-                // COLLISION!!!!
-                if remove_on_collision {
-                    self.carts.remove(&new_pos);
-                } else {
-                    return Some(new_pos);
+                self.advance_position(cart_pos, cart.facing, cart.state);
+
+            match self.carts.remove(&new_pos) {
+                Some((other, _)) => {
+                    // COLLISION!!!!
+                    self.collisions.push(Collision {
+                        tick,
+                        position: new_pos,
+                        carts: (id, other),
+                    });
+                    first_collision.get_or_insert(new_pos);
+                }
+                None => {
+                    self.carts.insert(
+                        new_pos,
+                        (
+                            id,
+                            Cart {
+                                facing: new_direction,
+                                state: new_state,
+                            },
+                        ),
+                    );
                 }
             }
         }
 
-        None
+        self.history.push(self.snapshot());
+
+        first_collision
+    }
+
+    fn snapshot(&self) -> Vec<CartSnapshot> {
+        self.carts_in_order()
+            .into_iter()
+            .map(|position| {
+                let (id, cart) = &self.carts[&position];
+                CartSnapshot {
+                    id: *id,
+                    position,
+                    facing: cart.facing,
+                    state: cart.state.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Draws the track as it was after `tick` ticks, with the carts on it and an `X` wherever
+    /// carts crashed during that tick.
+    fn render(&self, tick: usize) -> Option<String> {
+        let carts = self.carts_after(tick)?;
+        let width = self.track.keys().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let height = self.track.keys().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+        let mut rows = vec![vec![' '; width]; height];
+        for ((x, y), track) in self.track.iter() {
+            rows[*y][*x] = track.to_char();
+        }
+        for cart in carts {
+            rows[cart.position.1][cart.position.0] = cart.facing.to_char();
+        }
+        for collision in self.collisions.iter().filter(|c| c.tick == tick) {
+            rows[collision.position.1][collision.position.0] = 'X';
+        }
+
+        Some(
+            rows.into_iter()
+                .map(|row| row.into_iter().collect::<String>().trim_end().to_string() + "\n")
+                .collect(),
+        )
     }
 
     fn carts_in_order(&self) -> Vec<(usize, usize)> {
@@ -344,29 +476,28 @@ impl Railway {
                 println!("Starting step {}", step);
             }
 
-            match self.step(false) {
+            match self.step() {
                 Some(pos) => return pos,
                 None => (),
             };
         }
     }
 
-    fn last_cart_left(&mut self) -> (usize, usize) {
+    /// Runs until there's at most one cart left, and returns where it is if there is one.
+    fn last_cart_left(&mut self) -> Option<(usize, usize)> {
         #[cfg(test)]
         let mut step = 0;
-        loop {
+        while self.carts.len() > 1 {
             #[cfg(test)]
             {
                 step += 1;
                 println!("Starting step {}", step);
             }
 
-            self.step(true);
-
-            if self.carts.len() == 1 {
-                return self.carts.keys().cloned().next().unwrap();
-            }
+            self.step();
         }
+
+        self.carts.keys().cloned().next()
     }
 }
 
@@ -416,22 +547,32 @@ impl From<ParsedRailway> for Railway {
             }
         }
 
-        Railway {
+        let mut starts = parsed.carts.into_iter().collect::<Vec<_>>();
+        starts.sort_by_key(|((x, y), _)| (*y, *x));
+
+        let mut railway = Railway {
             track,
-            carts: parsed
-                .carts
+            carts: starts
                 .into_iter()
-                .map(|(p, d)| {
+                .enumerate()
+                .map(|(id, (p, d))| {
                     (
                         p,
-                        Cart {
-                            facing: d,
-                            state: IntersectionState::Left,
-                        },
+                        (
+                            id,
+                            Cart {
+                                facing: d,
+                                state: IntersectionState::Left,
+                            },
+                        ),
                     )
                 })
                 .collect(),
-        }
+            history: Vec::new(),
+            collisions: Vec::new(),
+        };
+        railway.history.push(railway.snapshot());
+        railway
     }
 }
 
@@ -490,7 +631,7 @@ v    |
     let track = ParsedRailway::from_str(track).expect("Track should parse");
     let mut track = Railway::from(track);
 
-    track.step(false);
+    track.step();
     assert_eq!(track.cart_at((0, 2)), None);
     assert_eq!(
         track.cart_at((0, 3)),
@@ -500,7 +641,7 @@ v    |
         })
     );
 
-    track.step(false);
+    track.step();
     assert_eq!(
         track.cart_at((0, 4)),
         Some(Cart {
@@ -509,7 +650,7 @@ v    |
         })
     );
 
-    track.step(false);
+    track.step();
     assert_eq!(
         track.cart_at((0, 5)),
         Some(Cart {
@@ -518,7 +659,7 @@ v    |
         })
     );
 
-    track.step(false);
+    track.step();
     assert_eq!(
         track.cart_at((1, 5)),
         Some(Cart {
@@ -536,8 +677,8 @@ fn simple_intersection() {
     |";
     let mut railway = Railway::from(ParsedRailway::from_str(track).expect("Track should parse"));
 
-    railway.step(false);
-    railway.step(false);
+    railway.step();
+    railway.step();
     assert_eq!(
         railway.cart_at((3, 1)),
         Some(Cart {
@@ -559,10 +700,10 @@ fn simple_collision() {
     let track = r"->--<-";
     let mut railway = Railway::from(ParsedRailway::from_str(track).expect("Track should parse"));
 
-    let step = railway.step(false);
+    let step = railway.step();
     assert_eq!(step, None);
 
-    let step = railway.step(false);
+    let step = railway.step();
     assert_eq!(step, Some((3, 0)));
 }
 
@@ -582,7 +723,7 @@ fn test_first_failure() {
 
     let mut railway = Railway::from(ParsedRailway::from_str(track).expect("Track should parse"));
 
-    let _step = railway.step(false);
+    let _step = railway.step();
     assert_eq!(
         railway.cart_at((2, 1)),
         Some(Cart {
@@ -590,7 +731,7 @@ fn test_first_failure() {
             state: IntersectionState::Left
         })
     );
-    let _step = railway.step(false);
+    let _step = railway.step();
     assert_eq!(
         railway.cart_at((3, 1)),
         Some(Cart {
@@ -598,7 +739,7 @@ fn test_first_failure() {
             state: IntersectionState::Forward
         })
     );
-    let _step = railway.step(false);
+    let _step = railway.step();
     assert_eq!(
         railway.cart_at((3, 0)),
         Some(Cart {
@@ -607,3 +748,117 @@ fn test_first_failure() {
         })
     );
 }
+
+#[cfg(test)]
+const FIRST_EXAMPLE: &str = r"/->-\
+|   |  /----\
+| /-+--+-\  |
+| | |  | v  |
+\-+-/  \-+--/
+  \------/";
+
+#[test]
+fn first_example_frames() {
+    let mut railway =
+        Railway::from(ParsedRailway::from_str(FIRST_EXAMPLE).expect("Track should parse"));
+
+    assert_eq!(railway.first_collision(), (7, 3));
+    assert_eq!(railway.ticks(), 14);
+    assert_eq!(
+        railway.render(0),
+        Some(FIRST_EXAMPLE.to_string() + "\n"),
+        "The first frame should look like the input"
+    );
+    assert_eq!(
+        railway.render(1).unwrap(),
+        r"/-->\
+|   |  /----\
+| /-+--+-\  |
+| | |  | |  |
+\-+-/  \->--/
+  \------/
+"
+    );
+    assert_eq!(
+        railway.render(14).unwrap(),
+        r"/---\
+|   |  /----\
+| /-+--+-\  |
+| | |  X |  |
+\-+-/  \-+--/
+  \------/
+"
+    );
+    assert_eq!(railway.render(15), None);
+    assert_eq!(
+        railway.collisions(),
+        &[Collision {
+            tick: 14,
+            position: (7, 3),
+            carts: (0, 1)
+        }]
+    );
+}
+
+#[test]
+fn second_example_collision_log() {
+    let track = r"/>-<\
+|   |
+| /<+-\
+| | | v
+\>+</ |
+  |   ^
+  \<->/";
+    let mut railway = Railway::from(ParsedRailway::from_str(track).expect("Track should parse"));
+
+    assert_eq!(railway.last_cart_left(), Some((6, 4)));
+    assert_eq!(railway.collisions().len(), 4);
+    assert!(railway.collisions()[..3].iter().all(|c| c.tick == 1));
+    assert_eq!(
+        railway.carts_after(railway.ticks()),
+        Some(
+            &[CartSnapshot {
+                id: 8,
+                position: (6, 4),
+                facing: Direction::North,
+                state: IntersectionState::Left
+            }][..]
+        )
+    );
+    assert_eq!(railway.ticks(), 3);
+    assert_eq!(railway.render(0), Some(track.to_string() + "\n"));
+    assert_eq!(
+        railway.render(1).unwrap(),
+        r"/-X-\
+|   |
+| v-+-\
+| | | |
+\-X-/ X
+  |   |
+  ^---^
+"
+    );
+    assert_eq!(
+        railway.render(2).unwrap(),
+        r"/---\
+|   |
+| /-+-\
+| v | |
+\-+-/ |
+  ^   ^
+  \---/
+"
+    );
+    assert_eq!(
+        railway.render(3).unwrap(),
+        r"/---\
+|   |
+| /-+-\
+| | | |
+\-X-/ ^
+  |   |
+  \---/
+"
+    );
+    assert_eq!(railway.render(4), None);
+}
@@ -0,0 +1,171 @@
+use super::map::{Map, Race};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub rounds: usize,
+    pub winner: Race,
+    pub remaining_health: i32,
+}
+
+impl Outcome {
+    pub fn score(&self) -> i32 {
+        self.rounds as i32 * self.remaining_health
+    }
+}
+
+pub struct Battle {
+    map: Map,
+    rounds: usize,
+}
+
+impl Battle {
+    pub fn new(map: Map) -> Battle {
+        Battle { map, rounds: 0 }
+    }
+
+    /// Fights to the end.
+    pub fn fight(self) -> Outcome {
+        self.fight_with(|_, _| {})
+    }
+
+    /// Fights to the end, calling `after_round` with the number of rounds completed and the
+    /// map after each full round, which is handy for comparing against the puzzle's pictures.
+    pub fn fight_with(mut self, mut after_round: impl FnMut(usize, &Map)) -> Outcome {
+        while self.round() {
+            after_round(self.rounds, &self.map);
+        }
+        self.outcome()
+    }
+
+    /// Fights to the end, giving up as soon as an elf dies.
+    pub fn fight_without_elf_losses(mut self) -> Option<Outcome> {
+        let elves = self.map.count_living(Race::Elf);
+        loop {
+            let finished = !self.round();
+            if self.map.count_living(Race::Elf) < elves {
+                return None;
+            }
+            if finished {
+                return Some(self.outcome());
+            }
+        }
+    }
+
+    /// Plays one round, returning false if the fight ended part way through it.
+    pub fn round(&mut self) -> bool {
+        for unit in self.map.living_units() {
+            // it may have been killed earlier in the round
+            if !self.map.unit(unit).is_alive() {
+                continue;
+            }
+            if !self.take_turn(unit) {
+                return false;
+            }
+        }
+        self.rounds += 1;
+        true
+    }
+
+    fn outcome(&self) -> Outcome {
+        let survivors = self.map.living_units();
+        Outcome {
+            rounds: self.rounds,
+            winner: self.map.unit(survivors[0]).race,
+            remaining_health: survivors.iter().map(|u| self.map.unit(*u).health).sum(),
+        }
+    }
+
+    /// Moves and attacks with `unit`, returning false if it has no enemies left.
+    fn take_turn(&mut self, unit: usize) -> bool {
+        let me = self.map.unit(unit).clone();
+        let enemies = self
+            .map
+            .living_units()
+            .into_iter()
+            .filter(|u| self.map.unit(*u).is_enemy_of(&me))
+            .collect::<Vec<_>>();
+        if enemies.is_empty() {
+            return false;
+        }
+
+        if self.adjacent_enemy(unit).is_none() {
+            let in_range = enemies
+                .iter()
+                .flat_map(|e| self.map.neighbours(self.map.unit(*e).index))
+                .filter(|i| self.map.is_free(*i))
+                .collect::<Vec<_>>();
+            if let Some(step) = self.choose_step(me.index, &in_range) {
+                self.map.unit_mut(unit).index = step;
+            }
+        }
+
+        if let Some(target) = self.adjacent_enemy(unit) {
+            let attack = self.map.unit(unit).attack;
+            self.map.unit_mut(target).health -= attack;
+        }
+
+        true
+    }
+
+    /// The enemy next to `unit` with the least health, first in reading order on a tie.
+    fn adjacent_enemy(&self, unit: usize) -> Option<usize> {
+        let me = self.map.unit(unit);
+        self.map
+            .neighbours(me.index)
+            .into_iter()
+            .filter_map(|i| self.map.unit_at(i))
+            .filter(|u| self.map.unit(*u).is_enemy_of(me))
+            .min_by_key(|u| (self.map.unit(*u).health, self.map.unit(*u).index))
+    }
+
+    /// The first step from `from` towards the nearest of `targets`, where ties for the nearest
+    /// target and then for the step are both broken by reading order.
+    fn choose_step(&self, from: usize, targets: &[usize]) -> Option<usize> {
+        let distances = self.distances_from(from);
+        let target = targets
+            .iter()
+            .filter_map(|t| distances[*t].map(|d| (d, *t)))
+            .min()?
+            .1;
+
+        let back = self.distances_from(target);
+        self.map
+            .neighbours(from)
+            .into_iter()
+            .filter_map(|n| back[n].map(|d| (d, n)))
+            .min()
+            .map(|(_, n)| n)
+    }
+
+    /// How many steps it takes to reach each square from `start` without going through walls
+    /// or units.
+    fn distances_from(&self, start: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.map.width() * self.map.height()];
+        distances[start] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back((start, 0));
+        while let Some((index, distance)) = queue.pop_front() {
+            for next in self.map.neighbours(index) {
+                if distances[next].is_none() && self.map.is_free(next) {
+                    distances[next] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        distances
+    }
+}
+
+/// Finds the smallest attack power which lets the elves win without losing anyone, along with
+/// the outcome of that fight.
+pub fn elf_power_needed(map: &Map) -> Option<(i32, Outcome)> {
+    // from 200 up every blow kills, so more power than that can't help
+    (4..=200).find_map(|attack| {
+        let mut map = map.clone();
+        map.set_attack(Race::Elf, attack);
+        Battle::new(map)
+            .fight_without_elf_losses()
+            .map(|outcome| (attack, outcome))
+    })
+}
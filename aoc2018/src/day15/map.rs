@@ -1,52 +1,160 @@
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 
+const STARTING_HEALTH: i32 = 200;
+const STARTING_ATTACK: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tile {
     Wall,
     Open,
 }
 
-pub enum Unit {
-    Elf { health: usize, index: usize },
-    Goblin { health: usize, index: usize },
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Race {
+    Elf,
+    Goblin,
 }
 
-impl Unit {
-    fn index(&self) -> usize {
+impl Display for Race {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match self {
-            Unit::Elf { index, .. } => *index,
-            Unit::Goblin { index, .. } => *index,
+            Race::Elf => write!(fmt, "E"),
+            Race::Goblin => write!(fmt, "G"),
         }
     }
+}
 
-    fn is_goblin(&self) -> bool {
-        match self {
-            Unit::Elf { .. } => false,
-            Unit::Goblin { .. } => true,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unit {
+    pub race: Race,
+    pub health: i32,
+    pub attack: i32,
+    pub index: usize,
+}
+
+impl Unit {
+    fn new(race: Race, index: usize) -> Unit {
+        Unit {
+            race,
+            health: STARTING_HEALTH,
+            attack: STARTING_ATTACK,
+            index,
         }
     }
 
-    fn is_elf(&self) -> bool {
-        !self.is_goblin()
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
     }
 
-    fn identify_targets<'a>(&self, map: &'a Map) -> Vec<&'a Unit> {
-        match self {
-            Unit::Elf { .. } => map.units.iter().filter(|u| u.is_goblin()).collect(),
-            Unit::Goblin { .. } => map.units.iter().filter(|u| u.is_elf()).collect(),
-        }
+    pub fn is_enemy_of(&self, other: &Unit) -> bool {
+        self.race != other.race
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct Map {
     tiles: Vec<Tile>,
     width: usize,
     height: usize,
+    // dead units stay here so that a unit's number never changes during a fight
     units: Vec<Unit>,
 }
 
 impl Map {
-    fn sort_units(&mut self) {
-        self.units.sort_by_key(|u| u.index());
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set_attack(&mut self, race: Race, attack: i32) {
+        for unit in self.units.iter_mut().filter(|u| u.race == race) {
+            unit.attack = attack;
+        }
+    }
+
+    pub fn unit(&self, unit: usize) -> &Unit {
+        &self.units[unit]
+    }
+
+    pub fn unit_mut(&mut self, unit: usize) -> &mut Unit {
+        &mut self.units[unit]
+    }
+
+    /// The numbers of the living units, in reading order.
+    pub fn living_units(&self) -> Vec<usize> {
+        let mut living = (0..self.units.len())
+            .filter(|u| self.units[*u].is_alive())
+            .collect::<Vec<_>>();
+        living.sort_by_key(|u| self.units[*u].index);
+        living
+    }
+
+    pub fn count_living(&self, race: Race) -> usize {
+        self.units
+            .iter()
+            .filter(|u| u.race == race && u.is_alive())
+            .count()
+    }
+
+    /// The number of the living unit at `index`, if there is one.
+    pub fn unit_at(&self, index: usize) -> Option<usize> {
+        self.units
+            .iter()
+            .position(|u| u.index == index && u.is_alive())
+    }
+
+    /// Whether a unit could step onto `index`.
+    pub fn is_free(&self, index: usize) -> bool {
+        self.tiles[index] == Tile::Open && self.unit_at(index).is_none()
+    }
+
+    /// The squares next to `index`, in reading order.
+    pub fn neighbours(&self, index: usize) -> Vec<usize> {
+        let (x, y) = (index % self.width, index / self.width);
+        let mut result = Vec::with_capacity(4);
+        if y > 0 {
+            result.push(index - self.width);
+        }
+        if x > 0 {
+            result.push(index - 1);
+        }
+        if x + 1 < self.width {
+            result.push(index + 1);
+        }
+        if y + 1 < self.height {
+            result.push(index + self.width);
+        }
+        result
+    }
+}
+
+/// The map as the puzzle draws it, with each row followed by the health of the units on it.
+impl Display for Map {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let mut healths = Vec::new();
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                match (self.unit_at(index), self.tiles[index]) {
+                    (Some(unit), _) => {
+                        let unit = &self.units[unit];
+                        healths.push(format!("{}({})", unit.race, unit.health));
+                        write!(fmt, "{}", unit.race)?;
+                    }
+                    (None, Tile::Wall) => write!(fmt, "#")?,
+                    (None, Tile::Open) => write!(fmt, ".")?,
+                }
+            }
+            if !healths.is_empty() {
+                write!(fmt, "   {}", healths.join(", "))?;
+            }
+            writeln!(fmt)?;
+        }
+        Ok(())
     }
 }
 
@@ -80,17 +188,15 @@ impl FromStr for Map {
                     '.' => result.tiles.push(Tile::Open),
                     'G' => {
                         result.tiles.push(Tile::Open);
-                        result.units.push(Unit::Goblin {
-                            health: 300,
-                            index: result.tiles.len() - 1,
-                        })
+                        result
+                            .units
+                            .push(Unit::new(Race::Goblin, result.tiles.len() - 1))
                     }
                     'E' => {
                         result.tiles.push(Tile::Open);
-                        result.units.push(Unit::Elf {
-                            health: 300,
-                            index: result.tiles.len() - 1,
-                        })
+                        result
+                            .units
+                            .push(Unit::new(Race::Elf, result.tiles.len() - 1))
                     }
                     _ => return Err(format!("Unexpected map character {}", c)),
                 }
@@ -100,3 +206,24 @@ impl FromStr for Map {
         Ok(result)
     }
 }
+
+#[test]
+fn map_displays_as_parsed() {
+    let input = "#######
+#.G.E.#
+#E.G.E#
+#.G.E.#
+#######";
+    let map = Map::from_str(input).unwrap();
+    assert_eq!(
+        map.to_string(),
+        "#######
+#.G.E.#   G(200), E(200)
+#E.G.E#   E(200), G(200), E(200)
+#.G.E.#   G(200), E(200)
+#######
+"
+    );
+    assert_eq!(map.living_units().len(), 7);
+    assert_eq!(map.neighbours(8), vec![1, 7, 9, 15]);
+}
@@ -1 +1,238 @@
+mod combat;
 mod map;
+
+use self::combat::{elf_power_needed, Battle};
+use self::map::Map;
+use crate::day::Day;
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+
+pub struct Day15 {
+    map: Map,
+}
+
+impl Day15 {
+    pub fn new() -> Result<Day15, String> {
+        Ok(Day15 {
+            map: Map::from_str(include_str!("input.txt"))?,
+        })
+    }
+}
+
+impl Day for Day15 {
+    fn part1(&mut self, sender: &Sender<String>) {
+        let outcome = Battle::new(self.map.clone()).fight();
+        sender
+            .send(format!(
+                "The {:?}s win after {} full rounds with {} health left, scoring {}",
+                outcome.winner,
+                outcome.rounds,
+                outcome.remaining_health,
+                outcome.score()
+            ))
+            .unwrap();
+    }
+
+    fn part2(&mut self, sender: &Sender<String>) {
+        match elf_power_needed(&self.map) {
+            Some((attack, outcome)) => sender
+                .send(format!(
+                    "The elves need attack power {} to win without losses, scoring {}",
+                    attack,
+                    outcome.score()
+                ))
+                .unwrap(),
+            None => sender
+                .send("The elves can't win without losses".into())
+                .unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::map::Race;
+    use super::*;
+
+    fn fight(input: &str) -> combat::Outcome {
+        let map = Map::from_str(input).expect("Example should parse");
+        Battle::new(map).fight()
+    }
+
+    const FIRST_EXAMPLE: &str = "#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######";
+
+    #[test]
+    fn first_example_round_by_round() {
+        let mut dumps = Vec::new();
+        let map = Map::from_str(FIRST_EXAMPLE).unwrap();
+        let outcome =
+            Battle::new(map).fight_with(|round, map| dumps.push((round, map.to_string())));
+
+        assert_eq!(
+            dumps[0],
+            (
+                1,
+                "#######
+#..G..#   G(200)
+#...EG#   E(197), G(197)
+#.#G#G#   G(200), G(197)
+#...#E#   E(197)
+#.....#
+#######
+"
+                .to_string()
+            )
+        );
+        assert_eq!(
+            dumps[46],
+            (
+                47,
+                "#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(59)
+#...#.#
+#....G#   G(200)
+#######
+"
+                .to_string()
+            )
+        );
+        assert_eq!(outcome.rounds, 47);
+        assert_eq!(outcome.remaining_health, 590);
+        assert_eq!(outcome.winner, Race::Goblin);
+        assert_eq!(outcome.score(), 27730);
+    }
+
+    #[test]
+    fn more_examples() {
+        let examples = [
+            (
+                "#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######",
+                36334,
+            ),
+            (
+                "#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######",
+                39514,
+            ),
+            (
+                "#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######",
+                27755,
+            ),
+            (
+                "#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######",
+                28944,
+            ),
+            (
+                "#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########",
+                18740,
+            ),
+        ];
+
+        for (input, score) in examples.iter() {
+            assert_eq!(fight(input).score(), *score, "for\n{}", input);
+        }
+    }
+
+    #[test]
+    fn elf_power_examples() {
+        let examples = [
+            (FIRST_EXAMPLE, 15, 4988),
+            (
+                "#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######",
+                4,
+                31284,
+            ),
+            (
+                "#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######",
+                15,
+                3478,
+            ),
+            (
+                "#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######",
+                12,
+                6474,
+            ),
+            (
+                "#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########",
+                34,
+                1140,
+            ),
+        ];
+
+        for (input, attack, score) in examples.iter() {
+            let map = Map::from_str(input).unwrap();
+            let (needed, outcome) = elf_power_needed(&map).expect("Elves should be able to win");
+            assert_eq!(
+                (needed, outcome.score()),
+                (*attack, *score),
+                "for\n{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::day::Day;
+use crate::elfcode::{infer_opcodes, Instruction, Machine, Program, Sample};
+use aoc_common::input::read_input;
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+
+pub struct Day16 {
+    samples: Vec<Sample>,
+    program: Vec<[i64; 4]>,
+}
+
+impl Day16 {
+    pub fn new() -> Result<Day16, String> {
+        let (samples, program) = parse_input(&read_input(env!("CARGO_MANIFEST_DIR"), "day16")?)?;
+        Ok(Day16 { samples, program })
+    }
+}
+
+impl Day for Day16 {
+    fn part1(&mut self, sender: &Sender<String>) {
+        let ambiguous = self
+            .samples
+            .iter()
+            .filter(|s| s.candidates().len() >= 3)
+            .count();
+        sender
+            .send(format!(
+                "{} samples behave like three or more opcodes",
+                ambiguous
+            ))
+            .unwrap();
+    }
+
+    fn part2(&mut self, sender: &Sender<String>) {
+        match run_test_program(&self.samples, &self.program) {
+            Ok(result) => sender
+                .send(format!("Register 0 ends up holding {}", result))
+                .unwrap(),
+            Err(e) => sender.send(e).unwrap(),
+        }
+    }
+}
+
+/// Splits the input into the samples, which are separated by blank lines, and the test
+/// program, which comes after a bigger gap.
+fn parse_input(input: &str) -> Result<(Vec<Sample>, Vec<[i64; 4]>), String> {
+    let input = input.replace("\r\n", "\n");
+    let (samples, program) = match input.find("\n\n\n") {
+        Some(gap) => input.split_at(gap),
+        None => (input.as_str(), ""),
+    };
+
+    let samples = samples
+        .split("\n\n")
+        .filter(|s| !s.trim().is_empty())
+        .map(Sample::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let program = program
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let numbers = l
+                .split_whitespace()
+                .map(|n| i64::from_str(n).map_err(|e| format!("{}: {}", n, e)))
+                .collect::<Result<Vec<_>, _>>()?;
+            match numbers[..] {
+                [opcode, a, b, c] => Ok([opcode, a, b, c]),
+                _ => Err(format!("\"{}\" should be four numbers", l)),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok((samples, program))
+}
+
+fn run_test_program(samples: &[Sample], program: &[[i64; 4]]) -> Result<i64, String> {
+    let opcodes = infer_opcodes(samples)?;
+    let instructions = program
+        .iter()
+        .map(|&[number, a, b, c]| {
+            opcodes
+                .get(&number)
+                .map(|&opcode| Instruction::new(opcode, a, b, c))
+                .ok_or_else(|| format!("None of the samples show what {} does", number))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let program = Program {
+        ip_register: None,
+        instructions,
+    };
+    let mut machine = Machine::new(&program);
+    machine.run()?;
+    Ok(machine.register(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        let (samples, program) = parse_input(
+            "Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]
+
+Before: [0, 1, 2, 3]
+4 0 1 3
+After:  [0, 1, 2, 1]
+
+
+
+9 3 3 0
+4 1 2 3
+",
+        )
+        .unwrap();
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].instruction, [4, 0, 1, 3]);
+        assert_eq!(program, vec![[9, 3, 3, 0], [4, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_ambiguous_samples_stop_the_program() {
+        let (samples, program) = parse_input(
+            "Before: [3, 2, 1, 1]
+9 2 1 2
+After:  [3, 2, 2, 1]
+
+
+
+9 3 3 0
+",
+        )
+        .unwrap();
+
+        assert!(run_test_program(&samples, &program).is_err());
+    }
+}
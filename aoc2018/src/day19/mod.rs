@@ -0,0 +1,127 @@
+use crate::day::Day;
+use crate::elfcode::{Machine, Program};
+use aoc_common::input::read_input;
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+
+pub struct Day19 {
+    program: Program,
+}
+
+impl Day19 {
+    pub fn new() -> Result<Day19, String> {
+        Ok(Day19 {
+            program: Program::from_str(&read_input(env!("CARGO_MANIFEST_DIR"), "day19")?)?,
+        })
+    }
+}
+
+impl Day for Day19 {
+    fn part1(&mut self, sender: &Sender<String>) {
+        let mut machine = Machine::new(&self.program);
+        match machine.profile(usize::MAX) {
+            Ok(profile) => {
+                sender
+                    .send(format!(
+                        "Register 0 ends up holding {} after {} instructions",
+                        machine.register(0),
+                        machine.executed()
+                    ))
+                    .unwrap();
+                if let Some(hot) = profile.loops().first() {
+                    sender
+                        .send(format!(
+                            "Most of the time went on instructions {} to {}, round {} times",
+                            hot.start, hot.end, hot.iterations
+                        ))
+                        .unwrap();
+                }
+            }
+            Err(e) => sender.send(e).unwrap(),
+        }
+    }
+
+    fn part2(&mut self, sender: &Sender<String>) {
+        match sum_of_factors_program(&self.program, 1) {
+            Ok(result) => sender
+                .send(format!("Register 0 would end up holding {}", result))
+                .unwrap(),
+            Err(e) => sender.send(e).unwrap(),
+        }
+    }
+}
+
+/// The program works out a number, jumps back to the start, then adds up its factors in a
+/// pair of nested loops which take forever. So this runs it only as far as that first jump
+/// back, takes the number it's built, which is the biggest thing in the registers, and adds up
+/// the factors itself.
+fn sum_of_factors_program(program: &Program, register_0: i64) -> Result<i64, String> {
+    let mut machine = Machine::new(program);
+    machine.set_register(0, register_0);
+
+    let mut last_ip = machine.ip();
+    let halted = machine.run_while(|m| {
+        let jumped_back = m.ip() < last_ip;
+        last_ip = m.ip();
+        !jumped_back
+    })?;
+    if halted {
+        return Err("The program finished before getting to its loops".to_owned());
+    }
+
+    let target = *machine.registers().iter().max().unwrap();
+    Ok(sum_of_factors(target))
+}
+
+fn sum_of_factors(n: i64) -> i64 {
+    (1..)
+        .take_while(|f| f * f <= n)
+        .filter(|f| n % f == 0)
+        .map(|f| if f * f == n { f } else { f + n / f })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sets r5 to 10, then adds up its factors the slow way
+    const FACTORS: &str = "#ip 4
+addi 4 16 4
+seti 1 0 1
+seti 1 0 2
+mulr 1 2 3
+eqrr 3 5 3
+addr 3 4 4
+addi 4 1 4
+addr 1 0 0
+addi 2 1 2
+gtrr 2 5 3
+addr 4 3 4
+seti 2 0 4
+addi 1 1 1
+gtrr 1 5 3
+addr 3 4 4
+seti 1 0 4
+mulr 4 4 4
+addi 5 10 5
+seti 0 0 4";
+
+    #[test]
+    fn test_sum_of_factors() {
+        assert_eq!(sum_of_factors(1), 1);
+        assert_eq!(sum_of_factors(10), 18);
+        assert_eq!(sum_of_factors(36), 91);
+    }
+
+    #[test]
+    fn test_shortcut_agrees_with_running() {
+        let program = Program::from_str(FACTORS).unwrap();
+
+        let mut machine = Machine::new(&program);
+        machine.run().unwrap();
+        assert_eq!(machine.register(0), 18);
+
+        assert_eq!(sum_of_factors_program(&program, 0), Ok(18));
+    }
+}
@@ -0,0 +1,378 @@
+use crate::day::Day;
+use crate::elfcode::{HotLoop, Machine, Opcode, Program};
+use aoc_common::input::read_input;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::mpsc::Sender;
+
+pub struct Day21 {
+    program: Program,
+}
+
+impl Day21 {
+    pub fn new() -> Result<Day21, String> {
+        Ok(Day21 {
+            program: Program::from_str(&read_input(env!("CARGO_MANIFEST_DIR"), "day21")?)?,
+        })
+    }
+}
+
+impl Day for Day21 {
+    fn part1(&mut self, sender: &Sender<String>) {
+        match first_halting_value(&self.program) {
+            Ok(first) => sender
+                .send(format!("{} in register 0 halts soonest", first))
+                .unwrap(),
+            Err(e) => sender.send(e).unwrap(),
+        }
+    }
+
+    fn part2(&mut self, sender: &Sender<String>) {
+        match last_halting_value(&self.program) {
+            Ok(last) => sender
+                .send(format!("{} in register 0 halts latest", last))
+                .unwrap(),
+            Err(e) => sender.send(e).unwrap(),
+        }
+    }
+}
+
+/// The only place the program looks at register 0 is to compare it with another register and
+/// halt if they're the same, so this finds that comparison and returns its index and the other
+/// register.
+fn halting_check(program: &Program) -> Result<(i64, usize), String> {
+    let checks = program
+        .instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.opcode == Opcode::Eqrr && (i.a == 0) != (i.b == 0))
+        .map(|(index, i)| (index as i64, if i.a == 0 { i.b } else { i.a } as usize))
+        .collect::<Vec<_>>();
+
+    match checks[..] {
+        [check] => Ok(check),
+        _ => Err(format!(
+            "Expected one comparison with register 0, but found {}",
+            checks.len()
+        )),
+    }
+}
+
+/// How long to profile the program for to find its hot loop. The loop runs hundreds of times
+/// for the very first value, so this is plenty.
+const PROFILE_STEPS: usize = 100_000;
+
+/// Between values, the program divides by 256 by counting up until `(quotient + 1) * 256` goes
+/// past the number being divided, which is where nearly all of its time goes:
+///
+/// ```text
+/// addi q 1 t      t = (q + 1) * divisor
+/// muli t K t
+/// gtrr t n t      if t > n, jump out
+/// addr t ip ip
+/// addi ip 1 ip
+/// seti exit _ ip
+/// addi q 1 q      otherwise add one to q and go round again
+/// seti start _ ip
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+struct DivisionLoop {
+    start: i64,
+    exit: i64,
+    quotient: usize,
+    scratch: usize,
+    dividend: usize,
+    divisor: i64,
+}
+
+impl DivisionLoop {
+    /// Checks whether a hot loop found by profiling is the division loop.
+    fn recognise(program: &Program, hot: HotLoop) -> Option<DivisionLoop> {
+        let ip = program.ip_register? as i64;
+        let body = program.instructions.get(hot.start..=hot.end)?;
+        let (inc, mul, gt, skip, step, exit, next, back) = match body {
+            [inc, mul, gt, skip, step, exit, next, back] => {
+                (inc, mul, gt, skip, step, exit, next, back)
+            }
+            _ => return None,
+        };
+
+        let (q, t, n, k) = (inc.a, inc.c, gt.b, mul.b);
+        let distinct = q != t && q != n && t != n && ![q, t, n].contains(&ip);
+        let recognised = distinct
+            && (inc.opcode, inc.b) == (Opcode::Addi, 1)
+            && (mul.opcode, mul.a, mul.c) == (Opcode::Muli, t, t)
+            && k > 0
+            && (gt.opcode, gt.a, gt.c) == (Opcode::Gtrr, t, t)
+            && skip.opcode == Opcode::Addr
+            && ((skip.a, skip.b) == (t, ip) || (skip.a, skip.b) == (ip, t))
+            && skip.c == ip
+            && (step.opcode, step.a, step.b, step.c) == (Opcode::Addi, ip, 1, ip)
+            && (exit.opcode, exit.c) == (Opcode::Seti, ip)
+            && (next.opcode, next.a, next.b, next.c) == (Opcode::Addi, q, 1, q)
+            && (back.opcode, back.a + 1, back.c) == (Opcode::Seti, hot.start as i64, ip);
+
+        if recognised {
+            Some(DivisionLoop {
+                start: hot.start as i64,
+                exit: exit.a + 1,
+                quotient: q as usize,
+                scratch: t as usize,
+                dividend: n as usize,
+                divisor: k,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Does the whole loop in one go, leaving the machine as the loop would have.
+    fn apply(&self, machine: &mut Machine) {
+        let quotient = machine
+            .register(self.quotient)
+            .max(machine.register(self.dividend).div_euclid(self.divisor));
+        machine.set_register(self.quotient, quotient);
+        machine.set_register(self.scratch, 1);
+        machine.jump(self.exit);
+    }
+}
+
+/// Runs the program, doing the division loop natively if profiling turns it up, and stops each
+/// time it compares something with register 0.
+struct Comparisons<'a> {
+    machine: Machine<'a>,
+    check: i64,
+    register: usize,
+    division: Option<DivisionLoop>,
+}
+
+impl<'a> Comparisons<'a> {
+    fn new(program: &'a Program) -> Result<Comparisons<'a>, String> {
+        let (check, register) = halting_check(program)?;
+        let division = Machine::new(program)
+            .profile(PROFILE_STEPS)?
+            .loops()
+            .first()
+            .and_then(|&hot| DivisionLoop::recognise(program, hot));
+
+        Ok(Comparisons {
+            machine: Machine::new(program),
+            check,
+            register,
+            division,
+        })
+    }
+
+    /// The next value register 0 is compared with, or `None` if the program halts first.
+    fn next_value(&mut self) -> Result<Option<i64>, String> {
+        loop {
+            if let Some(division) = &self.division {
+                if self.machine.ip() == division.start {
+                    division.apply(&mut self.machine);
+                }
+            }
+
+            let compared = if self.machine.ip() == self.check {
+                Some(self.machine.register(self.register))
+            } else {
+                None
+            };
+            if !self.machine.step()? {
+                return Ok(None);
+            }
+            if compared.is_some() {
+                return Ok(compared);
+            }
+        }
+    }
+}
+
+/// The first value register 0 is compared with would halt the program soonest.
+fn first_halting_value(program: &Program) -> Result<i64, String> {
+    Comparisons::new(program)?
+        .next_value()?
+        .ok_or_else(|| "The program never compared anything with register 0".to_owned())
+}
+
+/// The last value register 0 is compared with before they start repeating would keep the
+/// program going longest.
+fn last_halting_value(program: &Program) -> Result<i64, String> {
+    let mut comparisons = Comparisons::new(program)?;
+    let mut seen = HashSet::new();
+    let mut last = None;
+
+    while let Some(value) = comparisons.next_value()? {
+        if !seen.insert(value) {
+            break;
+        }
+        last = Some(value);
+    }
+
+    last.ok_or_else(|| "The program never compared anything with register 0".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// r1 goes 1, 2, 5, 10, 5, ... until it matches register 0
+    const SQUARES: &str = "#ip 5
+mulr 1 1 1
+addi 1 1 1
+bani 1 15 1
+eqrr 1 0 2
+addr 2 5 5
+seti -1 0 5";
+
+    /// The shape everyone's puzzle input takes, give or take the constants and registers.
+    const HASH: &str = "#ip 4
+seti 123 0 3
+bani 3 456 3
+eqri 3 72 3
+addr 3 4 4
+seti 0 0 4
+seti 0 6 3
+bori 3 65536 2
+seti 7586220 4 3
+bani 2 255 1
+addr 3 1 3
+bani 3 16777215 3
+muli 3 65899 3
+bani 3 16777215 3
+gtir 256 2 1
+addr 1 4 4
+addi 4 1 4
+seti 27 6 4
+seti 0 2 1
+addi 1 1 5
+muli 5 256 5
+gtrr 5 2 5
+addr 5 4 4
+addi 4 1 4
+seti 25 3 4
+addi 1 1 1
+seti 17 1 4
+setr 1 6 2
+seti 7 8 4
+eqrr 3 0 1
+addr 1 4 4
+seti 5 3 4";
+
+    /// What `HASH` works out from the previous value it compared.
+    fn hash(previous: i64) -> i64 {
+        let mut input = previous | 65536;
+        let mut value = 7586220;
+        loop {
+            value = (((value + (input & 255)) & 16777215) * 65899) & 16777215;
+            if input < 256 {
+                return value;
+            }
+            input /= 256;
+        }
+    }
+
+    #[test]
+    fn test_halting_check() {
+        let program = Program::from_str(SQUARES).unwrap();
+        assert_eq!(halting_check(&program), Ok((3, 1)));
+    }
+
+    #[test]
+    fn test_halting_values() {
+        let program = Program::from_str(SQUARES).unwrap();
+        assert_eq!(first_halting_value(&program), Ok(1));
+        assert_eq!(last_halting_value(&program), Ok(10));
+
+        let mut machine = Machine::new(&program);
+        machine.set_register(0, 10);
+        machine.run().unwrap();
+        // three times round, then out past the end the fourth time
+        assert_eq!(machine.executed(), 3 * 6 + 5);
+    }
+
+    #[test]
+    fn test_recognise_division_loop() {
+        let program = Program::from_str(HASH).unwrap();
+        let profile = Machine::new(&program).profile(PROFILE_STEPS).unwrap();
+        let hot = profile.loops()[0];
+        assert_eq!((hot.start, hot.end), (18, 25));
+
+        assert_eq!(
+            DivisionLoop::recognise(&program, hot),
+            Some(DivisionLoop {
+                start: 18,
+                exit: 26,
+                quotient: 1,
+                scratch: 5,
+                dividend: 2,
+                divisor: 256,
+            })
+        );
+
+        let squares = Program::from_str(SQUARES).unwrap();
+        let hot = Machine::new(&squares).profile(100).unwrap().loops()[0];
+        assert_eq!(DivisionLoop::recognise(&squares, hot), None);
+    }
+
+    #[test]
+    fn test_division_matches_the_loop() {
+        let program = Program::from_str(HASH).unwrap();
+        let division = DivisionLoop::recognise(
+            &program,
+            HotLoop {
+                start: 18,
+                end: 25,
+                iterations: 0,
+            },
+        )
+        .unwrap();
+
+        for &dividend in &[0, 1, 255, 256, 1000, 65536, 16777215] {
+            let mut slow = Machine::new(&program);
+            slow.set_register(2, dividend);
+            slow.jump(17);
+            slow.run_while(|m| m.ip() != 26).unwrap();
+
+            let mut fast = Machine::new(&program);
+            fast.set_register(2, dividend);
+            fast.jump(17);
+            fast.step().unwrap();
+            division.apply(&mut fast);
+
+            assert_eq!(fast.ip(), slow.ip());
+            // the instruction pointer's register only catches up on the next step
+            for register in (0..4).chain(5..6) {
+                assert_eq!(
+                    fast.register(register),
+                    slow.register(register),
+                    "register {} dividing {}",
+                    register,
+                    dividend
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_program() {
+        let program = Program::from_str(HASH).unwrap();
+        let mut comparisons = Comparisons::new(&program).unwrap();
+        assert!(comparisons.division.is_some());
+
+        let mut expected = 0;
+        for _ in 0..10 {
+            expected = hash(expected);
+            assert_eq!(comparisons.next_value(), Ok(Some(expected)));
+        }
+        assert_eq!(first_halting_value(&program), Ok(hash(0)));
+
+        let mut seen = HashSet::new();
+        let mut value = hash(0);
+        let mut last = value;
+        while seen.insert(value) {
+            last = value;
+            value = hash(value);
+        }
+        assert_eq!(last_halting_value(&program), Ok(last));
+    }
+}
@@ -1,12 +1,10 @@
 use crate::day::Day;
+use aoc_common::summed_area::SummedAreaTable;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashSet;
-use std::fmt::Debug;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 use std::sync::mpsc::Sender;
-use std::usize;
 
 pub struct Day3 {
     input: &'static str,
@@ -112,23 +110,6 @@ struct Rect {
     height: usize,
 }
 
-impl Rect {
-    fn intersects(&self, other: &Rect) -> bool {
-        !(self.is_above(other)
-            || self.is_left(other)
-            || other.is_above(self)
-            || other.is_left(self))
-    }
-
-    fn is_above(&self, other: &Rect) -> bool {
-        self.bottom() <= other.top
-    }
-
-    fn is_left(&self, other: &Rect) -> bool {
-        self.right() <= other.left
-    }
-}
-
 impl Positioned for Rect {
     fn top(&self) -> usize {
         self.top
@@ -162,12 +143,6 @@ struct Claim {
     rect: Rect,
 }
 
-impl Claim {
-    fn intersects(&self, other: &Claim) -> bool {
-        self.rect.intersects(&other.rect)
-    }
-}
-
 impl Positioned for Claim {
     fn top(&self) -> usize {
         self.rect.top()
@@ -206,10 +181,10 @@ impl FromStr for Claim {
 
     fn from_str(source: &str) -> Result<Claim, Self::Err> {
         lazy_static! {
-            static ref re: Regex = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
+            static ref RE: Regex = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
         }
 
-        let mut iter = re.captures_iter(source);
+        let mut iter = RE.captures_iter(source);
         if let Some(cap) = iter.next() {
             Ok(Claim {
                 id: u32::from_str(&cap[1]).expect("If ID won't parse the regex is broken"),
@@ -232,187 +207,66 @@ impl FromStr for Claim {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Range {
-    start: usize,
-    end: usize,
-    claims: usize,
-    claimed_by: Vec<u32>,
-}
-
-impl Range {
-    fn new(start: usize, end: usize) -> Range {
-        Range {
-            start,
-            end,
-            claims: 0,
-            claimed_by: vec![],
-        }
-    }
-
-    fn claim(&self, start: usize, end: usize, claim_id: u32) -> Option<Vec<Range>> {
-        if self.start > start || self.end < end {
-            return None;
-        }
-
-        let mut new_claimed_by = self.claimed_by.clone();
-        new_claimed_by.push(claim_id);
-
-        let mut ranges = Vec::new();
-        if start > self.start {
-            let new = Range {
-                start: self.start,
-                end: start,
-                claims: self.claims,
-                claimed_by: self.claimed_by.clone(),
-            };
-            ranges.push(new);
-        }
-
-        let new = Range {
-            start: start,
-            end: end,
-            claims: self.claims + 1,
-            claimed_by: new_claimed_by.clone(),
-        };
-        ranges.push(new);
-
-        if end < self.end {
-            let new = Range {
-                start: end,
-                end: self.end,
-                claims: self.claims,
-                claimed_by: self.claimed_by.clone(),
-            };
-            ranges.push(new);
-        }
-
-        Some(ranges)
-    }
-
-    fn contains(&self, x: usize) -> bool {
-        self.start <= x && self.end > x
-    }
-
-    fn overlaps(&self, left: usize, right: usize) -> bool {
-        if self.contains(left) {
-            true
-        } else if right > self.start && right < self.end {
-            true
-        } else if left < self.start && right >= self.end {
-            true
-        } else {
-            false
-        }
-    }
-
-    fn end(&self) -> usize {
-        self.end
-    }
-
-    fn start(&self) -> usize {
-        self.start
-    }
-
-    fn size(&self) -> usize {
-        self.end - self.start
-    }
-
-    fn claims(&self) -> usize {
-        self.claims
-    }
-}
-
 #[derive(Debug, PartialEq, Eq)]
 struct Fabric {
-    rows: Vec<Vec<Range>>,
+    width: usize,
+    height: usize,
+    // each claim adds one at its top left corner and takes one away just past its top right
+    // and bottom left corners (and adds one back past the bottom right), so that summing
+    // everything above and to the left of a square counts the claims covering it
+    claim_edges: Vec<Vec<i32>>,
 }
 
 impl Fabric {
     fn new(width: usize, height: usize) -> Fabric {
-        let mut rows = Vec::new();
-
-        for _ in 0..height {
-            rows.push(vec![Range::new(0, width)]);
+        Fabric {
+            width,
+            height,
+            claim_edges: vec![vec![0; width + 1]; height + 1],
         }
-
-        Fabric { rows }
     }
 
-    fn add_claim<C: Positioned + Dimensioned + Debug + Identified<u32>>(&mut self, claim: &C) {
-        for row in self.rows.iter_mut().skip(claim.top()).take(claim.height()) {
-            let affected_ranges = row
-                .iter()
-                .enumerate()
-                .filter(|(_, r)| r.overlaps(claim.left(), claim.right()))
-                .collect::<Vec<_>>();
-
-            let mut new_ranges = Vec::new();
-            let mut affected_index_start = usize::MAX;
-            let mut affected_index_end = 0;
-
-            for (index, affected_range) in affected_ranges {
-                affected_index_start = usize::min(index, affected_index_start);
-                affected_index_end = usize::max(index, affected_index_end);
-
-                let overlap_start = usize::max(affected_range.start(), claim.left());
-                let overlap_end = usize::min(affected_range.end(), claim.right());
-
-                if overlap_start == overlap_end {
-                    // this was a bug before
-                    panic!("Zero-length subrange can never happen");
-                }
-
-                if let Some(mut ranges) =
-                    affected_range.claim(overlap_start, overlap_end, claim.id())
-                {
-                    new_ranges.append(&mut ranges);
-                }
-            }
-
-            let _: Vec<_> = row
-                .splice(
-                    affected_index_start..affected_index_end + 1,
-                    new_ranges.into_iter(),
-                )
-                .collect();
+    fn add_claim<C: Positioned>(&mut self, claim: &C) {
+        let right = usize::min(claim.right(), self.width);
+        let bottom = usize::min(claim.bottom(), self.height);
+        if claim.left() >= right || claim.top() >= bottom {
+            return;
         }
-    }
 
-    fn area_over_claims(&self, claims: usize) -> usize {
-        self.rows
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .filter(|range| range.claims() >= claims)
-                    .map(|range| range.size())
-                    .sum::<usize>()
-            })
-            .sum()
+        self.claim_edges[claim.top()][claim.left()] += 1;
+        self.claim_edges[claim.top()][right] -= 1;
+        self.claim_edges[bottom][claim.left()] -= 1;
+        self.claim_edges[bottom][right] += 1;
     }
-}
 
-impl Display for Range {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let c = match self.claims {
-            0 => '.',
-            1 => 'O',
-            _ => 'X',
-        };
-
-        for _ in self.start..self.end {
-            write!(f, "{}", c)?;
-        }
+    /// How many claims cover each square inch.
+    fn claim_counts(&self) -> SummedAreaTable<i32> {
+        let edges = SummedAreaTable::from_rows(&self.claim_edges);
+        SummedAreaTable::from_fn(self.width, self.height, |x, y| {
+            edges.prefix_sum(x, y).unwrap()
+        })
+    }
 
-        Ok(())
+    fn area_over_claims(&self, claims: usize) -> usize {
+        let counts = self.claim_counts();
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|(x, y)| counts.value(*x, *y).unwrap() as usize >= claims)
+            .count()
     }
 }
 
 impl Display for Fabric {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in self.rows.iter() {
-            for range in row {
-                write!(f, "{}", range)?;
+        let counts = self.claim_counts();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = match counts.value(x, y).unwrap() {
+                    0 => '.',
+                    1 => 'O',
+                    _ => 'X',
+                };
+                write!(f, "{}", c)?;
             }
             writeln!(f, "")?;
         }
@@ -421,23 +275,24 @@ impl Display for Fabric {
     }
 }
 
+/// A claim doesn't overlap any others if every square inch of it is only claimed once.
 fn find_non_overlapping_claims(claims: &Vec<Claim>) -> Vec<u32> {
-    let mut candidates = HashSet::new();
-
+    let max_x = claims.iter().map(|m| m.right()).max().unwrap_or(0);
+    let max_y = claims.iter().map(|m| m.bottom()).max().unwrap_or(0);
+    let mut fabric = Fabric::new(max_x, max_y);
     for claim in claims {
-        candidates.insert(claim.id);
-    }
-
-    for outer in claims {
-        for inner in claims {
-            if outer != inner && outer.intersects(inner) {
-                candidates.remove(&outer.id());
-                candidates.remove(&inner.id());
-            }
-        }
-    }
-
-    candidates.into_iter().collect::<Vec<_>>()
+        fabric.add_claim(claim);
+    }
+
+    let counts = fabric.claim_counts();
+    claims
+        .iter()
+        .filter(|c| {
+            counts.sum(c.left(), c.top(), c.width(), c.height())
+                == Some((c.width() * c.height()) as i32)
+        })
+        .map(|c| c.id())
+        .collect()
 }
 
 #[cfg(test)]
@@ -462,37 +317,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn claim_middle_of_range_get_three_ranges() {
-        let range = Range::new(0, 6);
-
-        let ranges = range.claim(2, 4, 1);
-
-        assert_eq!(
-            ranges,
-            Some(vec![
-                Range {
-                    start: 0,
-                    end: 2,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 2,
-                    end: 4,
-                    claims: 1,
-                    claimed_by: vec![1]
-                },
-                Range {
-                    start: 4,
-                    end: 6,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ])
-        );
-    }
-
     #[test]
     fn claim_in_claim() {
         let mut fabric = Fabric::new(8, 1);
@@ -552,49 +376,6 @@ O...
         );
     }
 
-    #[test]
-    fn overlaps() {
-        let range = Range::new(2, 5);
-        assert_eq!(true, range.overlaps(2, 5), "Overlaps identical range");
-        assert_eq!(
-            true,
-            range.overlaps(1, 3),
-            "Overlaps range over left boundary"
-        );
-        assert_eq!(
-            true,
-            range.overlaps(3, 6),
-            "Overlaps range over right boundary"
-        );
-        assert_eq!(
-            false,
-            range.overlaps(0, 2),
-            "Doesn't overlap touching range on the left"
-        );
-        assert_eq!(
-            false,
-            range.overlaps(5, 6),
-            "Doesn't overlap touching range on the right"
-        );
-        assert_eq!(true, range.overlaps(3, 4), "Overlaps subrange");
-        assert_eq!(
-            true,
-            Range::new(1, 3).overlaps(0, 4),
-            "1, 3 range overlaps 0, 4"
-        );
-    }
-
-    #[test]
-    fn contains() {
-        let range = Range::new(2, 5);
-        assert_eq!(true, range.contains(2), "contains left boundary");
-        assert_eq!(false, range.contains(5), "doesn't countain right boundary");
-        assert_eq!(true, range.contains(3), "contains middle");
-        assert_eq!(false, range.contains(1), "doesn't contain left neighbour");
-        assert_eq!(false, range.contains(6), "doesn't contain right neighbour");
-        assert_eq!(false, range.contains(0), "2, 5 doesn't contain 0");
-    }
-
     #[test]
     fn fabric_area_claimed_twice() {
         let mut fabric = Fabric::new(4, 4);
@@ -614,199 +395,6 @@ O...
         let result = fabric.area_over_claims(2);
 
         assert_eq!(result, 4);
-        let first_row = (&fabric.rows).iter().nth(0).unwrap();
-        assert_eq!(
-            first_row,
-            &vec![Range {
-                start: 0,
-                end: 8,
-                claims: 0,
-                claimed_by: vec![]
-            }]
-        );
-        let second_row = (&fabric.rows).iter().nth(1).unwrap();
-        assert_eq!(
-            second_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 3,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 3,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![2]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ]
-        );
-        let third_row = (&fabric.rows).iter().nth(2).unwrap();
-        assert_eq!(
-            third_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 3,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 3,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![2]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ]
-        );
-        let fourth_row = (&fabric.rows).iter().nth(3).unwrap();
-        assert_eq!(
-            fourth_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 1,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 1,
-                    end: 3,
-                    claims: 1,
-                    claimed_by: vec![1]
-                },
-                Range {
-                    start: 3,
-                    end: 5,
-                    claims: 2,
-                    claimed_by: vec![1, 2]
-                },
-                Range {
-                    start: 5,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![2]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ]
-        );
-        let fifth_row = (&fabric.rows).iter().nth(4).unwrap();
-        assert_eq!(
-            fifth_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 1,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 1,
-                    end: 3,
-                    claims: 1,
-                    claimed_by: vec![1]
-                },
-                Range {
-                    start: 3,
-                    end: 5,
-                    claims: 2,
-                    claimed_by: vec![1, 2]
-                },
-                Range {
-                    start: 5,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![2]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ],
-            "fifth row"
-        );
-        let sixth_row = (&fabric.rows).iter().nth(5).unwrap();
-        assert_eq!(
-            sixth_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 1,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 1,
-                    end: 5,
-                    claims: 1,
-                    claimed_by: vec![1]
-                },
-                Range {
-                    start: 5,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![3]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ],
-            "sixth row"
-        );
-        let seventh_row = (&fabric.rows).iter().nth(6).unwrap();
-        assert_eq!(
-            seventh_row,
-            &vec![
-                Range {
-                    start: 0,
-                    end: 1,
-                    claims: 0,
-                    claimed_by: vec![]
-                },
-                Range {
-                    start: 1,
-                    end: 5,
-                    claims: 1,
-                    claimed_by: vec![1]
-                },
-                Range {
-                    start: 5,
-                    end: 7,
-                    claims: 1,
-                    claimed_by: vec![3]
-                },
-                Range {
-                    start: 7,
-                    end: 8,
-                    claims: 0,
-                    claimed_by: vec![]
-                }
-            ],
-            "seventh row"
-        );
         assert_eq!(
             fabric.to_string(),
             r"........
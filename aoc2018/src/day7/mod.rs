@@ -1,14 +1,7 @@
-mod dependencymap;
-mod task;
-mod timeline;
-
-use self::dependencymap::DependencyMap;
-use self::task::Task;
-use self::timeline::Timeline;
 use crate::day::Day;
+use aoc_common::schedule::{CriticalPath, Scheduler};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::Sender;
 
 pub struct Day7 {
@@ -40,6 +33,15 @@ impl Day for Day7 {
             Some(time) => sender.send(format!("The time taken is {}", time)).unwrap(),
             None => sender.send("Unable to determine time".into()).unwrap(),
         }
+        if let Some(path) = critical_path(&self.dependencies, 60) {
+            let steps = path.tasks().collect::<String>();
+            sender
+                .send(format!(
+                    "The critical path is {} taking {}",
+                    steps, path.length
+                ))
+                .unwrap();
+        }
     }
 }
 
@@ -56,159 +58,29 @@ fn parse_dependency(line: &str) -> Option<(char, char)> {
     Some((*dep, *trg))
 }
 
-fn determine_order(deps: &Vec<(char, char)>) -> Option<String> {
-    let mut map_prerequisite_to_target: HashMap<char, Vec<char>> = HashMap::new();
-    let mut map_target_to_prerequisite: HashMap<char, Vec<char>> = HashMap::new();
-    let mut all_nodes: HashSet<char> = HashSet::new();
-    let mut all_prerequisites: HashSet<char> = HashSet::new();
-    let mut all_with_prerequisites: HashSet<char> = HashSet::new();
-
-    for (prerequisite, wanted) in deps.iter() {
-        all_nodes.insert(*prerequisite);
-        all_nodes.insert(*wanted);
-
-        all_prerequisites.insert(*prerequisite);
-        all_with_prerequisites.insert(*wanted);
-
-        #[cfg(test)]
-        println!("{} is a prerequisite for {}", prerequisite, wanted);
-        let entry = map_prerequisite_to_target
-            .entry(*prerequisite)
-            .or_insert(Vec::new());
-        entry.push(*wanted);
-
-        let entry = map_target_to_prerequisite
-            .entry(*wanted)
-            .or_insert(Vec::new());
-        entry.push(*prerequisite);
-    }
-
-    for (_, val) in map_prerequisite_to_target.iter_mut() {
-        val.sort();
-    }
-
-    #[cfg(test)]
-    println!(
-        "Nodes with prerequisites: {:?}\nNodes which are prerequisites: {:?}",
-        all_with_prerequisites, all_prerequisites
-    );
-
-    let mut starting_nodes: Vec<char> = all_nodes
-        .difference(&all_with_prerequisites)
-        .cloned()
-        .collect();
-    starting_nodes.sort();
-    let starting_node = starting_nodes[0];
-    #[cfg(test)]
-    println!("Starting node is {}", starting_node);
-
-    let terminal_node = all_nodes.difference(&all_prerequisites).cloned().next()?;
-    #[cfg(test)]
-    println!("Terminal node is {}", terminal_node);
-
-    let mut result = String::new();
-    let mut completed: HashSet<char> = HashSet::new();
-    completed.insert(starting_node);
-
-    let mut available: HashSet<char> = starting_nodes.drain(0..).collect();
-    available.remove(&starting_node);
-
-    let mut current_node = starting_node;
-    while current_node != terminal_node {
-        let candidates = map_prerequisite_to_target.get(&current_node)?;
-        #[cfg(test)]
-        println!(
-            "Current node is {} with candidates {:?}, completed {:?}, available: {:?}",
-            current_node, candidates, completed, available
-        );
-        result.push(current_node);
-
-        available.extend(candidates.iter().filter_map(|c| {
-            let prerequisites: HashSet<char> = map_target_to_prerequisite
-                .get(c)?
-                .iter()
-                .filter(|p| !completed.contains(*p))
-                .cloned()
-                .collect();
-            if prerequisites.is_empty() {
-                Some(c)
-            } else {
-                None
-            }
-        }));
-
-        let mut possible_moves: Vec<char> = available.iter().cloned().collect();
-        possible_moves.sort();
-
-        let next_move = possible_moves.first();
-
-        #[cfg(test)]
-        println!(
-            "Possible moves: {:?}, next move: {:?}",
-            possible_moves, next_move
-        );
-
-        match next_move {
-            Some(n) => {
-                current_node = *n;
-                completed.insert(current_node);
-                available.remove(&current_node);
-            }
-            None => {
-                #[cfg(test)]
-                println!("No next move, ending loop");
-                break;
-            }
-        }
-    }
-
-    result.push(current_node);
-
-    Some(result)
+fn determine_order(deps: &[(char, char)]) -> Option<String> {
+    let scheduler = Scheduler::from_dependencies(deps.iter().cloned());
+    let order = scheduler.topological_order().ok()?;
+    Some(order.into_iter().collect())
 }
 
-fn determine_time(deps: &Vec<(char, char)>, step_time_factor: u32, workers: usize) -> Option<u32> {
-    let time_for_step = |c| time_for_letter(c) + step_time_factor;
-
-    let mut dependency_map = DependencyMap::new(deps.clone());
-
-    let mut timeline = Timeline::new(workers);
-
-    let mut time = 0;
-
-    let mut waiting_for = HashSet::new();
-
-    while !dependency_map.is_finished() {
-        #[cfg(test)]
-        println!("Time is {}", time);
-
-        for completed in timeline.completed_at(time) {
-            dependency_map.complete(completed);
-        }
-
-        let inserting_now = dependency_map
-            .next_available()
-            .into_iter()
-            .filter(|c| !waiting_for.contains(c))
-            .take(timeline.free_workers_at(time))
-            .map(|c| Task::new(c, time_for_step(c)))
-            .collect::<Vec<Task>>();
-
-        for task in inserting_now {
-            #[cfg(test)]
-            println!("Adding {} at/after {}", task.name(), time);
-
-            waiting_for.insert(task.name());
-            timeline.add_task_after(time, task);
-        }
-
-        time += 1;
-    }
+fn determine_time(deps: &[(char, char)], step_time_factor: u32, workers: usize) -> Option<u32> {
+    let scheduler = Scheduler::from_dependencies(deps.iter().cloned());
+    let schedule = scheduler
+        .run(workers, |c| step_duration(*c, step_time_factor))
+        .ok()?;
+    Some(schedule.total_time() as u32)
+}
 
-    #[cfg(test)]
-    println!("Dependency map is complete.\n\n{}", timeline);
+fn critical_path(deps: &[(char, char)], step_time_factor: u32) -> Option<CriticalPath<char>> {
+    let scheduler = Scheduler::from_dependencies(deps.iter().cloned());
+    scheduler
+        .critical_path(|c| step_duration(*c, step_time_factor))
+        .ok()
+}
 
-    Some(timeline.total_time_required())
+fn step_duration(c: char, step_time_factor: u32) -> u64 {
+    (time_for_letter(c) + step_time_factor) as u64
 }
 
 fn time_for_letter(c: char) -> u32 {
@@ -303,6 +175,10 @@ Step F must be finished before step E can begin."
         let time = determine_time(&deps, 0, 2);
 
         assert_eq!(time, Some(15));
+
+        let path = critical_path(&deps, 0).expect("Example has no loops");
+        assert_eq!(path.tasks().collect::<String>(), "CFE");
+        assert_eq!(path.length, 14);
     }
 
 }
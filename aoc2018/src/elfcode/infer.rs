@@ -0,0 +1,170 @@
+use super::opcode::{Instruction, Opcode};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+/// An observation of one instruction, given by its opcode number, and the registers either
+/// side of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub before: Vec<i64>,
+    pub instruction: [i64; 4],
+    pub after: Vec<i64>,
+}
+
+impl Sample {
+    /// The opcodes which would have had this effect.
+    pub fn candidates(&self) -> BTreeSet<Opcode> {
+        let [_, a, b, c] = self.instruction;
+        Opcode::ALL
+            .iter()
+            .cloned()
+            .filter(|&opcode| {
+                let mut registers = self.before.clone();
+                Instruction::new(opcode, a, b, c).apply(&mut registers) && registers == self.after
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Sample {
+    type Err = String;
+
+    /// Reads the three lines the puzzle gives for each sample.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(
+                r"Before:\s*\[([\d, ]+)\]\s*\n\s*([\d ]+?)\s*\n\s*After:\s*\[([\d, ]+)\]"
+            )
+            .unwrap();
+        }
+
+        fn numbers(s: &str, separator: char) -> Result<Vec<i64>, String> {
+            s.split(separator)
+                .map(|n| i64::from_str(n.trim()).map_err(|e| format!("{}: {}", n, e)))
+                .collect()
+        }
+
+        let cap = RE
+            .captures(s)
+            .ok_or_else(|| format!("\"{}\" isn't a sample", s))?;
+        let instruction = numbers(&cap[2], ' ')?;
+        if instruction.len() != 4 {
+            return Err(format!("\"{}\" should be four numbers", &cap[2]));
+        }
+
+        Ok(Sample {
+            before: numbers(&cap[1], ',')?,
+            instruction: [
+                instruction[0],
+                instruction[1],
+                instruction[2],
+                instruction[3],
+            ],
+            after: numbers(&cap[3], ',')?,
+        })
+    }
+}
+
+/// Works out which opcode each number stands for. Each sample rules out the opcodes which don't
+/// fit it, then any number left with only one possibility takes that opcode away from all the
+/// others, until every number is settled.
+pub fn infer_opcodes(samples: &[Sample]) -> Result<HashMap<i64, Opcode>, String> {
+    let mut possible: HashMap<i64, BTreeSet<Opcode>> = HashMap::new();
+    for sample in samples {
+        let candidates = sample.candidates();
+        let entry = possible
+            .entry(sample.instruction[0])
+            .or_insert_with(|| candidates.clone());
+        *entry = entry.intersection(&candidates).cloned().collect();
+    }
+
+    let mut known = HashMap::new();
+    while !possible.is_empty() {
+        let (&number, opcode) = possible
+            .iter()
+            .find(|(_, opcodes)| opcodes.len() == 1)
+            .map(|(number, opcodes)| (number, *opcodes.iter().next().unwrap()))
+            .ok_or_else(|| {
+                let mut stuck = possible.keys().cloned().collect::<Vec<_>>();
+                stuck.sort();
+                format!("Can't tell which opcodes {:?} are", stuck)
+            })?;
+
+        possible.remove(&number);
+        for opcodes in possible.values_mut() {
+            opcodes.remove(&opcode);
+        }
+        known.insert(number, opcode);
+    }
+
+    Ok(known)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_candidates() {
+        let sample =
+            Sample::from_str("Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]").unwrap();
+
+        assert_eq!(sample.before, vec![3, 2, 1, 1]);
+        assert_eq!(sample.instruction, [9, 2, 1, 2]);
+        assert_eq!(
+            sample.candidates(),
+            vec![Opcode::Addi, Opcode::Mulr, Opcode::Seti]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_infer_opcodes() {
+        // show each number doing its opcode to a few sets of registers, plus one more sample
+        // which on its own could be several
+        let befores = vec![
+            vec![5, 3, 2, 0],
+            vec![1, 7, 7, 4],
+            vec![2, 0, 9, 1],
+            vec![4, 5, 0, 0],
+            vec![0, 2, 1, 0],
+            vec![3, 2, 4, 0],
+        ];
+        let mut samples = vec![];
+        for (i, &opcode) in Opcode::ALL.iter().enumerate() {
+            for before in &befores {
+                let mut after = before.clone();
+                Instruction::new(opcode, 1, 2, 3).apply(&mut after);
+                samples.push(Sample {
+                    before: before.clone(),
+                    instruction: [(15 - i) as i64, 1, 2, 3],
+                    after,
+                });
+            }
+        }
+        samples.push(Sample {
+            before: vec![3, 2, 1, 1],
+            instruction: [6, 2, 1, 2],
+            after: vec![3, 2, 2, 1],
+        });
+
+        let opcodes = infer_opcodes(&samples).unwrap();
+        assert_eq!(opcodes.len(), 16);
+        for (i, &opcode) in Opcode::ALL.iter().enumerate() {
+            assert_eq!(opcodes[&((15 - i) as i64)], opcode);
+        }
+    }
+
+    #[test]
+    fn test_infer_opcodes_ambiguous() {
+        let samples =
+            vec![Sample::from_str("Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]").unwrap()];
+        assert_eq!(
+            infer_opcodes(&samples),
+            Err("Can't tell which opcodes [9] are".to_owned())
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use super::opcode::Instruction;
+use super::program::Program;
+use std::collections::HashMap;
+use std::fmt;
+
+pub const REGISTERS: usize = 6;
+
+/// Runs a program on six registers.
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    program: &'a Program,
+    registers: [i64; REGISTERS],
+    ip: i64,
+    executed: usize,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a Program) -> Machine<'a> {
+        Machine {
+            program,
+            registers: [0; REGISTERS],
+            ip: 0,
+            executed: 0,
+        }
+    }
+
+    pub fn register(&self, register: usize) -> i64 {
+        self.registers[register]
+    }
+
+    pub fn set_register(&mut self, register: usize, value: i64) {
+        self.registers[register] = value;
+    }
+
+    pub fn registers(&self) -> &[i64; REGISTERS] {
+        &self.registers
+    }
+
+    /// Which instruction is up next.
+    pub fn ip(&self) -> i64 {
+        self.ip
+    }
+
+    /// How many instructions have been carried out so far.
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    /// Carries on from instruction `ip`, for skipping over code that's been worked out some
+    /// quicker way.
+    pub fn jump(&mut self, ip: i64) {
+        self.ip = ip;
+    }
+
+    pub fn next_instruction(&self) -> Option<&'a Instruction> {
+        self.program.get(self.ip)
+    }
+
+    /// Carries out one instruction. Returns `Ok(false)` once the instruction pointer has left
+    /// the program.
+    pub fn step(&mut self) -> Result<bool, String> {
+        let instruction = match self.next_instruction() {
+            Some(instruction) => instruction,
+            None => return Ok(false),
+        };
+
+        if let Some(bound) = self.program.ip_register {
+            self.registers[bound] = self.ip;
+        }
+        if !instruction.apply(&mut self.registers) {
+            return Err(format!(
+                "Instruction {} ({}) refers to a register which doesn't exist",
+                self.ip, instruction
+            ));
+        }
+        if let Some(bound) = self.program.ip_register {
+            self.ip = self.registers[bound];
+        }
+
+        self.ip += 1;
+        self.executed += 1;
+        Ok(true)
+    }
+
+    /// Runs until the program halts.
+    pub fn run(&mut self) -> Result<(), String> {
+        while self.step()? {}
+        Ok(())
+    }
+
+    /// Runs until the program halts or `keep_going` says to stop. `keep_going` gets to look at
+    /// the machine before each instruction. Returns whether the program halted.
+    pub fn run_while<F>(&mut self, mut keep_going: F) -> Result<bool, String>
+    where
+        F: FnMut(&Machine) -> bool,
+    {
+        while keep_going(self) {
+            if !self.step()? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Runs for up to `limit` instructions, counting how often each one is carried out and every
+    /// jump taken, to find where the program spends its time.
+    pub fn profile(&mut self, limit: usize) -> Result<Profile<'a>, String> {
+        let mut counts = vec![0; self.program.len()];
+        let mut jumps = HashMap::new();
+        let mut halted = false;
+
+        for _ in 0..limit {
+            let from = self.ip;
+            if !self.step()? {
+                halted = true;
+                break;
+            }
+            counts[from as usize] += 1;
+            if self.ip != from + 1 {
+                *jumps.entry((from as usize, self.ip)).or_insert(0) += 1;
+            }
+        }
+
+        Ok(Profile {
+            program: self.program,
+            counts,
+            jumps,
+            halted,
+        })
+    }
+}
+
+/// How often each instruction ran, and which jumps were taken, during a profiled run.
+#[derive(Debug, Clone)]
+pub struct Profile<'a> {
+    program: &'a Program,
+    pub counts: Vec<usize>,
+    /// How often control went from one instruction to somewhere other than the next one.
+    pub jumps: HashMap<(usize, i64), usize>,
+    pub halted: bool,
+}
+
+/// A backward jump which keeps being taken, and so the instructions it goes round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotLoop {
+    pub start: usize,
+    pub end: usize,
+    pub iterations: usize,
+}
+
+impl<'a> Profile<'a> {
+    /// Every backward jump, most taken first.
+    pub fn loops(&self) -> Vec<HotLoop> {
+        let mut loops = self
+            .jumps
+            .iter()
+            .filter(|&(&(from, to), _)| to >= 0 && to as usize <= from)
+            .map(|(&(from, to), &iterations)| HotLoop {
+                start: to as usize,
+                end: from,
+                iterations,
+            })
+            .collect::<Vec<_>>();
+        loops.sort_by(|a, b| b.iterations.cmp(&a.iterations).then(a.start.cmp(&b.start)));
+        loops
+    }
+}
+
+impl<'a> fmt::Display for Profile<'a> {
+    /// A listing of the program with how often each instruction ran, marking where the busiest
+    /// loop goes round, followed by the loops themselves.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let loops = self.loops();
+        let hottest = loops.first();
+
+        for (i, instruction) in self.program.instructions.iter().enumerate() {
+            let marker = match hottest {
+                Some(l) if l.start == i && l.end == i => "<>",
+                Some(l) if l.start == i => "┌ ",
+                Some(l) if l.end == i => "└ ",
+                Some(l) if l.start < i && i < l.end => "│ ",
+                _ => "  ",
+            };
+            writeln!(
+                f,
+                "{:3} {}{:16} {:>12}",
+                i,
+                marker,
+                instruction.to_string(),
+                self.counts[i]
+            )?;
+        }
+
+        for l in loops {
+            writeln!(
+                f,
+                "Loop {} to {} went round {} times",
+                l.start, l.end, l.iterations
+            )?;
+        }
+        if self.halted {
+            writeln!(f, "The program halted")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const EXAMPLE: &str = "#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5";
+
+    #[test]
+    fn test_run_example() {
+        let program = Program::from_str(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+
+        machine.run().unwrap();
+        assert_eq!(machine.registers(), &[6, 5, 6, 0, 0, 9]);
+        assert_eq!(machine.executed(), 5);
+        assert_eq!(machine.ip(), 7);
+    }
+
+    #[test]
+    fn test_run_while() {
+        let program = Program::from_str(EXAMPLE).unwrap();
+        let mut machine = Machine::new(&program);
+
+        let halted = machine.run_while(|m| m.ip() != 4).unwrap();
+        assert!(!halted);
+        assert_eq!(machine.executed(), 3);
+        assert_eq!(machine.register(1), 5);
+
+        assert!(machine.run_while(|_| true).unwrap());
+    }
+
+    #[test]
+    fn test_bad_register() {
+        let program = Program::from_str("addr 0 7 1").unwrap();
+        assert!(Machine::new(&program).run().is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        // counts r1 up to 5, going round instructions 1 to 4
+        let program = Program::from_str(
+            "#ip 3
+seti 0 0 1
+addi 1 1 1
+gtri 1 4 2
+addr 3 2 3
+seti 0 0 3",
+        )
+        .unwrap();
+        let mut machine = Machine::new(&program);
+        let profile = machine.profile(1000).unwrap();
+
+        assert!(profile.halted);
+        assert_eq!(profile.counts, vec![1, 5, 5, 5, 4]);
+        assert_eq!(
+            profile.loops(),
+            vec![HotLoop {
+                start: 1,
+                end: 4,
+                iterations: 4
+            }]
+        );
+        assert_eq!(
+            profile.to_string(),
+            "  0   seti 0 0 1                  1
+  1 ┌ addi 1 1 1                  5
+  2 │ gtri 1 4 2                  5
+  3 │ addr 3 2 3                  5
+  4 └ seti 0 0 3                  4
+Loop 1 to 4 went round 4 times
+The program halted
+"
+        );
+
+        let mut machine = Machine::new(&program);
+        assert!(!machine.profile(3).unwrap().halted);
+    }
+}
@@ -0,0 +1,12 @@
+//! The elves' register machine from days 16, 19 and 21: sixteen opcodes, up to six registers,
+//! and optionally an instruction pointer bound to one of them.
+
+mod infer;
+mod machine;
+mod opcode;
+mod program;
+
+pub use self::infer::{infer_opcodes, Sample};
+pub use self::machine::{HotLoop, Machine};
+pub use self::opcode::{Instruction, Opcode};
+pub use self::program::Program;
@@ -0,0 +1,197 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// One of the sixteen operations. The letter at the end of the name says whether each input is
+/// a register (r) or an immediate value (i), the answer always going in register C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Opcode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+use self::Opcode::*;
+
+impl Opcode {
+    pub const ALL: [Opcode; 16] = [
+        Addr, Addi, Mulr, Muli, Banr, Bani, Borr, Bori, Setr, Seti, Gtir, Gtri, Gtrr, Eqir, Eqri,
+        Eqrr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Addr => "addr",
+            Addi => "addi",
+            Mulr => "mulr",
+            Muli => "muli",
+            Banr => "banr",
+            Bani => "bani",
+            Borr => "borr",
+            Bori => "bori",
+            Setr => "setr",
+            Seti => "seti",
+            Gtir => "gtir",
+            Gtri => "gtri",
+            Gtrr => "gtrr",
+            Eqir => "eqir",
+            Eqri => "eqri",
+            Eqrr => "eqrr",
+        }
+    }
+
+    /// Works out what should go in register C, or `None` if A or B name a register which
+    /// doesn't exist.
+    pub fn evaluate(self, a: i64, b: i64, registers: &[i64]) -> Option<i64> {
+        let reg = |n: i64| {
+            if n < 0 {
+                None
+            } else {
+                registers.get(n as usize).cloned()
+            }
+        };
+        let flag = |b: bool| if b { 1 } else { 0 };
+
+        Some(match self {
+            Addr => reg(a)? + reg(b)?,
+            Addi => reg(a)? + b,
+            Mulr => reg(a)? * reg(b)?,
+            Muli => reg(a)? * b,
+            Banr => reg(a)? & reg(b)?,
+            Bani => reg(a)? & b,
+            Borr => reg(a)? | reg(b)?,
+            Bori => reg(a)? | b,
+            Setr => reg(a)?,
+            Seti => a,
+            Gtir => flag(a > reg(b)?),
+            Gtri => flag(reg(a)? > b),
+            Gtrr => flag(reg(a)? > reg(b)?),
+            Eqir => flag(a == reg(b)?),
+            Eqri => flag(reg(a)? == b),
+            Eqrr => flag(reg(a)? == reg(b)?),
+        })
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Opcode::ALL
+            .iter()
+            .find(|o| o.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("{} isn't an opcode", s))
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, a: i64, b: i64, c: i64) -> Instruction {
+        Instruction { opcode, a, b, c }
+    }
+
+    /// Carries out the instruction on the registers, returning `false` and leaving them alone
+    /// if it refers to a register that isn't there.
+    pub fn apply(&self, registers: &mut [i64]) -> bool {
+        match self.opcode.evaluate(self.a, self.b, registers) {
+            Some(value) if self.c >= 0 && (self.c as usize) < registers.len() => {
+                registers[self.c as usize] = value;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        if words.len() != 4 {
+            return Err(format!("\"{}\" should be an opcode and three numbers", s));
+        }
+
+        let number = |w: &str| i64::from_str(w).map_err(|e| format!("{}: {}", w, e));
+        Ok(Instruction::new(
+            Opcode::from_str(words[0])?,
+            number(words[1])?,
+            number(words[2])?,
+            number(words[3])?,
+        ))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.opcode, self.a, self.b, self.c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let registers = [3, 2, 1, 1];
+
+        assert_eq!(Addr.evaluate(0, 1, &registers), Some(5));
+        assert_eq!(Addi.evaluate(0, 7, &registers), Some(10));
+        assert_eq!(Mulr.evaluate(0, 1, &registers), Some(6));
+        assert_eq!(Bani.evaluate(0, 6, &registers), Some(2));
+        assert_eq!(Borr.evaluate(0, 1, &registers), Some(3));
+        assert_eq!(Setr.evaluate(1, 99, &registers), Some(2));
+        assert_eq!(Seti.evaluate(9, 99, &registers), Some(9));
+        assert_eq!(Gtir.evaluate(3, 1, &registers), Some(1));
+        assert_eq!(Gtri.evaluate(1, 2, &registers), Some(0));
+        assert_eq!(Eqrr.evaluate(2, 3, &registers), Some(1));
+        assert_eq!(Eqri.evaluate(2, 3, &registers), Some(0));
+
+        assert_eq!(Addr.evaluate(0, 4, &registers), None);
+        assert_eq!(Seti.evaluate(4, 4, &registers), Some(4));
+    }
+
+    #[test]
+    fn test_parse_and_apply() {
+        let instruction = Instruction::from_str("mulr 2 1 2").unwrap();
+        assert_eq!(instruction, Instruction::new(Mulr, 2, 1, 2));
+        assert_eq!(instruction.to_string(), "mulr 2 1 2");
+
+        let mut registers = [3, 2, 1, 1];
+        assert!(instruction.apply(&mut registers));
+        assert_eq!(registers, [3, 2, 2, 1]);
+
+        assert!(!Instruction::new(Seti, 1, 0, 4).apply(&mut registers));
+        assert_eq!(registers, [3, 2, 2, 1]);
+
+        assert!(Instruction::from_str("jump 1 2 3").is_err());
+        assert!(Instruction::from_str("addi 1 2").is_err());
+    }
+}
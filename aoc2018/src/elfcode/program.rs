@@ -0,0 +1,100 @@
+use super::opcode::Instruction;
+use std::fmt;
+use std::str::FromStr;
+
+/// A list of instructions, with the register the instruction pointer is bound to if there's an
+/// `#ip` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub ip_register: Option<usize>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Program {
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn get(&self, index: i64) -> Option<&Instruction> {
+        if index < 0 {
+            None
+        } else {
+            self.instructions.get(index as usize)
+        }
+    }
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ip_register = None;
+        let mut instructions = vec![];
+
+        for (number, line) in s.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(register) = line.strip_prefix("#ip ") {
+                if ip_register.is_some() {
+                    return Err(format!(
+                        "Line {} binds the instruction pointer again",
+                        number
+                    ));
+                }
+                ip_register = Some(
+                    usize::from_str(register.trim())
+                        .map_err(|e| format!("Line {}: {}", number, e))?,
+                );
+            } else {
+                instructions.push(
+                    Instruction::from_str(line).map_err(|e| format!("Line {}: {}", number, e))?,
+                );
+            }
+        }
+
+        Ok(Program {
+            ip_register,
+            instructions,
+        })
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(register) = self.ip_register {
+            writeln!(f, "#ip {}", register)?;
+        }
+        for instruction in &self.instructions {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::opcode::Opcode;
+    use super::*;
+
+    #[test]
+    fn test_parse_program() {
+        let program = Program::from_str("#ip 0\nseti 5 0 1\n\nseti 6 0 2\n").unwrap();
+
+        assert_eq!(program.ip_register, Some(0));
+        assert_eq!(program.len(), 2);
+        assert_eq!(
+            program.get(1),
+            Some(&Instruction::new(Opcode::Seti, 6, 0, 2))
+        );
+        assert_eq!(program.get(-1), None);
+        assert_eq!(program.to_string(), "#ip 0\nseti 5 0 1\nseti 6 0 2\n");
+
+        assert_eq!(
+            Program::from_str("seti 5 0 1\nseti x 0 2"),
+            Err("Line 2: x: invalid digit found in string".to_owned())
+        );
+        assert!(Program::from_str("#ip 1\n#ip 2").is_err());
+    }
+}
@@ -5,7 +5,11 @@ mod day11;
 mod day12;
 mod day13;
 mod day14;
+mod day15;
+mod day16;
+mod day19;
 mod day2;
+mod day21;
 mod day3;
 mod day4;
 mod day5;
@@ -13,6 +17,7 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod elfcode;
 mod util;
 
 use crate::day::Day;
@@ -61,8 +66,19 @@ fn main() -> Result<(), String> {
             10 => timed(|| run_day(Arc::new(Mutex::new(day10::Day10::default())))),
             11 => timed(|| run_day(Arc::new(Mutex::new(day11::Day11::default())))),
             12 => timed(|| run_day(Arc::new(Mutex::new(day12::Day12::default())))),
-            13 => timed(|| run_day(Arc::new(Mutex::new(day13::Day13::default())))),
+            13 => timed(|| {
+                day13::Day13::new(args.get(2).map(String::as_str))
+                    .and_then(|d| run_day(Arc::new(Mutex::new(d))))
+            }),
             14 => timed(|| run_day(Arc::new(Mutex::new(day14::Day14::default())))),
+            15 => timed(|| {
+                run_day(Arc::new(Mutex::new(
+                    day15::Day15::new().expect("Day 15 could not parse input"),
+                )))
+            }),
+            16 => timed(|| day16::Day16::new().and_then(|d| run_day(Arc::new(Mutex::new(d))))),
+            19 => timed(|| day19::Day19::new().and_then(|d| run_day(Arc::new(Mutex::new(d))))),
+            21 => timed(|| day21::Day21::new().and_then(|d| run_day(Arc::new(Mutex::new(d))))),
             _ => (Err(format!("I don't know how to be day {} yet", day)), 0),
         };
 
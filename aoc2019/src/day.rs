@@ -1,4 +1,11 @@
+use aoc_common::input;
+
 pub trait Day {
     fn part1(&mut self) -> Result<String, String>;
     fn part2(&mut self) -> Result<String, String>;
 }
+
+/// Reads `src/day<NN>/input.txt` when the day is made, for the days whose input isn't checked in.
+pub fn read_input(day: u8) -> Result<String, String> {
+    input::read_input(env!("CARGO_MANIFEST_DIR"), &format!("day{:02}", day))
+}
@@ -1,5 +1,5 @@
 use crate::day::Day;
-use itertools::Itertools;
+use aoc_common::ocr::recognise;
 
 pub struct Day8 {
     image_layers: Vec<Vec<u8>>,
@@ -32,21 +32,7 @@ impl Day for Day8 {
             .map(|l| layer_to_pixels(l.as_slice()))
             .collect::<Result<Vec<Vec<Pixel>>, String>>()?;
         let result = stack_all_layers(pixel_layers.as_slice());
-        let result_string = result
-            .iter()
-            .chunks(25)
-            .into_iter()
-            .map(|line| {
-                line.map(|pixel| match pixel {
-                    Pixel::White => '\u{2588}',
-                    Pixel::Black => ' ',
-                    Pixel::Transparent => ' ',
-                })
-                .collect::<String>()
-            })
-            .intersperse("\n".to_owned())
-            .collect::<String>();
-        Ok(format!("\n{}", result_string))
+        recognise(25, 6, |x, y| result[y * 25 + x] == Pixel::White).map_err(|e| e.to_string())
     }
 }
 
@@ -0,0 +1,227 @@
+use crate::day::{read_input, Day};
+use aoc_common::numtheory::gcd;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+
+pub struct Day10 {
+    asteroids: Vec<Pos>,
+}
+
+impl Day10 {
+    pub fn new() -> Result<Day10, String> {
+        Ok(Day10 {
+            asteroids: parse_asteroids(&read_input(10)?),
+        })
+    }
+}
+
+impl Day for Day10 {
+    fn part1(&mut self) -> Result<String, String> {
+        let (station, visible) =
+            best_station(&self.asteroids).ok_or_else(|| "There are no asteroids".to_owned())?;
+        Ok(format!(
+            "The station at {},{} can see {} asteroids",
+            station.0, station.1, visible
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        let (station, _) =
+            best_station(&self.asteroids).ok_or_else(|| "There are no asteroids".to_owned())?;
+        let (x, y) = *vaporisation_order(station, &self.asteroids)
+            .get(199)
+            .ok_or_else(|| "There are fewer than 200 asteroids to vaporise".to_owned())?;
+        Ok(format!(
+            "The 200th asteroid vaporised is at {},{}, giving {}",
+            x,
+            y,
+            x * 100 + y
+        ))
+    }
+}
+
+type Pos = (i64, i64);
+
+fn parse_asteroids(input: &str) -> Vec<Pos> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.trim()
+                .chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x as i64, y as i64))
+        })
+        .collect()
+}
+
+/// The offset from one asteroid to another reduced to its smallest whole steps, so every
+/// asteroid along the same line of sight has the same direction.
+fn direction(from: Pos, to: Pos) -> Pos {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let divisor = gcd(dx, dy);
+    (dx / divisor, dy / divisor)
+}
+
+fn visible_from(station: Pos, asteroids: &[Pos]) -> usize {
+    asteroids
+        .iter()
+        .filter(|&&a| a != station)
+        .map(|&a| direction(station, a))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// The asteroid which can see the most others, and how many it can see.
+fn best_station(asteroids: &[Pos]) -> Option<(Pos, usize)> {
+    asteroids
+        .iter()
+        .map(|&a| (a, visible_from(a, asteroids)))
+        .max_by_key(|&(_, visible)| visible)
+}
+
+/// Orders directions clockwise starting from straight up, remembering that y increases down
+/// the map. Directions in the half starting from straight up come first, and within a half one
+/// direction is ahead of another if turning clockwise gets from it to the other.
+fn clockwise(a: &Pos, b: &Pos) -> Ordering {
+    let half = |&(dx, dy): &Pos| if dx > 0 || (dx == 0 && dy < 0) { 0 } else { 1 };
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| (b.0 * a.1).cmp(&(a.0 * b.1)))
+}
+
+/// The order the laser vaporises everything in as it sweeps round clockwise from straight up,
+/// only hitting the nearest asteroid in each direction on each rotation.
+fn vaporisation_order(station: Pos, asteroids: &[Pos]) -> Vec<Pos> {
+    let mut lines_of_sight = BTreeMap::new();
+    for &a in asteroids.iter().filter(|&&a| a != station) {
+        lines_of_sight
+            .entry(direction(station, a))
+            .or_insert_with(Vec::new)
+            .push(a);
+    }
+
+    let mut lines_of_sight = lines_of_sight.into_iter().collect::<Vec<_>>();
+    lines_of_sight.sort_by(|(a, _), (b, _)| clockwise(a, b));
+    for (_, line) in lines_of_sight.iter_mut() {
+        line.sort_by_key(|&(x, y)| (x - station.0).abs() + (y - station.1).abs());
+    }
+
+    let rotations = lines_of_sight.iter().map(|(_, line)| line.len()).max();
+    (0..rotations.unwrap_or(0))
+        .flat_map(|rotation| {
+            lines_of_sight
+                .iter()
+                .filter_map(move |(_, line)| line.get(rotation).cloned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+const LARGE_EXAMPLE: &str = ".#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##";
+
+#[test]
+fn test_small_example() {
+    let asteroids = parse_asteroids(
+        ".#..#
+.....
+#####
+....#
+...##",
+    );
+
+    assert_eq!(asteroids.len(), 10);
+    assert_eq!(visible_from((1, 0), &asteroids), 7);
+    assert_eq!(visible_from((4, 2), &asteroids), 5);
+    assert_eq!(best_station(&asteroids), Some(((3, 4), 8)));
+}
+
+#[test]
+fn test_direction() {
+    assert_eq!(direction((3, 4), (1, 0)), (-1, -2));
+    assert_eq!(direction((0, 0), (0, 5)), (0, 1));
+    assert_eq!(direction((2, 2), (8, -1)), (2, -1));
+}
+
+#[test]
+fn test_clockwise() {
+    let directions = [
+        (0, -1),
+        (1, -2),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-2, -1),
+        (-1, -1),
+    ];
+    for (i, a) in directions.iter().enumerate() {
+        for (j, b) in directions.iter().enumerate() {
+            assert_eq!(clockwise(a, b), i.cmp(&j), "{:?} and {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_large_example_station() {
+    let asteroids = parse_asteroids(LARGE_EXAMPLE);
+    assert_eq!(best_station(&asteroids), Some(((11, 13), 210)));
+}
+
+#[test]
+fn test_large_example_vaporisation() {
+    let order = vaporisation_order((11, 13), &parse_asteroids(LARGE_EXAMPLE));
+
+    assert_eq!(order.len(), 299);
+    for &(n, expected) in &[
+        (1, (11, 12)),
+        (2, (12, 1)),
+        (3, (12, 2)),
+        (10, (12, 8)),
+        (20, (16, 0)),
+        (50, (16, 9)),
+        (100, (10, 16)),
+        (199, (9, 6)),
+        (200, (8, 2)),
+        (201, (10, 9)),
+        (299, (11, 1)),
+    ] {
+        assert_eq!(order[n - 1], expected, "asteroid {}", n);
+    }
+}
+
+#[test]
+fn test_nearest_goes_first() {
+    let asteroids = parse_asteroids(
+        ".#.
+.#.
+.##
+.#.",
+    );
+
+    // the column above the station takes three rotations to clear
+    let order = vaporisation_order((1, 3), &asteroids);
+    assert_eq!(order, vec![(1, 2), (2, 2), (1, 1), (1, 0)]);
+}
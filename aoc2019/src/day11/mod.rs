@@ -1,5 +1,6 @@
 use crate::day::Day;
 use crate::intcode::{Program, State};
+use aoc_common::ocr::recognise_points;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::str::FromStr;
@@ -34,7 +35,7 @@ impl Day for Day11 {
         grid.set_colour_at((0, 0), Colour::White);
         robot.paint_hull(&mut grid);
 
-        Ok(format!("\n{}", grid.to_string()))
+        recognise_points(grid.white_panels()).map_err(|e| e.to_string())
     }
 }
 
@@ -103,6 +104,13 @@ impl Grid {
         *(self.painted.entry(pos).or_default()) = colour
     }
 
+    fn white_panels(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.painted
+            .iter()
+            .filter(|(_, colour)| **colour == Colour::White)
+            .map(|((x, y), _)| (*x as i64, *y as i64))
+    }
+
     fn turn_left_from((x, y): (i32, i32), facing: Facing) -> ((i32, i32), Facing) {
         match facing {
             Facing::Up => ((x - 1, y), facing.left()),
@@ -1,12 +1,15 @@
 use crate::day::Day;
+use aoc_common::cycle::brent;
+#[cfg(test)]
+use aoc_common::numtheory::lcm;
+use aoc_common::numtheory::lcm_all;
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use num_integer::lcm;
 use regex::Regex;
 use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::HashSet;
 use std::fmt::Debug;
+use std::iter::successors;
 use std::ops::{Add, Mul};
 use std::rc::Rc;
 use std::str::FromStr;
@@ -54,7 +57,7 @@ fn run_until_repeat(moons: &Vec<Moon>) -> u64 {
     let y_repeats_in = find_y_repeat(moons);
     let z_repeats_in = find_z_repeat(moons);
 
-    find_least_common_multiple(x_repeats_in, y_repeats_in, z_repeats_in)
+    lcm_all(vec![x_repeats_in, y_repeats_in, z_repeats_in])
 }
 
 #[test]
@@ -76,7 +79,7 @@ fn test_big_sample_find_repeat_parts() {
     assert_eq!(4686774924 % lcm(x_repeats_in, z_repeats_in), 0, "X Z");
     assert_eq!(4686774924 % lcm(y_repeats_in, z_repeats_in), 0, "Y Z");
 
-    let repeat = find_least_common_multiple(x_repeats_in, y_repeats_in, z_repeats_in);
+    let repeat = lcm_all(vec![x_repeats_in, y_repeats_in, z_repeats_in]);
     assert_eq!(repeat, 4686774924);
 }
 
@@ -93,32 +96,6 @@ fn test_big_sample_find_repeat() {
     assert_eq!(repeat, 4686774924);
 }
 
-fn find_least_common_multiple(a: u64, b: u64, c: u64) -> u64 {
-    let largest_lcm = *[lcm(a, b), lcm(b, c), lcm(a, c)].iter().max().unwrap();
-    let mut current = largest_lcm;
-
-    loop {
-        let a_rem = current % a;
-        let b_rem = current % b;
-        let c_rem = current % c;
-
-        if a_rem == 0 && b_rem == 0 && c_rem == 0 {
-            break;
-        } else {
-            current += largest_lcm;
-        }
-    }
-
-    current
-}
-
-#[test]
-fn test_find_least_common_multiple() {
-    assert_eq!(find_least_common_multiple(1, 1, 1), 1);
-    assert_eq!(find_least_common_multiple(2, 4, 1), 4);
-    assert_eq!(find_least_common_multiple(10, 20, 30), 60);
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Moon {
     pos: Vector,
@@ -319,27 +296,20 @@ fn run_step(moons: Vec<Moon>) -> Vec<Moon> {
         .collect()
 }
 
+/// Each axis moves independently of the others, and the simulation can be run backwards, so
+/// each axis on its own loops back round to where it started. The whole system repeats when
+/// all of them do at once.
 fn find_repeat_by<F>(moons: &Vec<Moon>, f: F) -> u64
 where
     F: Fn(&Moon) -> (i32, i32) + Copy,
 {
-    let mut iterations = 0;
-    let mut previous_states = HashSet::new();
-
-    let mut xs = moons.iter().map(f).collect::<Vec<_>>();
-    previous_states.insert(xs.clone());
-
-    loop {
-        iterations += 1;
-        let new_state = run_axis_step(xs.clone());
-        if previous_states.contains(&new_state) {
-            break;
-        } else {
-            xs = new_state;
-        }
-    }
+    let states = successors(Some(moons.iter().map(f).collect::<Vec<_>>()), |xs| {
+        Some(run_axis_step(xs.clone()))
+    });
 
-    iterations
+    brent(states)
+        .expect("The moons should come back round eventually")
+        .period as u64
 }
 
 fn extract_x_axis(moon: &Moon) -> (i32, i32) {
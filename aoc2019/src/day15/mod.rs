@@ -0,0 +1,160 @@
+use crate::day::{read_input, Day};
+use crate::intcode::{Program, State};
+use std::collections::{HashMap, VecDeque};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day15 {
+    program: Program<i64>,
+}
+
+impl Day15 {
+    pub fn new() -> Result<Day15, String> {
+        Ok(Day15 {
+            program: Program::from_str(&read_input(15)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+}
+
+impl Day for Day15 {
+    fn part1(&mut self) -> Result<String, String> {
+        let map = explore(&self.program)?;
+        let oxygen = oxygen_system(&map)?;
+        let distance = distances_from(&map, (0, 0))[&oxygen];
+        Ok(format!(
+            "The oxygen system is {} moves from the start",
+            distance
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        let map = explore(&self.program)?;
+        let oxygen = oxygen_system(&map)?;
+        Ok(format!(
+            "It takes {} minutes for the oxygen to fill the area",
+            minutes_to_fill(&map, oxygen)
+        ))
+    }
+}
+
+type Pos = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+/// The movement commands the droid understands, with which way they go.
+const MOVES: [(i64, Pos); 4] = [(1, (0, -1)), (2, (0, 1)), (3, (-1, 0)), (4, (1, 0))];
+
+/// Maps out everywhere the droid can get to. Each position reached keeps its own copy of the
+/// droid's program, so trying a direction from there is just a case of cloning it rather than
+/// walking back.
+fn explore(program: &Program<i64>) -> Result<HashMap<Pos, Tile>, String> {
+    let mut map = HashMap::new();
+    map.insert((0, 0), Tile::Open);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(((0, 0), program.run_until_needs_interaction()));
+
+    while let Some(((x, y), droid)) = queue.pop_front() {
+        for &(command, (dx, dy)) in MOVES.iter() {
+            let next = (x + dx, y + dy);
+            if map.contains_key(&next) {
+                continue;
+            }
+
+            let moved = droid.clone().resume_with_input(command);
+            let tile = match moved.state {
+                State::ProvidedOutput(0) => Tile::Wall,
+                State::ProvidedOutput(1) => Tile::Open,
+                State::ProvidedOutput(2) => Tile::Oxygen,
+                other => return Err(format!("The droid replied {:?}", other)),
+            };
+            map.insert(next, tile);
+
+            if tile != Tile::Wall {
+                queue.push_back((next, moved.resume()));
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+fn oxygen_system(map: &HashMap<Pos, Tile>) -> Result<Pos, String> {
+    map.iter()
+        .find(|(_, &tile)| tile == Tile::Oxygen)
+        .map(|(&pos, _)| pos)
+        .ok_or_else(|| "The droid never found the oxygen system".to_owned())
+}
+
+/// How many moves it takes to get to every open position from `start`.
+fn distances_from(map: &HashMap<Pos, Tile>, start: Pos) -> HashMap<Pos, usize> {
+    let mut distances = HashMap::new();
+    distances.insert(start, 0);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some((x, y)) = queue.pop_front() {
+        let distance = distances[&(x, y)];
+        for &(_, (dx, dy)) in MOVES.iter() {
+            let next = (x + dx, y + dy);
+            let open = map.get(&next).is_some_and(|&t| t != Tile::Wall);
+            if open && !distances.contains_key(&next) {
+                distances.insert(next, distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Oxygen spreads one step a minute, so the time to fill everywhere is the distance to the
+/// furthest point.
+fn minutes_to_fill(map: &HashMap<Pos, Tile>, oxygen: Pos) -> usize {
+    distances_from(map, oxygen)
+        .values()
+        .cloned()
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+fn parse_map(map: &str) -> HashMap<Pos, Tile> {
+    map.lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars().enumerate().filter_map(move |(x, c)| {
+                let tile = match c {
+                    '#' => Tile::Wall,
+                    '.' | 'D' => Tile::Open,
+                    'O' => Tile::Oxygen,
+                    _ => return None,
+                };
+                Some(((x as i64, y as i64), tile))
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_minutes_to_fill() {
+    let map = parse_map(
+        " ##   
+#..## 
+#.#..#
+#.O.# 
+ ###  ",
+    );
+    let oxygen = oxygen_system(&map).unwrap();
+
+    assert_eq!(oxygen, (2, 3));
+    assert_eq!(minutes_to_fill(&map, oxygen), 4);
+    assert_eq!(distances_from(&map, oxygen)[&(1, 1)], 3);
+}
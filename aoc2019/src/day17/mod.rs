@@ -0,0 +1,393 @@
+use crate::day::{read_input, Day};
+use crate::intcode::{ascii, Program, State};
+use std::collections::HashSet;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day17 {
+    program: Program<i64>,
+}
+
+impl Day17 {
+    pub fn new() -> Result<Day17, String> {
+        Ok(Day17 {
+            program: Program::from_str(&read_input(17)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+}
+
+impl Day for Day17 {
+    fn part1(&mut self) -> Result<String, String> {
+        let view = View::from_str(&camera_output(&self.program))?;
+        Ok(format!(
+            "The alignment parameters add up to {}",
+            view.alignment_parameters()
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        let view = View::from_str(&camera_output(&self.program))?;
+        let path = view.path();
+        let routines = Routines::compress(&path)
+            .ok_or_else(|| "Couldn't fit the path into three movement functions".to_owned())?;
+
+        let mut program = self.program.clone();
+        program[0] = 2;
+        let (outputs, state) = program.run_until_needs_interaction().collect_outputs();
+        if state.state != State::NeedsInput {
+            return Err("The robot didn't ask for its movement routines".to_owned());
+        }
+
+        let (more, state) = state
+            .resume_with_inputs(ascii::encode(&format!("{}n\n", routines)))
+            .collect_outputs();
+        if state.state != State::Completed {
+            return Err("The robot wanted more input than it was given".to_owned());
+        }
+
+        let outputs = outputs.into_iter().chain(more).collect::<Vec<_>>();
+        match ascii::decode(&outputs) {
+            (_, ref dust) if dust.len() == 1 => Ok(format!("The robot collected {} dust", dust[0])),
+            (text, _) => Err(format!("The robot didn't report any dust:\n{}", text)),
+        }
+    }
+}
+
+fn camera_output(program: &Program<i64>) -> String {
+    ascii::decode(&program.run_pure(&vec![])).0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn step(self, (x, y): (i64, i64)) -> (i64, i64) {
+        match self {
+            Direction::Up => (x, y - 1),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
+        }
+    }
+
+    fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    fn turn_right(self) -> Direction {
+        self.turn_left().turn_left().turn_left()
+    }
+}
+
+/// What the cameras show: where the scaffolding is, and where the robot is and which way it's
+/// facing.
+#[derive(Debug)]
+struct View {
+    scaffold: HashSet<(i64, i64)>,
+    robot: (i64, i64),
+    facing: Direction,
+}
+
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scaffold = HashSet::new();
+        let mut robot = None;
+
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let pos = (x as i64, y as i64);
+                let facing = match c {
+                    '#' => {
+                        scaffold.insert(pos);
+                        continue;
+                    }
+                    '.' | 'X' => continue,
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    '<' => Direction::Left,
+                    '>' => Direction::Right,
+                    _ => return Err(format!("Unexpected {} in the camera view", c)),
+                };
+                scaffold.insert(pos);
+                robot = Some((pos, facing));
+            }
+        }
+
+        let (robot, facing) = robot.ok_or_else(|| "The robot isn't in view".to_owned())?;
+        Ok(View {
+            scaffold,
+            robot,
+            facing,
+        })
+    }
+}
+
+impl View {
+    fn intersections(&self) -> impl Iterator<Item = &(i64, i64)> {
+        self.scaffold.iter().filter(move |&&pos| {
+            [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ]
+            .iter()
+            .all(|d| self.scaffold.contains(&d.step(pos)))
+        })
+    }
+
+    fn alignment_parameters(&self) -> i64 {
+        self.intersections().map(|(x, y)| x * y).sum()
+    }
+
+    /// The route along the scaffolding, going straight over every junction, as turns each
+    /// followed by a number of steps forward.
+    fn path(&self) -> Vec<Move> {
+        let mut path = vec![];
+        let mut position = self.robot;
+        let mut facing = self.facing;
+
+        loop {
+            let (turn, direction) = if self.scaffold.contains(&facing.turn_left().step(position)) {
+                (Turn::Left, facing.turn_left())
+            } else if self.scaffold.contains(&facing.turn_right().step(position)) {
+                (Turn::Right, facing.turn_right())
+            } else {
+                return path;
+            };
+
+            facing = direction;
+            let mut steps = 0;
+            while self.scaffold.contains(&facing.step(position)) {
+                position = facing.step(position);
+                steps += 1;
+            }
+            path.push(Move { turn, steps });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    turn: Turn,
+    steps: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let turn = match self.turn {
+            Turn::Left => 'L',
+            Turn::Right => 'R',
+        };
+        write!(f, "{},{}", turn, self.steps)
+    }
+}
+
+/// The robot's memory only holds 20 characters per line, not counting the newline.
+const MEMORY_LIMIT: usize = 20;
+
+fn length(moves: &[Move]) -> usize {
+    moves.iter().map(|m| m.to_string().len() + 1).sum::<usize>() - 1
+}
+
+/// A main routine calling three movement functions, A, B and C.
+#[derive(Debug, PartialEq, Eq)]
+struct Routines {
+    main: Vec<usize>,
+    functions: Vec<Vec<Move>>,
+}
+
+impl Routines {
+    /// Finds a way of splitting the path into at most three functions, each short enough to
+    /// fit in the robot's memory, along with a main routine to call them.
+    fn compress(path: &[Move]) -> Option<Routines> {
+        let mut routines = Routines {
+            main: vec![],
+            functions: vec![],
+        };
+        if routines.compress_from(path) {
+            while routines.functions.len() < 3 {
+                routines.functions.push(vec![]);
+            }
+            Some(routines)
+        } else {
+            None
+        }
+    }
+
+    fn compress_from(&mut self, rest: &[Move]) -> bool {
+        if rest.is_empty() {
+            return true;
+        }
+        if self.main.len() * 2 + 1 > MEMORY_LIMIT {
+            return false;
+        }
+
+        // carry on with a function we've already got
+        for f in 0..self.functions.len() {
+            let function = &self.functions[f];
+            if rest.starts_with(function) {
+                let len = function.len();
+                self.main.push(f);
+                if self.compress_from(&rest[len..]) {
+                    return true;
+                }
+                self.main.pop();
+            }
+        }
+
+        // or start a new one
+        if self.functions.len() < 3 {
+            for len in 1..=rest.len() {
+                if length(&rest[..len]) > MEMORY_LIMIT {
+                    break;
+                }
+                self.functions.push(rest[..len].to_vec());
+                self.main.push(self.functions.len() - 1);
+                if self.compress_from(&rest[len..]) {
+                    return true;
+                }
+                self.main.pop();
+                self.functions.pop();
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+impl Routines {
+    fn expand(&self) -> Vec<Move> {
+        self.main
+            .iter()
+            .flat_map(|&f| self.functions[f].iter().cloned())
+            .collect()
+    }
+}
+
+impl fmt::Display for Routines {
+    /// The lines the robot wants as its input, each ending with a newline.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let main = self
+            .main
+            .iter()
+            .map(|&f| ((b'A' + f as u8) as char).to_string())
+            .collect::<Vec<_>>();
+        writeln!(f, "{}", main.join(","))?;
+
+        for function in &self.functions {
+            let moves = function.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+            writeln!(f, "{}", moves.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_alignment_parameters() {
+    let view = View::from_str(
+        "..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..",
+    )
+    .unwrap();
+
+    assert_eq!(view.intersections().count(), 4);
+    assert_eq!(view.alignment_parameters(), 76);
+}
+
+#[cfg(test)]
+const ROUTE_EXAMPLE: &str = "#######...#####
+#.....#...#...#
+#.....#...#...#
+......#...#...#
+......#...###.#
+......#.....#.#
+^########...#.#
+......#.#...#.#
+......#########
+........#...#..
+....#########..
+....#...#......
+....#...#......
+....#...#......
+....#####......";
+
+#[test]
+fn test_path() {
+    let view = View::from_str(ROUTE_EXAMPLE).unwrap();
+    let path = view
+        .path()
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    assert_eq!(
+        path,
+        "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+    );
+}
+
+#[test]
+fn test_compress() {
+    let path = View::from_str(ROUTE_EXAMPLE).unwrap().path();
+    let routines = Routines::compress(&path).expect("The example should compress");
+
+    assert_eq!(routines.expand(), path);
+    assert_eq!(routines.functions.len(), 3);
+    for line in routines.to_string().lines() {
+        assert!(line.len() <= MEMORY_LIMIT, "{} is too long", line);
+    }
+}
+
+#[test]
+fn test_routines_display() {
+    let routines = Routines {
+        main: vec![0, 1, 0],
+        functions: vec![
+            vec![Move {
+                turn: Turn::Right,
+                steps: 8,
+            }],
+            vec![
+                Move {
+                    turn: Turn::Left,
+                    steps: 10,
+                },
+                Move {
+                    turn: Turn::Right,
+                    steps: 4,
+                },
+            ],
+            vec![],
+        ],
+    };
+
+    assert_eq!(routines.to_string(), "A,B,A\nR,8\nL,10,R,4\n\n");
+}
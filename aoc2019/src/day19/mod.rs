@@ -0,0 +1,106 @@
+use crate::day::{read_input, Day};
+use crate::intcode::Program;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day19 {
+    program: Program<i64>,
+}
+
+impl Day19 {
+    pub fn new() -> Result<Day19, String> {
+        Ok(Day19 {
+            program: Program::from_str(&read_input(19)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+
+    fn pulled(&self, x: i64, y: i64) -> bool {
+        self.program.run_pure(&vec![x, y]) == vec![1]
+    }
+}
+
+impl Day for Day19 {
+    fn part1(&mut self) -> Result<String, String> {
+        Ok(format!(
+            "{} points in the 50x50 area are affected by the beam",
+            affected_points(|x, y| self.pulled(x, y), 50)
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        let (x, y) = closest_square(|x, y| self.pulled(x, y), 100);
+        Ok(format!(
+            "The ship fits into the beam at {},{}, giving {}",
+            x,
+            y,
+            x * 10000 + y
+        ))
+    }
+}
+
+fn affected_points<F>(beam: F, size: i64) -> usize
+where
+    F: Fn(i64, i64) -> bool,
+{
+    (0..size)
+        .flat_map(|y| (0..size).map(move |x| (x, y)))
+        .filter(|&(x, y)| beam(x, y))
+        .count()
+}
+
+/// The top left corner of the first `size` by `size` square which fits entirely in the beam.
+///
+/// Following the left edge of the beam down, a square with its bottom left corner there fits if
+/// its top right corner is also in the beam. Each drone deployment is a whole run of the
+/// program, so this only checks two points per row.
+fn closest_square<F>(beam: F, size: i64) -> (i64, i64)
+where
+    F: Fn(i64, i64) -> bool,
+{
+    let mut left = 0;
+    for y in size - 1.. {
+        // the beam is so narrow near the emitter that some rows miss it altogether
+        let edge = match (left..=left + y).find(|&x| beam(x, y)) {
+            Some(edge) => edge,
+            None => continue,
+        };
+        left = edge;
+
+        if beam(left + size - 1, y - size + 1) {
+            return (left, y - size + 1);
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+fn cone(x: i64, y: i64) -> bool {
+    x * 4 >= y * 3 && x * 4 <= y * 5
+}
+
+#[test]
+fn test_affected_points() {
+    let expected = (0..10)
+        .map(|y: i64| (0..10).filter(|&x| cone(x, y)).count())
+        .sum::<usize>();
+    assert_eq!(affected_points(cone, 10), expected);
+}
+
+#[test]
+fn test_closest_square() {
+    for &size in &[1, 3, 10] {
+        let fits = |x: i64, y: i64| {
+            cone(x, y)
+                && cone(x + size - 1, y)
+                && cone(x, y + size - 1)
+                && cone(x + size - 1, y + size - 1)
+        };
+        let brute_force = (0..200)
+            .flat_map(|y| (0..200).map(move |x| (x, y)))
+            .find(|&(x, y)| fits(x, y))
+            .unwrap();
+
+        assert_eq!(closest_square(cone, size), brute_force, "size {}", size);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::day::{read_input, Day};
+use crate::intcode::{ascii, Program};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day21 {
+    program: Program<i64>,
+}
+
+impl Day21 {
+    pub fn new() -> Result<Day21, String> {
+        Ok(Day21 {
+            program: Program::from_str(&read_input(21)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+
+    fn survey(&self, script: &str, command: &str) -> Result<i64, String> {
+        let input = ascii::encode(&format!("{}{}\n", script, command));
+        match ascii::decode(&self.program.run_pure(&input)) {
+            (_, ref damage) if damage.len() == 1 => Ok(damage[0]),
+            (text, _) => Err(format!("The springdroid didn't make it:\n{}", text)),
+        }
+    }
+}
+
+impl Day for Day21 {
+    fn part1(&mut self) -> Result<String, String> {
+        Ok(format!(
+            "Walking, the hull damage is {}",
+            self.survey(WALK, "WALK")?
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        Ok(format!(
+            "Running, the hull damage is {}",
+            self.survey(RUN, "RUN")?
+        ))
+    }
+}
+
+/// Jump if there's a hole in the next three tiles and somewhere to land: J = (!A | !B | !C) & D
+const WALK: &str = "NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+";
+
+/// As for walking, but only if it's possible to carry on from the landing spot, by either
+/// stepping or jumping straight away: J = (!A | !B | !C) & D & (E | H)
+const RUN: &str = "NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+NOT E T
+NOT T T
+OR H T
+AND T J
+";
+
+/// Runs a springscript against sensor readings, returning whether the droid jumps.
+#[cfg(test)]
+fn jumps(script: &str, sensors: &[bool]) -> bool {
+    let (mut t, mut j) = (false, false);
+    for line in script.lines() {
+        let parts = line.split(' ').collect::<Vec<_>>();
+        let x = match parts[1] {
+            "T" => t,
+            "J" => j,
+            register => sensors[(register.as_bytes()[0] - b'A') as usize],
+        };
+        let y = if parts[2] == "T" { &mut t } else { &mut j };
+        match parts[0] {
+            "AND" => *y = *y && x,
+            "OR" => *y = *y || x,
+            "NOT" => *y = !x,
+            other => panic!("Unknown instruction {}", other),
+        }
+    }
+    j
+}
+
+/// Walks the droid along a hull, returning whether it gets across.
+#[cfg(test)]
+fn survives(script: &str, hull: &str, range: usize) -> bool {
+    let ground = |i: usize| hull.as_bytes().get(i) != Some(&b'.');
+    let mut position = 0;
+    while position < hull.len() {
+        let sensors = (1..=range)
+            .map(|i| ground(position + i))
+            .collect::<Vec<_>>();
+        position += if jumps(script, &sensors) { 4 } else { 1 };
+        if !ground(position) {
+            return false;
+        }
+    }
+    true
+}
+
+#[test]
+fn test_walk() {
+    for hull in &[
+        "#####.###########",
+        "#####...#########",
+        "#####..#.########",
+    ] {
+        assert!(survives(WALK, hull, 4), "{}", hull);
+        assert!(survives(RUN, hull, 9), "{}", hull);
+    }
+}
+
+#[test]
+fn test_run() {
+    let hull = "#####.#.##..#.###";
+    assert!(!survives(WALK, hull, 4));
+    assert!(survives(RUN, hull, 9));
+}
@@ -0,0 +1,205 @@
+use crate::day::{read_input, Day};
+use crate::intcode::{Program, RunState, State};
+use std::collections::VecDeque;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day23 {
+    program: Program<i64>,
+}
+
+impl Day23 {
+    pub fn new() -> Result<Day23, String> {
+        Ok(Day23 {
+            program: Program::from_str(&read_input(23)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+}
+
+impl Day for Day23 {
+    fn part1(&mut self) -> Result<String, String> {
+        Ok(format!(
+            "The first Y value sent to address 255 is {}",
+            first_nat_packet(&self.program, COMPUTERS)?
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        Ok(format!(
+            "The first Y value the NAT sends twice in a row is {}",
+            first_repeated_wake_up(&self.program, COMPUTERS)?
+        ))
+    }
+}
+
+const COMPUTERS: usize = 50;
+const NAT: i64 = 255;
+
+type Packet = (i64, i64);
+
+/// Computers which take turns to run until they're waiting on input, passing packets between
+/// each other as they go.
+struct Network {
+    computers: Vec<Option<RunState<i64>>>,
+    queues: Vec<VecDeque<Packet>>,
+}
+
+impl Network {
+    /// Boots up `size` computers, each told its own address.
+    fn new(program: &Program<i64>, size: usize) -> Result<(Network, Vec<Packet>), String> {
+        let mut network = Network {
+            computers: vec![],
+            queues: vec![VecDeque::new(); size],
+        };
+
+        let mut for_nat = vec![];
+        for address in 0..size {
+            let booted = program
+                .run_until_needs_interaction()
+                .resume_with_input(address as i64);
+            let computer = network.send_outputs(booted, &mut for_nat)?;
+            network.computers.push(computer);
+        }
+        Ok((network, for_nat))
+    }
+
+    /// Gives every computer the packets waiting for it, or -1 if there aren't any, and runs it
+    /// until it wants more. Returns anything sent to the NAT, and whether the network was idle:
+    /// nobody had anything to receive or sent anything.
+    fn round(&mut self) -> Result<(Vec<Packet>, bool), String> {
+        let mut for_nat = vec![];
+        let mut idle = true;
+
+        for address in 0..self.computers.len() {
+            let computer = match self.computers[address].take() {
+                Some(computer) => computer,
+                None => continue,
+            };
+
+            let inputs = if self.queues[address].is_empty() {
+                vec![-1]
+            } else {
+                idle = false;
+                self.queues[address]
+                    .drain(..)
+                    .flat_map(|(x, y)| vec![x, y])
+                    .collect()
+            };
+
+            let sent = self.packets_in_flight();
+            let computer = self.send_outputs(computer.resume_with_inputs(inputs), &mut for_nat)?;
+            if self.packets_in_flight() != sent || !for_nat.is_empty() {
+                idle = false;
+            }
+            self.computers[address] = computer;
+        }
+
+        Ok((for_nat, idle))
+    }
+
+    fn packets_in_flight(&self) -> usize {
+        self.queues.iter().map(|q| q.len()).sum()
+    }
+
+    /// Routes whatever a computer sends, keeping it around if it's still running.
+    fn send_outputs(
+        &mut self,
+        computer: RunState<i64>,
+        for_nat: &mut Vec<Packet>,
+    ) -> Result<Option<RunState<i64>>, String> {
+        let (outputs, computer) = computer.collect_outputs();
+        if outputs.len() % 3 != 0 {
+            return Err(format!(
+                "A computer sent an incomplete packet: {:?}",
+                outputs
+            ));
+        }
+
+        for packet in outputs.chunks(3) {
+            let (address, packet) = (packet[0], (packet[1], packet[2]));
+            if address == NAT {
+                for_nat.push(packet);
+            } else if address >= 0 && (address as usize) < self.queues.len() {
+                self.queues[address as usize].push_back(packet);
+            } else {
+                return Err(format!("A packet was sent to unknown address {}", address));
+            }
+        }
+
+        Ok(match computer.state {
+            State::Completed => None,
+            _ => Some(computer),
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        self.computers.iter().any(|c| c.is_some())
+    }
+}
+
+fn first_nat_packet(program: &Program<i64>, size: usize) -> Result<i64, String> {
+    let (mut network, mut for_nat) = Network::new(program, size)?;
+    while for_nat.is_empty() {
+        if !network.is_running() {
+            return Err("Nothing was ever sent to the NAT".to_owned());
+        }
+        for_nat = network.round()?.0;
+    }
+    Ok(for_nat[0].1)
+}
+
+/// The NAT remembers the last packet sent to it, and when the network goes idle it sends that on
+/// to address 0 to get things going again.
+fn first_repeated_wake_up(program: &Program<i64>, size: usize) -> Result<i64, String> {
+    let (mut network, for_nat) = Network::new(program, size)?;
+    let mut remembered = for_nat.last().cloned();
+    let mut last_sent = None;
+
+    while network.is_running() {
+        let (for_nat, idle) = network.round()?;
+        if let Some(&packet) = for_nat.last() {
+            remembered = Some(packet);
+        }
+
+        if idle {
+            let packet = remembered.ok_or_else(|| "The network went idle too soon".to_owned())?;
+            if last_sent == Some(packet.1) {
+                return Ok(packet.1);
+            }
+            last_sent = Some(packet.1);
+            network.queues[0].push_back(packet);
+        }
+    }
+    Err("The network shut down".to_owned())
+}
+
+/// Sends its own address to the NAT as both X and Y when it boots, then ignores everything.
+#[cfg(test)]
+const REPORT_ADDRESS: &str = "3,100,104,255,4,100,4,100,3,101,1105,1,8";
+
+#[test]
+fn test_first_nat_packet() {
+    let program = Program::from_str(REPORT_ADDRESS).unwrap();
+    assert_eq!(first_nat_packet(&program, 50), Ok(0));
+}
+
+#[test]
+fn test_first_repeated_wake_up() {
+    let program = Program::from_str(REPORT_ADDRESS).unwrap();
+    assert_eq!(first_repeated_wake_up(&program, 50), Ok(49));
+}
+
+#[test]
+fn test_packets_are_delivered() {
+    // each computer sends its address to computer 0, which forwards whatever it gets to the NAT
+    let program = Program::from_str(
+        "3,100,104,0,4,100,4,100,3,102,1007,102,0,103,1005,103,8,3,104,104,255,4,102,4,104,1105,1,8",
+    )
+    .unwrap();
+    let (mut network, for_nat) = Network::new(&program, 3).unwrap();
+    assert!(for_nat.is_empty());
+
+    assert_eq!(network.round(), Ok((vec![(0, 0), (1, 1), (2, 2)], false)));
+    assert_eq!(network.round(), Ok((vec![], true)));
+}
@@ -0,0 +1,299 @@
+use crate::day::{read_input, Day};
+use crate::intcode::{ascii, Program, RunState, State};
+use std::collections::HashSet;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub struct Day25 {
+    program: Program<i64>,
+}
+
+impl Day25 {
+    pub fn new() -> Result<Day25, String> {
+        Ok(Day25 {
+            program: Program::from_str(&read_input(25)?)
+                .map_err(|e: ParseIntError| e.to_string())?,
+        })
+    }
+}
+
+impl Day for Day25 {
+    fn part1(&mut self) -> Result<String, String> {
+        let (mut droid, text) = Droid::new(&self.program)?;
+        let mut explorer = Explorer::default();
+        explorer.explore(&mut droid, Room::from_str(&text)?, None)?;
+        Ok(format!(
+            "The password for the main airlock is {}",
+            explorer.get_through_checkpoint(&mut droid)?
+        ))
+    }
+
+    fn part2(&mut self) -> Result<String, String> {
+        Ok("There's no part two on Christmas Day".to_owned())
+    }
+}
+
+/// Items which either end the game or stop the droid moving.
+const DANGEROUS: [&str; 5] = [
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+const CHECKPOINT: &str = "Security Checkpoint";
+
+fn opposite(direction: &str) -> &'static str {
+    match direction {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        _ => "east",
+    }
+}
+
+/// What the droid says when it arrives somewhere.
+#[derive(Debug, PartialEq, Eq)]
+struct Room {
+    name: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+impl FromStr for Room {
+    type Err = String;
+
+    /// Reads the first room described, as a rejected droid describes the room it was turned away
+    /// from before the one it ends up in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().skip_while(|line| !line.starts_with("== "));
+        let name = lines
+            .next()
+            .map(|line| line.trim_matches(|c| c == '=' || c == ' ').to_owned())
+            .ok_or_else(|| format!("Couldn't find a room in:\n{}", s))?;
+
+        let mut room = Room {
+            name,
+            doors: vec![],
+            items: vec![],
+        };
+        let mut list = None;
+        for line in lines.take_while(|line| !line.starts_with("== ")) {
+            match line {
+                "Doors here lead:" => list = Some(&mut room.doors),
+                "Items here:" => list = Some(&mut room.items),
+                _ if line.starts_with("- ") => {
+                    if let Some(ref mut list) = list {
+                        list.push(line[2..].to_owned());
+                    }
+                }
+                _ => list = None,
+            }
+        }
+        Ok(room)
+    }
+}
+
+fn password(text: &str) -> Option<&str> {
+    let start = text.find("typing ")? + "typing ".len();
+    text[start..].split_whitespace().next()
+}
+
+/// The droid, waiting for its next command.
+struct Droid {
+    state: Option<RunState<i64>>,
+}
+
+impl Droid {
+    fn new(program: &Program<i64>) -> Result<(Droid, String), String> {
+        let mut droid = Droid {
+            state: Some(program.run_until_needs_interaction()),
+        };
+        let text = droid.read()?;
+        Ok((droid, text))
+    }
+
+    fn command(&mut self, command: &str) -> Result<String, String> {
+        let state = self
+            .state
+            .take()
+            .ok_or_else(|| "The droid has shut down".to_owned())?;
+        self.state = Some(state.resume_with_inputs(ascii::encode(&format!("{}\n", command))));
+        self.read()
+    }
+
+    fn read(&mut self) -> Result<String, String> {
+        let state = self
+            .state
+            .take()
+            .ok_or_else(|| "The droid has shut down".to_owned())?;
+        let (outputs, state) = state.collect_outputs();
+        if state.state != State::Completed {
+            self.state = Some(state);
+        }
+        Ok(ascii::decode(&outputs).0)
+    }
+}
+
+#[derive(Default)]
+struct Explorer {
+    visited: HashSet<String>,
+    inventory: Vec<String>,
+    route: Vec<String>,
+    to_checkpoint: Option<Vec<String>>,
+    to_floor: Option<String>,
+}
+
+impl Explorer {
+    /// Visits every room reachable from this one, picking up everything safe, and leaves the
+    /// droid back where it started. Along the way it notes the way to the checkpoint, and which
+    /// door there leads to the pressure-sensitive floor.
+    fn explore(
+        &mut self,
+        droid: &mut Droid,
+        room: Room,
+        came_from: Option<&str>,
+    ) -> Result<(), String> {
+        self.visited.insert(room.name.clone());
+        if room.name == CHECKPOINT {
+            self.to_checkpoint = Some(self.route.clone());
+        }
+
+        for item in &room.items {
+            if !DANGEROUS.contains(&item.as_str()) {
+                droid.command(&format!("take {}", item))?;
+                self.inventory.push(item.clone());
+            }
+        }
+
+        for door in &room.doors {
+            if Some(door.as_str()) == came_from {
+                continue;
+            }
+
+            let text = droid.command(door)?;
+            if text.contains("Alert!") {
+                // turned away, so still at the checkpoint
+                self.to_floor = Some(door.clone());
+                continue;
+            }
+
+            let next = Room::from_str(&text)?;
+            let back = opposite(door);
+            if !self.visited.contains(&next.name) {
+                self.route.push(door.clone());
+                self.explore(droid, next, Some(back))?;
+                self.route.pop();
+            }
+            droid.command(back)?;
+        }
+        Ok(())
+    }
+
+    /// Goes to the checkpoint and tries carrying each combination of items onto the floor until
+    /// one weighs the right amount.
+    fn get_through_checkpoint(&self, droid: &mut Droid) -> Result<String, String> {
+        let route = self
+            .to_checkpoint
+            .as_ref()
+            .ok_or_else(|| "The droid never found the checkpoint".to_owned())?;
+        let to_floor = self
+            .to_floor
+            .as_ref()
+            .ok_or_else(|| "The droid never found the pressure-sensitive floor".to_owned())?;
+
+        for direction in route {
+            droid.command(direction)?;
+        }
+
+        let mut carrying = self.inventory.iter().collect::<HashSet<_>>();
+        for combination in 0..1u32 << self.inventory.len() {
+            for (i, item) in self.inventory.iter().enumerate() {
+                let wanted = combination & (1 << i) != 0;
+                if wanted && !carrying.contains(item) {
+                    droid.command(&format!("take {}", item))?;
+                    carrying.insert(item);
+                } else if !wanted && carrying.contains(item) {
+                    droid.command(&format!("drop {}", item))?;
+                    carrying.remove(item);
+                }
+            }
+
+            let text = droid.command(to_floor)?;
+            if !text.contains("Alert!") {
+                return password(&text)
+                    .map(|p| p.to_owned())
+                    .ok_or_else(|| format!("Couldn't find the password in:\n{}", text));
+            }
+        }
+        Err("No combination of items got the droid through".to_owned())
+    }
+}
+
+#[test]
+fn test_parse_room() {
+    let text = "
+
+== Hull Breach ==
+You got in through a hole in the floor here.
+
+Doors here lead:
+- north
+- east
+
+Items here:
+- mug
+- weather machine
+
+Command?
+";
+
+    assert_eq!(
+        Room::from_str(text),
+        Ok(Room {
+            name: "Hull Breach".to_owned(),
+            doors: vec!["north".to_owned(), "east".to_owned()],
+            items: vec!["mug".to_owned(), "weather machine".to_owned()],
+        })
+    );
+}
+
+#[test]
+fn test_parse_rejected_room() {
+    let text = "
+
+== Pressure-Sensitive Floor ==
+Analyzing...
+
+Doors here lead:
+- west
+
+A loud, robotic voice says \"Alert! Droids on this ship are heavier than the detected value!\" and you are ejected back to the checkpoint.
+
+== Security Checkpoint ==
+In the next room, a pressure-sensitive floor will verify your identity.
+
+Doors here lead:
+- north
+- east
+
+Command?
+";
+
+    let room = Room::from_str(text).unwrap();
+    assert_eq!(room.name, "Pressure-Sensitive Floor");
+    assert_eq!(room.doors, vec!["west".to_owned()]);
+    assert!(room.items.is_empty());
+}
+
+#[test]
+fn test_password() {
+    let text = "A loud, robotic voice says \"Analysis complete! You may proceed.\" and you enter the cockpit.
+Santa notices your small droid, looks puzzled for a moment, realizes what has happened, and radios your ship directly.
+\"Oh, hello! You should be able to get in by typing 2424308736 on the keypad at the main airlock.\"
+";
+
+    assert_eq!(password(text), Some("2424308736"));
+    assert_eq!(password("Command?"), None);
+}
@@ -0,0 +1,40 @@
+//! Helpers for programs which talk in ASCII, one character per input or output.
+
+use std::convert::TryFrom;
+
+/// Turns text into inputs, one per character.
+pub fn encode<N: From<i32>>(text: &str) -> Vec<N> {
+    text.chars().map(|c| N::from(c as i32)).collect()
+}
+
+/// Turns outputs back into text. Anything which isn't an ASCII character, usually the answer
+/// to the puzzle, is returned separately.
+pub fn decode<N: Copy>(outputs: &[N]) -> (String, Vec<N>)
+where
+    usize: TryFrom<N>,
+{
+    let mut text = String::new();
+    let mut others = vec![];
+
+    for &o in outputs {
+        match usize::try_from(o) {
+            Ok(c) if c < 128 => text.push(c as u8 as char),
+            _ => others.push(o),
+        }
+    }
+
+    (text, others)
+}
+
+#[test]
+fn test_encode() {
+    assert_eq!(encode::<i64>("A,B\n"), vec![65, 44, 66, 10]);
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(
+        decode(&[72i64, 105, 10, 1234567, -1]),
+        ("Hi\n".to_owned(), vec![1234567, -1])
+    );
+}
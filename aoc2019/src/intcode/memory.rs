@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 /// An abstraction over a contiguous array of memory which can auto-grow when necessary
+#[derive(Clone)]
 pub struct Memory<N>(Vec<N>);
 
 impl<N> Memory<N>
@@ -1,4 +1,5 @@
 use crate::intcode::memory::Memory;
+use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::ops::Add;
@@ -8,6 +9,7 @@ use std::ops::Mul;
 use std::ops::Rem;
 use std::str::FromStr;
 
+pub mod ascii;
 mod memory;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -35,6 +37,9 @@ pub enum State<N> {
     Completed,
 }
 
+/// A program stopped part way through. Cloning one gives an independent copy which can carry on
+/// from the same point, which is handy for trying out different inputs.
+#[derive(Clone)]
 pub struct RunState<N> {
     pub state: State<N>,
     runner: ProgramRunner<N>,
@@ -61,6 +66,30 @@ where
         RunState::next(runner)
     }
 
+    /// Queues up several inputs, which the program will take in order, then resumes.
+    pub fn resume_with_inputs<I>(self, inputs: I) -> RunState<N>
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut runner = self.runner;
+        for input in inputs {
+            runner.provide_input(input);
+        }
+        RunState::next(runner)
+    }
+
+    /// Resumes past any outputs until the program needs input or completes, returning the
+    /// outputs along with where it stopped.
+    pub fn collect_outputs(self) -> (Vec<N>, RunState<N>) {
+        let mut outputs = vec![];
+        let mut state = self;
+        while let State::ProvidedOutput(o) = state.state {
+            outputs.push(o);
+            state = state.resume();
+        }
+        (outputs, state)
+    }
+
     fn next(mut runner: ProgramRunner<N>) -> RunState<N> {
         loop {
             match runner.run_until_state_change() {
@@ -159,11 +188,12 @@ enum ProgramState<N> {
     Completed,
 }
 
+#[derive(Clone)]
 struct ProgramRunner<N> {
     memory: Memory<N>,
     program_counter: usize,
     relative_offset: N,
-    inputs: Vec<N>,
+    inputs: VecDeque<N>,
     state: ProgramState<N>,
 }
 
@@ -183,7 +213,7 @@ where
             memory: Memory::from(locations),
             program_counter: 0,
             relative_offset: 0i32.into(),
-            inputs: Vec::new(),
+            inputs: VecDeque::new(),
             state: ProgramState::NotStarted,
         }
     }
@@ -288,7 +318,7 @@ where
     }
 
     fn provide_input(&mut self, input: N) {
-        self.inputs.push(input)
+        self.inputs.push_back(input)
     }
 
     fn input(&mut self) -> ProgramState<N> {
@@ -297,7 +327,10 @@ where
         } else {
             let mode = self.unary_parameter_mode();
             let write_location = self.output_parameter_write_location(1, mode);
-            let input = self.inputs.pop().expect("Cannot run input: no more inputs");
+            let input = self
+                .inputs
+                .pop_front()
+                .expect("Cannot run input: no more inputs");
             self.memory.set(write_location, input);
             self.advance(2);
             ProgramState::Running
@@ -621,3 +654,29 @@ fn test_input_relative() {
 
     assert_eq!(outputs, vec![66]);
 }
+
+#[test]
+fn test_resume_with_inputs_keeps_order() {
+    // adds two inputs together and outputs the result, twice over
+    let program = Program::<i64>::from_str("3,100,3,101,1,100,101,102,4,102,1105,1,0").unwrap();
+
+    let state = program
+        .run_until_needs_interaction()
+        .resume_with_inputs(vec![5, -2, 10, 20]);
+    assert_eq!(state.state, State::ProvidedOutput(3));
+
+    let (outputs, state) = state.collect_outputs();
+    assert_eq!(outputs, vec![3, 30]);
+    assert_eq!(state.state, State::NeedsInput);
+}
+
+#[test]
+fn test_cloned_run_states_are_independent() {
+    // outputs double each input it's given
+    let program = Program::<i64>::from_str("3,100,1002,100,2,100,4,100,1105,1,0").unwrap();
+    let state = program.run_until_needs_interaction();
+
+    let copy = state.clone();
+    assert_eq!(state.resume_with_input(4).state, State::ProvidedOutput(8));
+    assert_eq!(copy.resume_with_input(7).state, State::ProvidedOutput(14));
+}
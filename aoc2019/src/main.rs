@@ -8,10 +8,17 @@ mod day06;
 mod day07;
 mod day08;
 mod day09;
+mod day10;
 mod day11;
 mod day12;
 mod day13;
 mod day14;
+mod day15;
+mod day17;
+mod day19;
+mod day21;
+mod day23;
+mod day25;
 mod intcode;
 
 use crate::day::Day;
@@ -63,10 +70,17 @@ fn make_day(day: u8) -> Result<Box<dyn Day>, String> {
         7 => Ok(Box::new(day07::Day7::new()?)),
         8 => Ok(Box::new(day08::Day8::new()?)),
         9 => Ok(Box::new(day09::Day9::new()?)),
+        10 => Ok(Box::new(day10::Day10::new()?)),
         11 => Ok(Box::new(day11::Day11::new()?)),
         12 => Ok(Box::new(day12::Day12::new()?)),
         13 => Ok(Box::new(day13::Day13::new()?)),
         14 => Ok(Box::new(day14::Day14::new()?)),
+        15 => Ok(Box::new(day15::Day15::new()?)),
+        17 => Ok(Box::new(day17::Day17::new()?)),
+        19 => Ok(Box::new(day19::Day19::new()?)),
+        21 => Ok(Box::new(day21::Day21::new()?)),
+        23 => Ok(Box::new(day23::Day23::new()?)),
+        25 => Ok(Box::new(day25::Day25::new()?)),
         _ => Err(format!("I don't know how to make day {} yet", day)),
     }
 }
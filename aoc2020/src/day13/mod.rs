@@ -1,6 +1,7 @@
 use crate::DayError;
+use aoc_common::numtheory::crt;
 use itertools::Itertools;
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr};
 
 pub fn part1() -> Result<String, DayError> {
     let input = include_str!("input.txt");
@@ -18,7 +19,7 @@ pub fn part1() -> Result<String, DayError> {
 pub fn part2() -> Result<String, DayError> {
     let input = include_str!("input.txt");
     let requirements = parse_input_part2(input)?;
-    let answer = solve_part2(&requirements);
+    let answer = solve_part2(&requirements).ok_or_else(|| DayError::NoSolutionFoundError)?;
     Ok(format!("The earliest time is {}", answer))
 }
 
@@ -158,56 +159,28 @@ fn test_parse_part2_input() {
 }
 
 #[test]
-fn test_part2_with_daniels_solution() {
+fn test_part2_sample() {
     let input = "939
 7,13,x,x,59,x,31,19
 ";
     let requirements = parse_input_part2(input).expect("We expect this input to parse");
     let result = solve_part2(&requirements);
 
-    assert_eq!(result, 1068781);
+    assert_eq!(result, Some(1068781));
 }
 
-/// This solution borrowed from Daniel Patrick
-/// with some notes by me trying to understand why it works
-fn solve_part2(requirements: &BusReqs) -> i128 {
-    // turn the bus requirements into a map of bus number -> bus time offset from the time we're looking for
-    let buses = requirements
+/// Bus `b` at position `i` in the list has to leave `i` minutes after the time we're looking
+/// for, so that time must be `-i` modulo `b` for every bus. The Chinese Remainder Theorem
+/// finds the earliest time satisfying all of them at once.
+fn solve_part2(requirements: &BusReqs) -> Option<i64> {
+    let congruences = requirements
         .requirements
         .iter()
         .enumerate()
         .filter_map(|(i, r)| match r {
-            &BusReq::Bus(b) => Some((b as i128, (b as i128 - i as i128))),
+            &BusReq::Bus(b) => Some((-(i as i64), b as i64)),
             BusReq::Free => None,
-        })
-        .collect::<HashMap<_, _>>();
-
-    // least common multiple of all the time intervals involved
-    let product = buses.keys().product::<i128>();
-
-    let mut total = 0;
-
-    // go through the map of buses
-    for (divisor, remainder) in buses {
-        if remainder == 0 {
-            // if this is the bus on relative timestamp 0 we need do nothing
-            continue;
-        }
-
-        let mut iteration = 1;
-        let other_buses_time = product / divisor;
-
-        // figure out how many times we have to go through all the other buses before we align to this one
-        while iteration * other_buses_time % divisor != 1 {
-            iteration += 1;
-        }
-
-        // add that to the total overall
-        // this is not finding the earliest time, it's just finding a time where everything lines up
-        // it stacks one bus after another, gradually aligning each one each time through the loop
-        total += other_buses_time * remainder * iteration;
-    }
+        });
 
-    // and so now we have that instance that's really super massive, smash it down to size using the LCM of all the bus times
-    return total % product;
+    crt(congruences).map(|(time, _)| time)
 }
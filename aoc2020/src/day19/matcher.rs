@@ -0,0 +1,320 @@
+use super::{rule_to_regex_text, Rule};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Matches messages against a rule set. Regular grammars are compiled to a regex; anything
+/// else (recursion, or `SameNumberOf`) falls back to a general matcher which works out every
+/// position each rule could finish at from each starting point.
+pub(crate) enum Matcher<'a> {
+    Regular(Regex),
+    General {
+        rules: &'a HashMap<usize, Rule>,
+        root: usize,
+    },
+}
+
+impl<'a> Matcher<'a> {
+    pub fn new(rules: &'a HashMap<usize, Rule>, root: usize) -> Matcher<'a> {
+        if is_regular(rules, root) {
+            if let Some(text) = rule_to_regex_text(&rules[&root], rules) {
+                return Matcher::Regular(Regex::new(&format!("^{}$", text)).unwrap());
+            }
+        }
+        Matcher::General { rules, root }
+    }
+
+    pub fn is_match(&self, message: &str) -> bool {
+        match self {
+            Matcher::Regular(re) => re.is_match(message),
+            Matcher::General { rules, root } => {
+                let message = message.chars().collect::<Vec<_>>();
+                let mut memo = Memo::default();
+                loop {
+                    let ends = end_positions(rules, *root, &message, 0, &mut memo);
+                    if !memo.next_pass() {
+                        return ends.contains(&message.len());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A grammar is regular (for our purposes) if no rule can reach itself and nothing
+/// reachable from the root needs `SameNumberOf`.
+fn is_regular(rules: &HashMap<usize, Rule>, root: usize) -> bool {
+    fn visit(rules: &HashMap<usize, Rule>, index: usize, path: &mut Vec<usize>) -> bool {
+        if path.contains(&index) {
+            return false;
+        }
+        let rule = match rules.get(&index) {
+            Some(r) => r,
+            None => return false,
+        };
+        path.push(index);
+        let result = match rule {
+            Rule::Terminal(_) => true,
+            Rule::Sequence(s) => s.iter().all(|i| visit(rules, *i, path)),
+            Rule::Alternative(l, r) => l.iter().chain(r.iter()).all(|i| visit(rules, *i, path)),
+            Rule::OneOrMore(i) => visit(rules, *i, path),
+            Rule::SameNumberOf(_, _) => false,
+        };
+        path.pop();
+        result
+    }
+
+    visit(rules, root, &mut Vec::new())
+}
+
+/// What's known so far about where each rule can finish from each starting position.
+///
+/// Left recursion means a rule can need its own answer before it has one. Rather than give up,
+/// it makes do with what's been found so far, which starts out empty, and the whole match is
+/// run again until a pass finds nothing new. Each pass can only add end positions, so this
+/// settles on everything the grammar allows.
+#[derive(Default)]
+struct Memo {
+    ends: HashMap<(usize, usize), HashSet<usize>>,
+    in_progress: HashSet<(usize, usize)>,
+    done: HashSet<(usize, usize)>,
+    changed: bool,
+}
+
+impl Memo {
+    fn known(&self, key: (usize, usize)) -> HashSet<usize> {
+        self.ends.get(&key).cloned().unwrap_or_default()
+    }
+
+    /// Starts another pass, returning false if the last one didn't learn anything.
+    fn next_pass(&mut self) -> bool {
+        self.done.clear();
+        std::mem::replace(&mut self.changed, false)
+    }
+}
+
+/// All the positions in `message` that rule `index` could finish at, if it starts at `start`.
+fn end_positions(
+    rules: &HashMap<usize, Rule>,
+    index: usize,
+    message: &[char],
+    start: usize,
+    memo: &mut Memo,
+) -> HashSet<usize> {
+    let key = (index, start);
+    if memo.done.contains(&key) || !memo.in_progress.insert(key) {
+        return memo.known(key);
+    }
+
+    let result = match rules.get(&index) {
+        None => HashSet::new(),
+        Some(Rule::Terminal(c)) => {
+            if message.get(start) == Some(c) {
+                vec![start + 1].into_iter().collect()
+            } else {
+                HashSet::new()
+            }
+        }
+        Some(Rule::Sequence(s)) => sequence_end_positions(rules, s, message, start, memo),
+        Some(Rule::Alternative(l, r)) => {
+            let mut ends = sequence_end_positions(rules, l, message, start, memo);
+            ends.extend(sequence_end_positions(rules, r, message, start, memo));
+            ends
+        }
+        Some(Rule::OneOrMore(i)) => {
+            let mut ends = HashSet::new();
+            let mut frontier = end_positions(rules, *i, message, start, memo);
+            while !frontier.is_empty() {
+                let mut next_frontier = HashSet::new();
+                for position in frontier {
+                    if ends.insert(position) {
+                        next_frontier.extend(end_positions(rules, *i, message, position, memo));
+                    }
+                }
+                frontier = next_frontier;
+            }
+            ends
+        }
+        Some(Rule::SameNumberOf(first, second)) => {
+            let mut ends = HashSet::new();
+            let mut firsts = end_positions(rules, *first, message, start, memo);
+            let mut count = 1;
+            while !firsts.is_empty() && start + count <= message.len() {
+                let repeated = vec![*second; count];
+                for position in firsts.iter() {
+                    ends.extend(sequence_end_positions(
+                        rules, &repeated, message, *position, memo,
+                    ));
+                }
+                firsts = firsts
+                    .into_iter()
+                    .flat_map(|p| end_positions(rules, *first, message, p, memo))
+                    .collect();
+                count += 1;
+            }
+            ends
+        }
+    };
+
+    memo.in_progress.remove(&key);
+    memo.done.insert(key);
+    let known = memo.ends.entry(key).or_default();
+    if !result.is_subset(known) {
+        known.extend(result);
+        memo.changed = true;
+    }
+    known.clone()
+}
+
+fn sequence_end_positions(
+    rules: &HashMap<usize, Rule>,
+    sequence: &[usize],
+    message: &[char],
+    start: usize,
+    memo: &mut Memo,
+) -> HashSet<usize> {
+    let mut positions: HashSet<usize> = vec![start].into_iter().collect();
+    for index in sequence {
+        positions = positions
+            .into_iter()
+            .flat_map(|p| end_positions(rules, *index, message, p, memo))
+            .collect();
+        if positions.is_empty() {
+            break;
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+use super::parse_rules;
+
+#[test]
+fn test_regular_grammar_uses_regex() {
+    let rules = parse_rules(
+        "0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"",
+    )
+    .unwrap();
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matches!(matcher, Matcher::Regular(_)));
+    assert!(matcher.is_match("ababbb"));
+    assert!(!matcher.is_match("bababa"));
+}
+
+#[test]
+fn test_general_matcher_agrees_on_regular_grammar() {
+    let rules = parse_rules(
+        "0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"",
+    )
+    .unwrap();
+    let matcher = Matcher::General {
+        rules: &rules,
+        root: 0,
+    };
+    assert!(matcher.is_match("ababbb"));
+    assert!(matcher.is_match("abbbab"));
+    assert!(!matcher.is_match("bababa"));
+    assert!(!matcher.is_match("aaabbb"));
+    assert!(!matcher.is_match("aaaabbb"));
+}
+
+#[test]
+fn test_same_number_of_beyond_four() {
+    let mut rules = parse_rules(
+        "0: 8 11
+8: 42
+11: 42 31
+42: \"a\"
+31: \"b\"",
+    )
+    .unwrap();
+    rules.insert(8, Rule::OneOrMore(42));
+    rules.insert(11, Rule::SameNumberOf(42, 31));
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matches!(matcher, Matcher::General { .. }));
+    assert!(matcher.is_match("aab"));
+    assert!(!matcher.is_match("aabb"));
+    assert!(matcher.is_match("aaaaaabbbbb"));
+    assert!(matcher.is_match(&format!("{}{}", "a".repeat(30), "b".repeat(20))));
+    assert!(!matcher.is_match("aaaaabbbbb"));
+    assert!(!matcher.is_match("abab"));
+}
+
+#[test]
+fn test_recursive_rules() {
+    // the rules exactly as the puzzle gives them for part 2
+    let rules = parse_rules(
+        "0: 8 11
+8: 42 | 42 8
+11: 42 31 | 42 11 31
+42: \"a\"
+31: \"b\"",
+    )
+    .unwrap();
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matches!(matcher, Matcher::General { .. }));
+    assert!(matcher.is_match("aab"));
+    assert!(!matcher.is_match("aabb"));
+    assert!(matcher.is_match("aaaaaabbbbb"));
+    assert!(!matcher.is_match("aaaaabbbbb"));
+}
+
+#[test]
+fn test_left_recursion_terminates() {
+    let rules = parse_rules(
+        "0: 1 2
+1: 2 | 1 2
+2: \"a\"",
+    )
+    .unwrap();
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matcher.is_match("aa"));
+    assert!(!matcher.is_match("b"));
+}
+
+#[test]
+fn test_left_recursion_repeats() {
+    let rules = parse_rules(
+        "0: 1 2
+1: 2 | 1 2
+2: \"a\"",
+    )
+    .unwrap();
+    let matcher = Matcher::new(&rules, 0);
+    assert!(!matcher.is_match("a"));
+    assert!(matcher.is_match("aaa"));
+    assert!(matcher.is_match(&"a".repeat(20)));
+    assert!(!matcher.is_match("aaab"));
+}
+
+#[test]
+fn test_nested_left_recursion() {
+    // 1 and 3 are each left recursive through the other
+    let rules = parse_rules(
+        "0: 1 5
+1: 3 | 1 5
+3: 4 2 | 1 4 2
+2: \"b\"
+4: \"a\"
+5: \"c\"",
+    )
+    .unwrap();
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matches!(matcher, Matcher::General { .. }));
+    assert!(matcher.is_match("abc"));
+    assert!(matcher.is_match("ababc"));
+    assert!(matcher.is_match("abccc"));
+    assert!(matcher.is_match("ababcabcc"));
+    assert!(!matcher.is_match("ab"));
+    assert!(!matcher.is_match("abcab"));
+    assert!(!matcher.is_match("aabc"));
+}
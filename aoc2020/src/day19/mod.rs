@@ -1,6 +1,8 @@
+mod matcher;
+
+use self::matcher::Matcher;
 use crate::dayerror::DayError;
 use itertools::Itertools;
-use regex::Regex;
 use std::{collections::HashMap, str::FromStr};
 
 //mod part2;
@@ -29,8 +31,8 @@ fn run_part2(rules: &str, messages: &str) -> Result<usize, DayError> {
     rules.insert(8, Rule::OneOrMore(42));
     rules.insert(11, Rule::SameNumberOf(42, 31));
 
-    let re = compile_rules(&rules, 0);
-    Ok(messages.lines().filter(|l| re.is_match(l)).count())
+    let matcher = Matcher::new(&rules, 0);
+    Ok(messages.lines().filter(|l| matcher.is_match(l)).count())
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -99,47 +101,33 @@ fn parse_rules(input: &str) -> Result<HashMap<usize, Rule>, DayError> {
         .collect::<Result<HashMap<_, _>, _>>()
 }
 
-fn compile_rules(rules: &HashMap<usize, Rule>, root_index: usize) -> Regex {
-    let root_rule = &rules[&root_index];
-    let source = format!("^{}$", rule_to_regex_text(root_rule, rules));
-    Regex::new(&source).unwrap()
-}
-
-fn rule_to_regex_text(rule: &Rule, rules: &HashMap<usize, Rule>) -> String {
-    match rule {
-        Rule::Terminal(c) => format!("{}", c),
-        Rule::Sequence(s) => s
-            .iter()
-            .map(|i| rule_to_regex_text(&rules[i], rules))
-            .join(""),
-        Rule::Alternative(l, r) => format!(
-            "(({})|({}))",
-            l.iter()
+/// Only meaningful for regular grammars - it will recurse forever on rules which refer to
+/// themselves, and gives up on SameNumberOf, which no regex can express.
+fn rule_to_regex_text(rule: &Rule, rules: &HashMap<usize, Rule>) -> Option<String> {
+    let sequence_text = |s: &Vec<usize>| -> Option<String> {
+        Some(
+            s.iter()
                 .map(|i| rule_to_regex_text(&rules[i], rules))
+                .collect::<Option<Vec<_>>>()?
+                .iter()
                 .join(""),
-            r.iter()
-                .map(|i| rule_to_regex_text(&rules[i], rules))
-                .join(""),
-        ),
-        Rule::OneOrMore(i) => format!("({})+", rule_to_regex_text(&rules[i], rules)),
-        Rule::SameNumberOf(first, second) => {
-            // okay so we can't do this generically because it's not a regex
-            // but maybe we can do up to a certain number
-            let first = rule_to_regex_text(&rules[first], rules);
-            let second = rule_to_regex_text(&rules[second], rules);
-            format!(
-                "(({f}{s})|({f}{{2}}{s}{{2}})|({f}{{3}}{s}{{3}})|({f}{{4}}{s}{{4}}))",
-                f = first,
-                s = second
-            )
+        )
+    };
+    match rule {
+        Rule::Terminal(c) => Some(format!("{}", c)),
+        Rule::Sequence(s) => sequence_text(s),
+        Rule::Alternative(l, r) => {
+            Some(format!("(({})|({}))", sequence_text(l)?, sequence_text(r)?))
         }
+        Rule::OneOrMore(i) => Some(format!("({})+", rule_to_regex_text(&rules[i], rules)?)),
+        Rule::SameNumberOf(_, _) => None,
     }
 }
 
 fn run_part1(rules: &str, messages: &str) -> Result<usize, DayError> {
     let rules = parse_rules(rules)?;
-    let re = compile_rules(&rules, 0);
-    Ok(messages.lines().filter(|l| re.is_match(l)).count())
+    let matcher = Matcher::new(&rules, 0);
+    Ok(messages.lines().filter(|l| matcher.is_match(l)).count())
 }
 
 #[test]
@@ -153,12 +141,12 @@ fn test_part1_sample() {
 5: \"b\"",
     )
     .unwrap();
-    let re = compile_rules(&rules, 0);
-    assert!(re.is_match("ababbb"));
-    assert!(re.is_match("abbbab"));
-    assert!(!re.is_match("bababa"));
-    assert!(!re.is_match("aaabbb"));
-    assert!(!re.is_match("aaaabbb"));
+    let matcher = Matcher::new(&rules, 0);
+    assert!(matcher.is_match("ababbb"));
+    assert!(matcher.is_match("abbbab"));
+    assert!(!matcher.is_match("bababa"));
+    assert!(!matcher.is_match("aaabbb"));
+    assert!(!matcher.is_match("aaaabbb"));
 }
 
 #[test]
@@ -1,8 +1,6 @@
-mod ring;
-
-use self::ring::Ring;
 use crate::dayerror::DayError;
-use std::{fmt::Debug, fmt::Display, hash::Hash, str::FromStr};
+use aoc_common::circular::{CircularList, DenseKey};
+use std::{fmt::Debug, fmt::Display, str::FromStr};
 
 pub fn part1(visualise: bool) -> Result<String, DayError> {
     let answer = run_part1("368195742", visualise)?;
@@ -20,9 +18,14 @@ pub fn part2(visualise: bool) -> Result<String, DayError> {
     ))
 }
 
-fn make_move<T>(cups: &mut Ring<T>, current_cup: T, visualise: bool) -> T
+fn make_move<T>(
+    cups: &mut CircularList<T>,
+    current_cup: T,
+    (lowest_cup, highest_cup): (T, T),
+    visualise: bool,
+) -> T
 where
-    T: Debug + Display + Eq + PartialOrd + CheckedDecrement + Copy + Hash,
+    T: Debug + Display + Eq + PartialOrd + CheckedDecrement + Copy + DenseKey,
 {
     if visualise {
         println!(
@@ -30,34 +33,39 @@ where
             current_cup, cups
         );
     }
-    if !cups.contains(&current_cup) {
+    let current_node = cups.find(&current_cup).unwrap_or_else(|| {
         panic!(
             "Attempt to make a move when the current cup {} does not exist in the circle!",
             current_cup
-        );
-    }
+        )
+    });
 
     let removed = cups
-        .remove_three_after(&current_cup)
+        .detach_after(current_node, 3)
         .expect("Don't call this on a ring without enough cups in it!");
+    let removed_cups = cups.detached_values(&removed).cloned().collect::<Vec<_>>();
     if visualise {
-        println!("Removed {:?}", removed);
+        println!("Removed {:?}", removed_cups);
     }
 
-    let destination = find_destination_cup(cups, &current_cup);
+    let destination = find_destination_cup(&current_cup, &removed_cups, lowest_cup, highest_cup);
     if visualise {
         println!("Destination cup is {}", destination);
     }
 
-    cups.insert_three_after(&destination, removed);
+    let destination_node = cups
+        .find(&destination)
+        .expect("The destination cup should be in the circle");
+    cups.splice_after(destination_node, removed)
+        .expect("The destination can't be one of the cups picked up");
     if visualise {
         println!("Move complete: {:?}", cups);
     }
 
-    let new_current_cup = cups
-        .value_after(&current_cup)
-        .expect("There should always be a new current cup")
-        .clone();
+    let new_current_cup = *cups
+        .next(current_node)
+        .and_then(|n| cups.get(n))
+        .expect("There should always be a new current cup");
 
     if visualise {
         println!("New current cup: {}", new_current_cup);
@@ -66,54 +74,55 @@ where
     new_current_cup
 }
 
-fn find_destination_cup<T>(cups: &Ring<T>, current_cup: &T) -> T
+/// Cups are labelled with every value from `lowest_cup` to `highest_cup`, so the destination
+/// is the next label down which isn't one of the cups that have just been picked up.
+fn find_destination_cup<T>(current_cup: &T, removed_cups: &[T], lowest_cup: T, highest_cup: T) -> T
 where
-    T: PartialOrd + CheckedDecrement + Eq + Copy + Hash,
+    T: PartialOrd + CheckedDecrement + Eq + Copy,
 {
-    let minus_one = current_cup.checked_decrement();
-    match minus_one {
-        None => (cups
-            .highest_value()
-            .expect("Don't call this on an empty circle!"))
-        .clone(),
-        Some(n) => {
-            if cups.contains(&n) {
-                n
-            } else {
-                find_destination_cup(cups, &n)
-            }
+    let mut candidate = *current_cup;
+    loop {
+        candidate = match candidate.checked_decrement() {
+            Some(n) if n >= lowest_cup => n,
+            _ => highest_cup,
+        };
+        if !removed_cups.contains(&candidate) {
+            return candidate;
         }
     }
 }
 
-fn make_moves<T>(cups: &mut Ring<T>, current_cup: T, moves: usize, visualise: bool)
+fn make_moves<T>(cups: &mut CircularList<T>, current_cup: T, moves: usize, visualise: bool)
 where
-    T: Debug + Display + Eq + PartialOrd + CheckedDecrement + Copy + Hash,
+    T: Debug + Display + Ord + CheckedDecrement + Copy + DenseKey,
 {
+    let cup_range = cup_range(cups);
     let mut current_cup = current_cup;
     for m in 1..=moves {
         if visualise {
             println!("** MOVE {} **", m);
         }
-        current_cup = make_move(cups, current_cup, visualise);
+        current_cup = make_move(cups, current_cup, cup_range, visualise);
     }
 }
 
-fn gather_order(cups: &Ring<u8>) -> Vec<u8> {
-    let ordered_values = cups.iter().collect::<Vec<&u8>>();
-    let index_of_one = ordered_values
+fn cup_range<T: Ord + Copy>(cups: &CircularList<T>) -> (T, T) {
+    let lowest = *cups
         .iter()
-        .enumerate()
-        .filter(|(_, v)| v == &&&1)
-        .next()
-        .expect("1 must be in the circle")
-        .0;
-    let mut ordered_cups_from_one = Vec::new();
-    for n in 0..ordered_values.len() {
-        ordered_cups_from_one
-            .push(ordered_values[(n + index_of_one) % ordered_values.len()].clone());
-    }
-    ordered_cups_from_one
+        .min()
+        .expect("Don't call this on an empty circle!");
+    let highest = *cups
+        .iter()
+        .max()
+        .expect("Don't call this on an empty circle!");
+    (lowest, highest)
+}
+
+fn cups_from<T: Copy + DenseKey>(cups: &CircularList<T>, start: T) -> Vec<T> {
+    let start = cups
+        .find(&start)
+        .expect("The starting cup must be in the circle");
+    cups.iter_from(start).cloned().collect()
 }
 
 fn make_answer(ordered_cups: &Vec<u8>) -> String {
@@ -132,15 +141,16 @@ fn run_part1(input: &str, visualise: bool) -> Result<String, DayError> {
         .map(|c| u8::from_str(&c.to_string()))
         .collect::<Result<Vec<u8>, _>>()?;
     let current_cup = cups[0];
-    let mut cups = Ring::new(cups);
+    let mut cups = CircularList::indexed_from(cups);
     make_moves(&mut cups, current_cup, 100, visualise);
-    Ok(make_answer(&gather_order(&cups)))
+    Ok(make_answer(&cups_from(&cups, 1)))
 }
 
-fn two_cups_after_one(cups: &Ring<u32>) -> (u32, u32) {
-    let first = cups.value_after(&1).unwrap();
-    let second = cups.value_after(first).unwrap();
-    (*first, *second)
+fn two_cups_after_one(cups: &CircularList<u32>) -> (u32, u32) {
+    let one = cups.find(&1).unwrap();
+    let first = cups.next(one).unwrap();
+    let second = cups.next(first).unwrap();
+    (*cups.get(first).unwrap(), *cups.get(second).unwrap())
 }
 
 fn run_part2(input: &str, visualise: bool) -> Result<u64, DayError> {
@@ -158,7 +168,7 @@ fn run_part2(input: &str, visualise: bool) -> Result<u64, DayError> {
     }
     assert_eq!(cups.len(), 1_000_000);
     let current_cup = cups[0];
-    let mut cups = Ring::new(cups);
+    let mut cups = CircularList::indexed_from(cups);
     make_moves(&mut cups, current_cup, 10_000_000, visualise);
     let (first, second) = two_cups_after_one(&cups);
     Ok(first as u64 * second as u64)
@@ -166,12 +176,12 @@ fn run_part2(input: &str, visualise: bool) -> Result<u64, DayError> {
 
 #[test]
 fn test_move() {
-    let mut cups = Ring::new(vec![3, 8, 9, 1, 2, 5, 4, 6, 7]);
-    let current_cup = make_move(&mut cups, 3u8, true);
-    assert_eq!(format!("{:?}", cups), "[3, 2, 8, 9, 1, 5, 4, 6, 7, ]");
+    let mut cups = CircularList::indexed_from(vec![3u8, 8, 9, 1, 2, 5, 4, 6, 7]);
+    let current_cup = make_move(&mut cups, 3, (1, 9), true);
+    assert_eq!(cups_from(&cups, 3), vec![3, 2, 8, 9, 1, 5, 4, 6, 7]);
     assert_eq!(current_cup, 2);
-    let current_cup = make_move(&mut cups, current_cup, true);
-    assert_eq!(format!("{:?}", cups), "[3, 2, 5, 4, 6, 7, 8, 9, 1, ]");
+    let current_cup = make_move(&mut cups, current_cup, (1, 9), true);
+    assert_eq!(cups_from(&cups, 3), vec![3, 2, 5, 4, 6, 7, 8, 9, 1]);
     assert_eq!(current_cup, 5);
 }
 
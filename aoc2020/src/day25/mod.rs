@@ -1,4 +1,5 @@
 use crate::dayerror::DayError;
+use aoc_common::numtheory::{discrete_log, mod_pow};
 
 const KEY_1: u64 = 14205034;
 const KEY_2: u64 = 18047856;
@@ -23,26 +24,16 @@ fn run_part1(key1: u64, key2: u64) -> (u64, u64, u64, u64) {
     (loop_size_1, loop_size_2, encryption_key, encryption_key_2)
 }
 
-fn crypto_step(value: u64, subject_number: u64) -> u64 {
-    (value * subject_number) % 20201227
-}
+const MODULUS: u64 = 20201227;
 
+/// The loop size is the discrete logarithm of the public key.
 fn determine_loop_size(subject_number: u64, final_value: u64) -> u64 {
-    let mut loop_size = 0;
-    let mut value = 1;
-    while value != final_value {
-        value = crypto_step(value, subject_number);
-        loop_size += 1;
-    }
-    loop_size
+    discrete_log(subject_number, final_value, MODULUS)
+        .expect("Every public key should have a loop size")
 }
 
 fn transform(subject_number: u64, loop_size: u64) -> u64 {
-    let mut value = 1;
-    for _ in 0..loop_size {
-        value = crypto_step(value, subject_number);
-    }
-    value
+    mod_pow(subject_number, loop_size, MODULUS)
 }
 
 #[test]
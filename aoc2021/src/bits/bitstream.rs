@@ -0,0 +1,104 @@
+use super::packet::DecodeError;
+
+/// Reads big-endian fields of any width up to 64 bits from a sequence of bytes.
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// A reader which stops after `len` bits, for when the last byte isn't all used.
+    pub fn with_len(bytes: &'a [u8], len: usize) -> BitReader<'a> {
+        BitReader {
+            bytes,
+            len: len.min(bytes.len() * 8),
+            position: 0,
+        }
+    }
+
+    /// How many bits have been read so far, which is also the offset of the next one.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// Reads the next `bits` bits as an unsigned number, most significant bit first.
+    pub fn read(&mut self, bits: usize) -> Result<u64, DecodeError> {
+        assert!(bits <= 64, "Can't read {} bits into a u64", bits);
+        if bits > self.remaining() {
+            return Err(DecodeError::UnexpectedEnd {
+                offset: self.position,
+                wanted: bits,
+            });
+        }
+
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.bytes[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// Turns a hex transmission into bytes, along with how many bits it holds. An odd number of
+/// digits leaves the last byte half empty.
+pub fn hex_to_bytes(hex: &str) -> Result<(Vec<u8>, usize), DecodeError> {
+    let hex = hex.trim();
+    let mut bytes = Vec::with_capacity(hex.len().div_ceil(2));
+    for (position, character) in hex.chars().enumerate() {
+        let nibble = character.to_digit(16).ok_or(DecodeError::InvalidHex {
+            position,
+            character,
+        })? as u8;
+        if position % 2 == 0 {
+            bytes.push(nibble << 4);
+        } else {
+            *bytes.last_mut().unwrap() |= nibble;
+        }
+    }
+    let len = hex.chars().count() * 4;
+    Ok((bytes, len))
+}
+
+#[test]
+fn test_read_fields() {
+    let (bytes, len) = hex_to_bytes("D2FE28").unwrap();
+    assert_eq!(len, 24);
+    let mut reader = BitReader::with_len(&bytes, len);
+    assert_eq!(reader.read(3), Ok(6));
+    assert_eq!(reader.read(3), Ok(4));
+    assert_eq!(reader.read_bit(), Ok(true));
+    assert_eq!(reader.read(4), Ok(0b0111));
+    assert_eq!(reader.position(), 11);
+    assert_eq!(reader.read(13), Ok(0b1111000101000));
+    assert_eq!(
+        reader.read(1),
+        Err(DecodeError::UnexpectedEnd {
+            offset: 24,
+            wanted: 1
+        })
+    );
+}
+
+#[test]
+fn test_hex_errors() {
+    assert_eq!(
+        hex_to_bytes("8A0G"),
+        Err(DecodeError::InvalidHex {
+            position: 3,
+            character: 'G'
+        })
+    );
+    assert_eq!(hex_to_bytes("abc"), Ok((vec![0xAB, 0xC0], 12)));
+}
@@ -0,0 +1,218 @@
+//! Building transmissions from packets, the reverse of decoding them.
+
+use super::bitstream::BitReader;
+use super::packet::{LengthType, Packet, PacketBody, LITERAL_TYPE};
+use std::error::Error;
+use std::fmt;
+
+const MAX_SUBPACKET_BITS: usize = (1 << 15) - 1;
+const MAX_SUBPACKET_COUNT: usize = (1 << 11) - 1;
+
+/// Collects bits into bytes, filling each byte from its most significant bit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= 1 << (7 - self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// Writes the lowest `bits` bits of `value`, most significant bit first.
+    pub fn write(&mut self, value: u64, bits: usize) {
+        assert!(bits <= 64, "Can't write {} bits from a u64", bits);
+        for i in (0..bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    /// Writes everything in `other` after what's already here.
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader::with_len(&other.bytes, other.len);
+        while reader.remaining() > 0 {
+            self.write_bit(reader.read_bit().expect("Bits should be there to copy"));
+        }
+    }
+
+    /// The bits as upper case hex digits, with zeroes filling out the last digit.
+    pub fn to_hex(&self) -> String {
+        let digits = self.len.div_ceil(4);
+        (0..digits)
+            .map(|d| {
+                let nibble = (self.bytes[d / 2] >> if d % 2 == 0 { 4 } else { 0 }) & 0xF;
+                std::char::from_digit(nibble as u32, 16)
+                    .unwrap()
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// A version or type ID which won't fit in its three bits.
+    FieldTooLarge { field: &'static str, value: u8 },
+    /// A literal body with a type ID other than 4, or an operator body with type ID 4.
+    WrongBody { type_id: u8 },
+    /// Sub-packets too long for the 15 bit length field.
+    SubpacketsTooLong { bits: usize },
+    /// More sub-packets than the 11 bit count field can hold.
+    TooManySubpackets { count: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::FieldTooLarge { field, value } => {
+                write!(f, "The {} {} doesn't fit in three bits", field, value)
+            }
+            EncodeError::WrongBody { type_id } => {
+                write!(f, "Type ID {} doesn't go with that kind of body", type_id)
+            }
+            EncodeError::SubpacketsTooLong { bits } => {
+                write!(
+                    f,
+                    "{} bits of sub-packets won't fit in the length field",
+                    bits
+                )
+            }
+            EncodeError::TooManySubpackets { count } => {
+                write!(f, "{} sub-packets won't fit in the count field", count)
+            }
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+impl Packet {
+    /// The transmission for this packet, padded with zeroes to a whole number of hex digits.
+    pub fn to_hex(&self) -> Result<String, EncodeError> {
+        let mut writer = BitWriter::new();
+        self.encode(&mut writer)?;
+        Ok(writer.to_hex())
+    }
+
+    pub fn encode(&self, writer: &mut BitWriter) -> Result<(), EncodeError> {
+        if self.version > 7 {
+            return Err(EncodeError::FieldTooLarge {
+                field: "version",
+                value: self.version,
+            });
+        }
+        if self.type_id > 7 {
+            return Err(EncodeError::FieldTooLarge {
+                field: "type ID",
+                value: self.type_id,
+            });
+        }
+
+        writer.write(self.version as u64, 3);
+        writer.write(self.type_id as u64, 3);
+        match &self.body {
+            PacketBody::Literal(value) if self.type_id == LITERAL_TYPE => {
+                encode_literal(*value, writer);
+                Ok(())
+            }
+            PacketBody::Operator {
+                length_type,
+                subpackets,
+            } if self.type_id != LITERAL_TYPE => encode_operator(*length_type, subpackets, writer),
+            _ => Err(EncodeError::WrongBody {
+                type_id: self.type_id,
+            }),
+        }
+    }
+}
+
+fn encode_literal(value: u64, writer: &mut BitWriter) {
+    let significant_bits = 64 - value.leading_zeros() as usize;
+    let groups = significant_bits.div_ceil(4).max(1);
+    for g in (0..groups).rev() {
+        writer.write_bit(g > 0);
+        writer.write(value >> (4 * g), 4);
+    }
+}
+
+fn encode_operator(
+    length_type: LengthType,
+    subpackets: &[Packet],
+    writer: &mut BitWriter,
+) -> Result<(), EncodeError> {
+    let mut body = BitWriter::new();
+    for subpacket in subpackets {
+        subpacket.encode(&mut body)?;
+    }
+
+    match length_type {
+        LengthType::Bits => {
+            if body.len() > MAX_SUBPACKET_BITS {
+                return Err(EncodeError::SubpacketsTooLong { bits: body.len() });
+            }
+            writer.write_bit(false);
+            writer.write(body.len() as u64, 15);
+        }
+        LengthType::Count => {
+            if subpackets.len() > MAX_SUBPACKET_COUNT {
+                return Err(EncodeError::TooManySubpackets {
+                    count: subpackets.len(),
+                });
+            }
+            writer.write_bit(true);
+            writer.write(subpackets.len() as u64, 11);
+        }
+    }
+    writer.append(&body);
+    Ok(())
+}
+
+#[test]
+fn test_write_round_trip() {
+    let mut writer = BitWriter::new();
+    writer.write(6, 3);
+    writer.write(4, 3);
+    writer.write(0b10111, 5);
+    let mut other = BitWriter::new();
+    other.write(0b1111000101, 10);
+    writer.append(&other);
+    assert_eq!(writer.len(), 21);
+    assert_eq!(writer.to_hex(), "D2FE28");
+}
+
+#[test]
+fn test_encode_round_trip() {
+    for hex in &[
+        "D2FE28",
+        "8A004A801A8002F478",
+        "620080001611562C8802118E34",
+        "C0015000016115A2E0802F182340",
+        "A0016C880162017C3686B18A3D4780",
+        "9C0141080250320F1802104A08",
+    ] {
+        let packet = Packet::from_hex(hex).unwrap();
+        let encoded = packet.to_hex().unwrap();
+        assert!(hex.starts_with(&encoded), "{} encoded as {}", hex, encoded);
+        assert_eq!(Packet::from_hex(&encoded), Ok(packet));
+    }
+
+    let mut bad = Packet::literal(6, 1);
+    bad.type_id = 0;
+    assert_eq!(bad.to_hex(), Err(EncodeError::WrongBody { type_id: 0 }));
+}
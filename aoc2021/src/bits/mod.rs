@@ -0,0 +1,8 @@
+//! Reading, writing and evaluating the Buoyancy Interchange Transmission System's packets.
+
+mod bitstream;
+#[cfg(test)]
+mod encode;
+mod packet;
+
+pub use packet::Packet;
@@ -0,0 +1,394 @@
+use super::bitstream::{hex_to_bytes, BitReader};
+use std::error::Error;
+use std::fmt;
+
+pub(super) const LITERAL_TYPE: u8 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The character at `position` in the transmission isn't a hex digit.
+    InvalidHex { position: usize, character: char },
+    /// `wanted` bits were needed at bit `offset`, but the transmission ran out first.
+    UnexpectedEnd { offset: usize, wanted: usize },
+    /// The literal whose value starts at bit `offset` doesn't fit in 64 bits.
+    LiteralTooLarge { offset: usize },
+    /// The operator at bit `offset` said its sub-packets took `declared` bits but they took
+    /// `actual`.
+    LengthMismatch {
+        offset: usize,
+        declared: usize,
+        actual: usize,
+    },
+    /// Something other than zero padding follows the outermost packet, starting at bit `offset`.
+    TrailingData { offset: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHex {
+                position,
+                character,
+            } => write!(
+                f,
+                "Character {} ('{}') isn't a hex digit",
+                position, character
+            ),
+            DecodeError::UnexpectedEnd { offset, wanted } => write!(
+                f,
+                "Needed {} more bits at bit {} but the transmission ended",
+                wanted, offset
+            ),
+            DecodeError::LiteralTooLarge { offset } => {
+                write!(f, "Literal at bit {} is too large for 64 bits", offset)
+            }
+            DecodeError::LengthMismatch {
+                offset,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "Operator at bit {} declared {} bits of sub-packets but they took {}",
+                offset, declared, actual
+            ),
+            DecodeError::TrailingData { offset } => {
+                write!(f, "Unexpected data after the packet at bit {}", offset)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluateError {
+    /// A sum, product, minimum or maximum with nothing to work on.
+    NoOperands { type_id: u8 },
+    /// A comparison with other than two sub-packets.
+    WrongOperandCount { type_id: u8, count: usize },
+}
+
+impl fmt::Display for EvaluateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvaluateError::NoOperands { type_id } => {
+                write!(f, "Operator type {} has no sub-packets", type_id)
+            }
+            EvaluateError::WrongOperandCount { type_id, count } => write!(
+                f,
+                "Comparison type {} needs two sub-packets but has {}",
+                type_id, count
+            ),
+        }
+    }
+}
+
+impl Error for EvaluateError {}
+
+/// How an operator says where its sub-packets end.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum LengthType {
+    /// The total number of bits in the sub-packets, in 15 bits.
+    Bits,
+    /// The number of sub-packets, in 11 bits.
+    Count,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PacketBody {
+    Literal(u64),
+    Operator {
+        length_type: LengthType,
+        subpackets: Vec<Packet>,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Packet {
+    pub version: u8,
+    pub type_id: u8,
+    pub body: PacketBody,
+}
+
+impl Packet {
+    /// Decodes a whole transmission, which must hold one packet and then only zero padding.
+    pub fn from_hex(hex: &str) -> Result<Packet, DecodeError> {
+        let (bytes, len) = hex_to_bytes(hex)?;
+        let mut reader = BitReader::with_len(&bytes, len);
+        let packet = Packet::decode(&mut reader)?;
+
+        let padding_start = reader.position();
+        while reader.remaining() > 0 {
+            if reader.read_bit()? {
+                return Err(DecodeError::TrailingData {
+                    offset: padding_start,
+                });
+            }
+        }
+        Ok(packet)
+    }
+
+    /// Decodes the packet starting at the reader's position, leaving the reader just after it.
+    pub fn decode(reader: &mut BitReader) -> Result<Packet, DecodeError> {
+        let version = reader.read(3)? as u8;
+        let type_id = reader.read(3)? as u8;
+        let body = if type_id == LITERAL_TYPE {
+            PacketBody::Literal(decode_literal(reader)?)
+        } else {
+            decode_operator(reader)?
+        };
+
+        Ok(Packet {
+            version,
+            type_id,
+            body,
+        })
+    }
+
+    pub fn subpackets(&self) -> &[Packet] {
+        match &self.body {
+            PacketBody::Literal(_) => &[],
+            PacketBody::Operator { subpackets, .. } => subpackets,
+        }
+    }
+
+    /// The sum of the versions of this packet and all the packets inside it.
+    pub fn version_sum(&self) -> u64 {
+        self.version as u64
+            + self
+                .subpackets()
+                .iter()
+                .map(|s| s.version_sum())
+                .sum::<u64>()
+    }
+
+    pub fn evaluate(&self) -> Result<u64, EvaluateError> {
+        let subpackets = match &self.body {
+            PacketBody::Literal(n) => return Ok(*n),
+            PacketBody::Operator { subpackets, .. } => subpackets,
+        };
+        let values = subpackets
+            .iter()
+            .map(|sp| sp.evaluate())
+            .collect::<Result<Vec<_>, _>>()?;
+        let no_operands = EvaluateError::NoOperands {
+            type_id: self.type_id,
+        };
+
+        match self.type_id {
+            0 if !values.is_empty() => Ok(values.into_iter().sum()),
+            1 if !values.is_empty() => Ok(values.into_iter().product()),
+            2 => values.into_iter().min().ok_or(no_operands),
+            3 => values.into_iter().max().ok_or(no_operands),
+            0 | 1 => Err(no_operands),
+            _ if values.len() != 2 => Err(EvaluateError::WrongOperandCount {
+                type_id: self.type_id,
+                count: values.len(),
+            }),
+            5 => Ok((values[0] > values[1]) as u64),
+            6 => Ok((values[0] < values[1]) as u64),
+            _ => Ok((values[0] == values[1]) as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Packet {
+        Packet {
+            version,
+            type_id: LITERAL_TYPE,
+            body: PacketBody::Literal(value),
+        }
+    }
+
+    /// An operator which gives the length of its sub-packets in bits.
+    pub fn operator(version: u8, type_id: u8, subpackets: Vec<Packet>) -> Packet {
+        Packet {
+            version,
+            type_id,
+            body: PacketBody::Operator {
+                length_type: LengthType::Bits,
+                subpackets,
+            },
+        }
+    }
+
+    fn operation_name(&self) -> &'static str {
+        match self.type_id {
+            0 => "sum",
+            1 => "product",
+            2 => "minimum",
+            3 => "maximum",
+            5 => "greater than",
+            6 => "less than",
+            7 => "equal to",
+            _ => "unknown",
+        }
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        match &self.body {
+            PacketBody::Literal(value) => writeln!(f, "{} (v{})", value, self.version)?,
+            PacketBody::Operator { .. } => {
+                writeln!(f, "{} (v{})", self.operation_name(), self.version)?
+            }
+        }
+
+        let subpackets = self.subpackets();
+        for (i, subpacket) in subpackets.iter().enumerate() {
+            let last = i + 1 == subpackets.len();
+            write!(f, "{}{}", indent, if last { "└─ " } else { "├─ " })?;
+            subpacket.fmt_tree(
+                f,
+                &format!("{}{}", indent, if last { "   " } else { "│  " }),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws the packet as the tree of the expression it stands for, one packet per line with its
+/// version in brackets.
+#[cfg(test)]
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, "")
+    }
+}
+
+fn decode_literal(reader: &mut BitReader) -> Result<u64, DecodeError> {
+    let offset = reader.position();
+    let mut value: u64 = 0;
+    loop {
+        let more = reader.read_bit()?;
+        let group = reader.read(4)?;
+        if value >> 60 != 0 {
+            return Err(DecodeError::LiteralTooLarge { offset });
+        }
+        value = (value << 4) | group;
+        if !more {
+            return Ok(value);
+        }
+    }
+}
+
+fn decode_operator(reader: &mut BitReader) -> Result<PacketBody, DecodeError> {
+    let offset = reader.position();
+    let mut subpackets = Vec::new();
+    let length_type = if reader.read_bit()? {
+        let count = reader.read(11)?;
+        for _ in 0..count {
+            subpackets.push(Packet::decode(reader)?);
+        }
+        LengthType::Count
+    } else {
+        let declared = reader.read(15)? as usize;
+        let start = reader.position();
+        while reader.position() - start < declared {
+            subpackets.push(Packet::decode(reader)?);
+        }
+        let actual = reader.position() - start;
+        if actual != declared {
+            return Err(DecodeError::LengthMismatch {
+                offset,
+                declared,
+                actual,
+            });
+        }
+        LengthType::Bits
+    };
+
+    Ok(PacketBody::Operator {
+        length_type,
+        subpackets,
+    })
+}
+
+#[test]
+fn test_decode_literal() {
+    assert_eq!(Packet::from_hex("D2FE28"), Ok(Packet::literal(6, 2021)));
+}
+
+#[test]
+fn test_decode_operators() {
+    let by_bits = Packet::from_hex("38006F45291200").unwrap();
+    assert_eq!(
+        by_bits,
+        Packet::operator(1, 6, vec![Packet::literal(6, 10), Packet::literal(2, 20)])
+    );
+
+    let by_count = Packet::from_hex("EE00D40C823060").unwrap();
+    assert_eq!(by_count.version, 7);
+    assert_eq!(
+        by_count.body,
+        PacketBody::Operator {
+            length_type: LengthType::Count,
+            subpackets: vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3)
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_decode_errors() {
+    assert_eq!(
+        Packet::from_hex("D2FE"),
+        Err(DecodeError::UnexpectedEnd {
+            offset: 16,
+            wanted: 1
+        })
+    );
+    assert_eq!(
+        Packet::from_hex("D2FE2F"),
+        Err(DecodeError::TrailingData { offset: 21 })
+    );
+    // a literal of seventeen groups
+    assert_eq!(
+        Packet::from_hex("D3FFFFFFFFFFFFFFFFFFFC2"),
+        Err(DecodeError::LiteralTooLarge { offset: 6 })
+    );
+    // the example operator, claiming 26 bits of sub-packets rather than 27
+    assert_eq!(
+        Packet::from_hex("38006B45291200"),
+        Err(DecodeError::LengthMismatch {
+            offset: 6,
+            declared: 26,
+            actual: 27
+        })
+    );
+}
+
+#[test]
+fn test_evaluate_errors() {
+    assert_eq!(
+        Packet::operator(0, 2, vec![]).evaluate(),
+        Err(EvaluateError::NoOperands { type_id: 2 })
+    );
+    assert_eq!(
+        Packet::operator(0, 7, vec![Packet::literal(0, 1)]).evaluate(),
+        Err(EvaluateError::WrongOperandCount {
+            type_id: 7,
+            count: 1
+        })
+    );
+}
+
+#[test]
+fn test_display_tree() {
+    let packet = Packet::from_hex("9C0141080250320F1802104A08").unwrap();
+    assert_eq!(
+        packet.to_string(),
+        "equal to (v4)
+├─ sum (v2)
+│  ├─ 1 (v2)
+│  └─ 3 (v4)
+└─ product (v6)
+   ├─ 2 (v0)
+   └─ 2 (v2)
+"
+    );
+}
@@ -20,6 +20,18 @@ impl<T> Grid<T> {
         }
     }
 
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> T) -> Grid<T> {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Grid {
+            width,
+            height,
+            data,
+        }
+    }
+
     pub fn index_of(&self, x: usize, y: usize) -> Option<usize> {
         if x < self.width && y < self.height {
             Some(y * self.width + x)
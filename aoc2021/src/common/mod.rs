@@ -1,6 +1,9 @@
 pub mod command;
 pub mod grid;
 pub mod position;
+pub mod render;
+pub mod search;
+pub mod transform;
 
 pub use command::Command;
 pub use position::Position;
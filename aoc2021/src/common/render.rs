@@ -0,0 +1,111 @@
+use crate::common::grid::Grid;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, ImageResult, Rgb, RgbImage};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+impl<T> Grid<T> {
+    /// Draws the grid as an image, with each cell becoming a `scale` by `scale` block
+    /// of the colour chosen for it by `colour`.
+    pub fn to_image(&self, scale: u32, colour: impl Fn(&T) -> Rgb<u8>) -> RgbImage {
+        let scale = scale.max(1);
+        let mut image = RgbImage::new(self.width() as u32 * scale, self.height() as u32 * scale);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let pixel = colour(self.get(x, y).unwrap());
+                for py in 0..scale {
+                    for px in 0..scale {
+                        image.put_pixel(x as u32 * scale + px, y as u32 * scale + py, pixel);
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        scale: u32,
+        colour: impl Fn(&T) -> Rgb<u8>,
+    ) -> ImageResult<()> {
+        self.to_image(scale, colour)
+            .save_with_format(path, image::ImageFormat::Png)
+    }
+}
+
+/// Collects grid states from a running simulation so they can be written out as an animated GIF.
+pub struct GifRecorder {
+    frames: Vec<RgbImage>,
+    scale: u32,
+    frame_delay_ms: u32,
+}
+
+impl GifRecorder {
+    pub fn new(scale: u32, frame_delay_ms: u32) -> GifRecorder {
+        GifRecorder {
+            frames: Vec::new(),
+            scale,
+            frame_delay_ms,
+        }
+    }
+
+    pub fn record<T>(&mut self, grid: &Grid<T>, colour: impl Fn(&T) -> Rgb<u8>) {
+        self.frames.push(grid.to_image(self.scale, colour));
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn write_gif(&self, writer: impl Write) -> ImageResult<()> {
+        let mut encoder = GifEncoder::new(writer);
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay = Delay::from_numer_denom_ms(self.frame_delay_ms, 1);
+        encoder.encode_frames(self.frames.iter().map(|f| {
+            Frame::from_parts(DynamicImage::ImageRgb8(f.clone()).into_rgba8(), 0, 0, delay)
+        }))
+    }
+
+    pub fn save_gif(&self, path: impl AsRef<Path>) -> ImageResult<()> {
+        let file = File::create(path)?;
+        self.write_gif(BufWriter::new(file))
+    }
+}
+
+#[cfg(test)]
+fn on_off(value: &bool) -> Rgb<u8> {
+    if *value {
+        Rgb([255, 255, 255])
+    } else {
+        Rgb([0, 0, 0])
+    }
+}
+
+#[test]
+fn test_to_image_scales_cells() {
+    let mut grid = Grid::new(3, 2);
+    grid.set(1, 0, true);
+    let image = grid.to_image(2, on_off);
+    assert_eq!(image.dimensions(), (6, 4));
+    assert_eq!(image.get_pixel(0, 0), &Rgb([0, 0, 0]));
+    assert_eq!(image.get_pixel(2, 0), &Rgb([255, 255, 255]));
+    assert_eq!(image.get_pixel(3, 1), &Rgb([255, 255, 255]));
+    assert_eq!(image.get_pixel(3, 2), &Rgb([0, 0, 0]));
+}
+
+#[test]
+fn test_recorder_writes_gif() {
+    let mut grid = Grid::new(4, 4);
+    let mut recorder = GifRecorder::new(1, 100);
+    for i in 0..4 {
+        grid.set(i, i, true);
+        recorder.record(&grid, on_off);
+    }
+    assert_eq!(recorder.frame_count(), 4);
+
+    let mut output = Vec::new();
+    recorder.write_gif(&mut output).unwrap();
+    assert_eq!(&output[0..6], b"GIF89a");
+}
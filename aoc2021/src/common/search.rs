@@ -0,0 +1,97 @@
+use crate::common::grid::Grid;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub type Coord = (usize, usize);
+
+impl<T> Grid<T> {
+    /// Finds the cheapest orthogonal path from `start` to the first cell satisfying `is_goal`.
+    /// `cost` gives the cost of stepping into a cell, or `None` if it can't be entered.
+    pub fn dijkstra(
+        &self,
+        start: Coord,
+        is_goal: impl Fn(Coord) -> bool,
+        cost: impl Fn(&T) -> Option<usize>,
+    ) -> Option<(Vec<Coord>, usize)> {
+        self.get(start.0, start.1)?;
+        let mut best_costs: Grid<Option<usize>> = Grid::new(self.width(), self.height());
+        let mut came_from: Grid<Option<Coord>> = Grid::new(self.width(), self.height());
+        let mut queue = BinaryHeap::new();
+        best_costs.set(start.0, start.1, Some(0));
+        came_from.set(start.0, start.1, Some(start));
+        queue.push(Reverse((0, start)));
+
+        while let Some(Reverse((cost_so_far, current))) = queue.pop() {
+            if is_goal(current) {
+                return Some((reconstruct_path(&came_from, start, current), cost_so_far));
+            }
+            if let Some(best) = best_costs.get(current.0, current.1)? {
+                if cost_so_far > *best {
+                    continue;
+                }
+            }
+            for (nx, ny) in self.surrounding_coords_no_diagonals(current.0, current.1) {
+                let step_cost = match cost(self.get(nx, ny)?) {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let new_cost = cost_so_far + step_cost;
+                let improved = match best_costs.get(nx, ny)? {
+                    Some(existing) => new_cost < *existing,
+                    None => true,
+                };
+                if improved {
+                    best_costs.set(nx, ny, Some(new_cost));
+                    came_from.set(nx, ny, Some(current));
+                    queue.push(Reverse((new_cost, (nx, ny))));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn reconstruct_path(came_from: &Grid<Option<Coord>>, start: Coord, end: Coord) -> Vec<Coord> {
+    let mut path = vec![end];
+    let mut current = end;
+    while current != start {
+        current = came_from
+            .get(current.0, current.1)
+            .and_then(|c| *c)
+            .expect("Every visited cell should know where it came from");
+        path.push(current);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+fn parse_digits(input: &str) -> Grid<u8> {
+    let lines = input.lines().map(|l| l.trim()).collect::<Vec<_>>();
+    let mut grid = Grid::new(lines[0].len(), lines.len());
+    for (y, line) in lines.into_iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            grid.set(x, y, c.to_digit(10).unwrap() as u8);
+        }
+    }
+    grid
+}
+
+#[test]
+fn test_dijkstra_day15_sample() {
+    let caves = parse_digits(include_str!("../inputs/samples/day15.txt"));
+    let goal = (caves.width() - 1, caves.height() - 1);
+    let (path, cost) = caves
+        .dijkstra((0, 0), |c| c == goal, |risk| Some(*risk as usize))
+        .unwrap();
+    assert_eq!(cost, 40);
+    assert_eq!(path.first(), Some(&(0, 0)));
+    assert_eq!(path.last(), Some(&goal));
+    let path_risk: usize = path
+        .iter()
+        .skip(1)
+        .map(|(x, y)| *caves.get(*x, *y).unwrap() as usize)
+        .sum();
+    assert_eq!(path_risk, cost);
+}
@@ -0,0 +1,53 @@
+use crate::common::grid::Grid;
+use aoc_common::transform::Transform;
+
+impl<T: Clone> Transform for Grid<T> {
+    type Cell = T;
+
+    fn width(&self) -> usize {
+        self.width()
+    }
+
+    fn height(&self) -> usize {
+        self.height()
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &T {
+        self.get(x, y).unwrap()
+    }
+
+    fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> T) -> Grid<T> {
+        Grid::from_fn(width, height, f)
+    }
+}
+
+#[cfg(test)]
+fn parse_pattern(input: &str) -> Grid<char> {
+    let lines = input.split('/').collect::<Vec<_>>();
+    Grid::from_fn(lines[0].len(), lines.len(), |x, y| {
+        lines[y].chars().nth(x).unwrap()
+    })
+}
+
+#[cfg(test)]
+fn render_pattern(grid: &Grid<char>) -> String {
+    (0..grid.height())
+        .map(|y| {
+            (0..grid.width())
+                .map(|x| *grid.get(x, y).unwrap())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[test]
+fn test_grid_transforms() {
+    let grid = parse_pattern("abc/def");
+    assert_eq!(render_pattern(&grid.rotate_90()), "da/eb/fc");
+    assert_eq!(render_pattern(&grid.flip_vertical()), "def/abc");
+    assert_eq!(
+        Grid::join_blocks(&grid.split_blocks(1).unwrap()),
+        Some(grid.clone())
+    );
+}
@@ -1,11 +1,18 @@
 use crate::common::grid::Grid;
+use crate::common::render::GifRecorder;
 use crate::day::{DayResult, PartResult};
+use image::Rgb;
 use std::collections::VecDeque;
 use std::error::Error;
 
-pub fn run() -> Result<DayResult, Box<dyn Error>> {
+pub fn run(gif: Option<&str>) -> Result<DayResult, Box<dyn Error>> {
     let part1_flashes = part1(include_str!("inputs/day11.txt"))?;
-    let part2_step = part2(include_str!("inputs/day11.txt"))?;
+    let mut recorder = gif.map(|_| GifRecorder::new(8, 100));
+    let part2_step = part2(include_str!("inputs/day11.txt"), recorder.as_mut())?;
+    if let (Some(path), Some(recorder)) = (gif, recorder) {
+        recorder.save_gif(path)?;
+        log::info!("Recorded {} frames to {}", recorder.frame_count(), path);
+    }
     Ok(DayResult::new(
         PartResult::Success(format!("There were {} flashes", part1_flashes)),
         PartResult::Success(format!(
@@ -24,11 +31,17 @@ fn part1(input: &str) -> Result<u64, Box<dyn Error>> {
     Ok(flashes)
 }
 
-fn part2(input: &str) -> Result<u64, Box<dyn Error>> {
+fn part2(input: &str, mut recorder: Option<&mut GifRecorder>) -> Result<u64, Box<dyn Error>> {
     let mut input = parse_input(input)?;
     let num_octopodes = (input.width() * input.height()) as u64;
+    if let Some(recorder) = &mut recorder {
+        recorder.record(&input, octopus_colour);
+    }
     for step_num in 1.. {
         let flashes = step(&mut input);
+        if let Some(recorder) = &mut recorder {
+            recorder.record(&input, octopus_colour);
+        }
         if flashes == num_octopodes {
             return Ok(step_num);
         }
@@ -57,6 +70,15 @@ fn step(octopodes: &mut Grid<u8>) -> u64 {
     flashes
 }
 
+/// Octopodes which have just flashed are white, and the rest glow brighter as they charge up.
+fn octopus_colour(energy: &u8) -> Rgb<u8> {
+    if *energy == 0 {
+        Rgb([255, 255, 255])
+    } else {
+        Rgb([0, 10 * energy, 20 * energy])
+    }
+}
+
 fn should_flash(energy: u8) -> bool {
     energy > 9
 }
@@ -209,6 +231,18 @@ fn test_part1_small_sample_step1() {
 
 #[test]
 fn test_part2_sample() {
-    let step = part2(include_str!("inputs/samples/day11.txt")).unwrap();
+    let step = part2(include_str!("inputs/samples/day11.txt"), None).unwrap();
     assert_eq!(step, 195);
 }
+
+#[test]
+fn test_part2_recording() {
+    let mut recorder = GifRecorder::new(1, 100);
+    let step = part2(
+        include_str!("inputs/samples/day11.txt"),
+        Some(&mut recorder),
+    )
+    .unwrap();
+    // the starting grid, then one frame for each step up to and including the big flash
+    assert_eq!(recorder.frame_count(), step as usize + 1);
+}
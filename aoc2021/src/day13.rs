@@ -2,21 +2,36 @@ use crate::{
     common::grid::Grid,
     day::{DayResult, PartResult},
 };
+use aoc_common::ocr::recognise;
+#[cfg(test)]
+use aoc_common::ocr::OcrError;
+use image::Rgb;
 use regex::Regex;
 use std::{error::Error, fmt::Display};
 
-pub fn run() -> Result<DayResult, Box<dyn Error>> {
+pub fn run(png: Option<&str>) -> Result<DayResult, Box<dyn Error>> {
     let input_dots = include_str!("inputs/day13/dots.txt");
     let input_instructions = include_str!("inputs/day13/instructions.txt");
     let grid = parse_dots(input_dots)?;
     let instructions = parse_instructions(input_instructions)?;
 
     let part1 = part1(&grid, &instructions).ok_or(format!("Failed part 1"))?;
-    let part2 = part2(&grid, &instructions).ok_or(format!("Failed part 2"))?;
+    let folded = execute_instructions(instructions.iter().cloned(), &grid)
+        .ok_or("Failed to fold the paper")?;
+    if let Some(path) = png {
+        folded.save_png(path, 8, |dot| {
+            if *dot {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        })?;
+    }
+    let part2 = part2(&folded)?;
 
     Ok(DayResult::new(
         PartResult::Success(format!("{} dots", part1)),
-        PartResult::Success(format!("\n{}", part2)),
+        PartResult::Success(part2),
     ))
 }
 
@@ -25,9 +40,10 @@ fn part1(grid: &Grid<bool>, instructions: &Vec<Instruction>) -> Option<usize> {
     Some(count_grid_dots(&new_grid))
 }
 
-fn part2(grid: &Grid<bool>, instructions: &Vec<Instruction>) -> Option<String> {
-    let new_grid = execute_instructions(instructions.iter().cloned(), grid)?;
-    Some(new_grid.to_string())
+fn part2(folded: &Grid<bool>) -> Result<String, Box<dyn Error>> {
+    Ok(recognise(folded.width(), folded.height(), |x, y| {
+        *folded.get(x, y).unwrap()
+    })?)
 }
 
 fn parse_dots(input: &str) -> Result<Grid<bool>, Box<dyn Error>> {
@@ -183,16 +199,24 @@ fn test_part2_sample() {
         parse_dots(include_str!("inputs/samples/day13/dots.txt")).expect("this should parse");
     let instructions = parse_instructions(include_str!("inputs/samples/day13/instructions.txt"))
         .expect("this should also parse");
-    let result = part2(&dots, &instructions).unwrap();
+    let result = execute_instructions(instructions.iter().cloned(), &dots).unwrap();
 
     assert_eq!(
-        result,
+        result.to_string(),
         "#####
-#...#
-#...#
-#...#
+#   #
+#   #
+#   #
 #####
-.....
-....."
+     
+     
+"
+    );
+
+    // the sample folds up into a square, which isn't a letter
+    let error = part2(&result).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<OcrError>(),
+        Some(&OcrError::UnknownHeight(5))
     );
 }
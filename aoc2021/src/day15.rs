@@ -2,7 +2,7 @@ use crate::{
     common::grid::Grid,
     day::{DayResult, PartResult},
 };
-use pathfinding::directed::dijkstra::dijkstra;
+use aoc_common::transform::Transform;
 use std::error::Error;
 
 pub fn run() -> Result<DayResult, Box<dyn Error>> {
@@ -43,15 +43,11 @@ fn parse_input_grid(input: &str) -> Result<Grid<u8>, Box<dyn Error>> {
 }
 
 fn solve_part1(caves_grid: &Grid<u8>) -> Result<usize, String> {
-    let successors = |node: &(usize, usize)| {
-        caves_grid
-            .surrounding_coords_no_diagonals(node.0, node.1)
-            .map(|(nx, ny)| ((nx, ny), *caves_grid.get(nx, ny).unwrap() as usize))
-    };
-    let success =
-        |node: &(usize, usize)| *node == (caves_grid.width() - 1, caves_grid.height() - 1);
+    let goal = (caves_grid.width() - 1, caves_grid.height() - 1);
 
-    if let Some((_, cost)) = dijkstra(&(0, 0), successors, success) {
+    let path = caves_grid.dijkstra((0, 0), |c| c == goal, |risk| Some(*risk as usize));
+
+    if let Some((_, cost)) = path {
         Ok(cost)
     } else {
         Err(format!("Couldn't find a path :("))
@@ -64,21 +60,7 @@ fn solve_part2(caves_grid: &Grid<u8>) -> Result<usize, String> {
 }
 
 fn make_full_map(source_grid: &Grid<u8>) -> Grid<u8> {
-    let mut target_grid = Grid::new(source_grid.width() * 5, source_grid.height() * 5);
-    for gx in 0..5u8 {
-        for gy in 0..5u8 {
-            let add = gx + gy;
-            let x_offset = source_grid.width() * gx as usize;
-            let y_offset = source_grid.height() * gy as usize;
-            for (x, y) in source_grid.all_coords() {
-                let target_x = x + x_offset;
-                let target_y = y + y_offset;
-                let target_value = add_risk(*source_grid.get(x, y).unwrap(), add);
-                target_grid.set(target_x, target_y, target_value);
-            }
-        }
-    }
-    target_grid
+    source_grid.tile(5, 5, |risk, tx, ty| add_risk(*risk, (tx + ty) as u8))
 }
 
 fn add_risk(risk: u8, add: u8) -> u8 {
@@ -8,6 +8,7 @@ use std::time::Instant;
 extern crate lazy_static;
 
 mod bingo;
+mod bits;
 mod common;
 mod day;
 mod day1;
@@ -42,9 +43,25 @@ fn main() {
                 .required(true)
                 .index(1),
         )
+        .arg(
+            Arg::with_name("gif")
+                .long("gif")
+                .value_name("FILE")
+                .help("Records the day 11 octopodes flashing as an animated GIF")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("png")
+                .long("png")
+                .value_name("FILE")
+                .help("Saves the day 13 paper as a PNG once it's folded")
+                .takes_value(true),
+        )
         .get_matches();
 
     let day = matches.value_of("DAY").expect("Day must be provided");
+    let gif = matches.value_of("gif");
+    let png = matches.value_of("png");
 
     match day {
         "1" => run_day(1, || {
@@ -60,9 +77,9 @@ fn main() {
         "8" => run_day(8, || day8::run()),
         "9" => run_day(9, || day9::run()),
         "10" => run_day(10, || day10::run()),
-        "11" => run_day(11, || day11::run()),
+        "11" => run_day(11, || day11::run(gif)),
         "12" => run_day(12, || day12::run()),
-        "13" => run_day(13, || day13::run()),
+        "13" => run_day(13, || day13::run(png)),
         "14" => run_day(14, || day14::run()),
         "15" => run_day(15, || day15::run()),
         "16" => run_day(16, || day16::run()),
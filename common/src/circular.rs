@@ -0,0 +1,574 @@
+//! A circular doubly-linked list stored in a `Vec`, for the puzzles which insert and remove
+//! things around a circle millions of times (marbles, cups, spinlocks).
+//!
+//! Nodes are referred to by `NodeRef`s, which stay valid until the node is removed. While a
+//! node is detached it's treated as missing, so it can't be used to get back into the list.
+//! The list also has a cursor, which most of the convenience methods work relative to.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeRef(usize);
+
+/// A run of nodes which has been cut out of the list with `detach_after`, ready to be
+/// put back somewhere else with `splice_after`. Splicing it back in uses it up, so the same
+/// run can't go back in twice:
+///
+/// ```compile_fail
+/// # use aoc_common::circular::CircularList;
+/// let mut list = CircularList::indexed_from(vec![1u32, 2, 3, 4]);
+/// let one = list.find(&1).unwrap();
+/// let run = list.detach_after(one, 2).unwrap();
+/// list.splice_after(one, run).unwrap();
+/// list.splice_after(one, run).unwrap();
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Detached {
+    first: usize,
+    last: usize,
+    len: usize,
+}
+
+impl Detached {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Values which map to a small, dense range of `usize`s, so they can be looked up in a `Vec`.
+pub trait DenseKey {
+    fn dense_key(&self) -> usize;
+}
+
+macro_rules! impl_dense_key {
+    ($($t:ty),*) => {
+        $(
+            impl DenseKey for $t {
+                fn dense_key(&self) -> usize {
+                    *self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_dense_key!(u8, u16, u32, u64, usize);
+
+const NONE: usize = usize::MAX;
+
+struct Node<T> {
+    value: Option<T>,
+    next: usize,
+    prev: usize,
+    detached: bool,
+}
+
+pub struct CircularList<T> {
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    cursor: usize,
+    len: usize,
+    key: Option<fn(&T) -> usize>,
+    index: Vec<usize>,
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> CircularList<T> {
+        CircularList::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> CircularList<T> {
+        CircularList {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            cursor: NONE,
+            len: 0,
+            key: None,
+            index: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn cursor(&self) -> Option<NodeRef> {
+        if self.cursor == NONE {
+            None
+        } else {
+            Some(NodeRef(self.cursor))
+        }
+    }
+
+    pub fn current(&self) -> Option<&T> {
+        self.nodes.get(self.cursor)?.value.as_ref()
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.nodes.get_mut(self.cursor)?.value.as_mut()
+    }
+
+    /// The value at `node`, or `None` if it's been removed or is currently detached.
+    pub fn get(&self, node: NodeRef) -> Option<&T> {
+        match self.nodes.get(node.0)? {
+            Node { detached: true, .. } => None,
+            Node { value, .. } => value.as_ref(),
+        }
+    }
+
+    pub fn next(&self, node: NodeRef) -> Option<NodeRef> {
+        self.get(node)?;
+        Some(NodeRef(self.nodes[node.0].next))
+    }
+
+    pub fn prev(&self, node: NodeRef) -> Option<NodeRef> {
+        self.get(node)?;
+        Some(NodeRef(self.nodes[node.0].prev))
+    }
+
+    /// Moves the cursor to `node`. Returns false, leaving the cursor where it was, if the
+    /// node isn't in the list.
+    pub fn seek(&mut self, node: NodeRef) -> bool {
+        if self.get(node).is_some() {
+            self.cursor = node.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        if self.cursor != NONE {
+            self.cursor = self.nodes[self.cursor].next;
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if self.cursor != NONE {
+            self.cursor = self.nodes[self.cursor].prev;
+        }
+    }
+
+    /// Moves the cursor `n` places clockwise, or anticlockwise if `n` is negative. Whole
+    /// laps of the circle are skipped, so this is never more than `len` steps.
+    pub fn move_by(&mut self, n: isize) {
+        if self.len == 0 {
+            return;
+        }
+        let steps = n.rem_euclid(self.len as isize) as usize;
+        if steps <= self.len / 2 {
+            for _ in 0..steps {
+                self.move_next();
+            }
+        } else {
+            for _ in 0..(self.len - steps) {
+                self.move_prev();
+            }
+        }
+    }
+
+    /// Puts `value` after `node` and returns the new node. Inserting into an empty list
+    /// ignores `node` and makes the new node the cursor.
+    pub fn insert_after_node(&mut self, node: NodeRef, value: T) -> Option<NodeRef> {
+        if self.len == 0 {
+            let new = self.allocate(value);
+            self.nodes[new].next = new;
+            self.nodes[new].prev = new;
+            self.cursor = new;
+            return Some(NodeRef(new));
+        }
+        self.get(node)?;
+        let next = self.nodes[node.0].next;
+        let new = self.allocate(value);
+        self.nodes[new].prev = node.0;
+        self.nodes[new].next = next;
+        self.nodes[node.0].next = new;
+        self.nodes[next].prev = new;
+        Some(NodeRef(new))
+    }
+
+    pub fn insert_before_node(&mut self, node: NodeRef, value: T) -> Option<NodeRef> {
+        if self.len == 0 {
+            return self.insert_after_node(node, value);
+        }
+        let prev = self.prev(node)?;
+        self.insert_after_node(prev, value)
+    }
+
+    /// Inserts after the cursor, without moving it.
+    pub fn insert_after(&mut self, value: T) -> NodeRef {
+        self.insert_after_node(NodeRef(self.cursor), value)
+            .expect("The cursor is always valid in a non-empty list")
+    }
+
+    /// Inserts before the cursor, without moving it.
+    pub fn insert_before(&mut self, value: T) -> NodeRef {
+        self.insert_before_node(NodeRef(self.cursor), value)
+            .expect("The cursor is always valid in a non-empty list")
+    }
+
+    /// Removes `node`. If it was the cursor, the cursor moves on to the next node.
+    pub fn remove(&mut self, node: NodeRef) -> Option<T> {
+        self.get(node)?;
+        let Node { next, prev, .. } = self.nodes[node.0];
+        if self.len == 1 {
+            self.cursor = NONE;
+        } else {
+            self.nodes[prev].next = next;
+            self.nodes[next].prev = prev;
+            if self.cursor == node.0 {
+                self.cursor = next;
+            }
+        }
+        self.release(node.0)
+    }
+
+    /// Removes the node under the cursor, and moves the cursor to the next one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.remove(NodeRef(self.cursor))
+    }
+
+    /// Cuts `count` nodes out from after `node`, keeping them in the arena so they can be
+    /// spliced back in elsewhere without reallocating. The detached nodes can't be reached
+    /// by moving around the list, but their values can still be read with
+    /// `detached_values`. If the cursor was in the detached run it moves back to `node`.
+    /// Fails if that would take `node` itself or leave the list empty, or if `node` is
+    /// detached itself.
+    pub fn detach_after(&mut self, node: NodeRef, count: usize) -> Option<Detached> {
+        self.get(node)?;
+        if count == 0 || count >= self.len {
+            return None;
+        }
+        let first = self.nodes[node.0].next;
+        let mut last = first;
+        let mut takes_cursor = first == self.cursor;
+        self.nodes[first].detached = true;
+        for _ in 1..count {
+            last = self.nodes[last].next;
+            takes_cursor |= last == self.cursor;
+            self.nodes[last].detached = true;
+        }
+        if takes_cursor {
+            self.cursor = node.0;
+        }
+        let after = self.nodes[last].next;
+        self.nodes[node.0].next = after;
+        self.nodes[after].prev = node.0;
+        self.nodes[first].prev = NONE;
+        self.nodes[last].next = NONE;
+        self.len -= count;
+        Some(Detached {
+            first,
+            last,
+            len: count,
+        })
+    }
+
+    pub fn detached_values<'a>(&'a self, detached: &Detached) -> impl Iterator<Item = &'a T> {
+        let mut current = detached.first;
+        (0..detached.len).map(move |_| {
+            let node = &self.nodes[current];
+            current = node.next;
+            node.value.as_ref().unwrap()
+        })
+    }
+
+    /// Puts a detached run of nodes back into the list after `node`. If `node` isn't in the
+    /// list - which includes it being detached, perhaps in this very run - the run is handed
+    /// back untouched.
+    pub fn splice_after(&mut self, node: NodeRef, detached: Detached) -> Result<(), Detached> {
+        if self.get(node).is_none() {
+            return Err(detached);
+        }
+        let mut current = detached.first;
+        for _ in 0..detached.len {
+            self.nodes[current].detached = false;
+            current = self.nodes[current].next;
+        }
+        let next = self.nodes[node.0].next;
+        self.nodes[node.0].next = detached.first;
+        self.nodes[detached.first].prev = node.0;
+        self.nodes[detached.last].next = next;
+        self.nodes[next].prev = detached.last;
+        self.len += detached.len;
+        Ok(())
+    }
+
+    /// Iterates once around the circle starting from the cursor.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.iter_from(NodeRef(self.cursor))
+    }
+
+    /// Iterates once around the circle starting from `node`.
+    pub fn iter_from(&self, node: NodeRef) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            current: node.0,
+            remaining: if self.get(node).is_some() {
+                self.len
+            } else {
+                0
+            },
+        }
+    }
+
+    fn allocate(&mut self, value: T) -> usize {
+        let key = self.key.map(|key| key(&value));
+        let node = Node {
+            value: Some(value),
+            next: NONE,
+            prev: NONE,
+            detached: false,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        if let Some(k) = key {
+            if k >= self.index.len() {
+                self.index.resize(k + 1, NONE);
+            }
+            self.index[k] = slot;
+        }
+        self.len += 1;
+        slot
+    }
+
+    fn release(&mut self, slot: usize) -> Option<T> {
+        let value = self.nodes[slot].value.take();
+        if let (Some(key), Some(v)) = (self.key, value.as_ref()) {
+            self.index[key(v)] = NONE;
+        }
+        self.free.push(slot);
+        self.len -= 1;
+        value
+    }
+}
+
+impl<T: DenseKey> CircularList<T> {
+    /// A list which keeps a `Vec` from each value's key to its node, so `find` is O(1).
+    /// Values must be distinct, and their keys shouldn't be much bigger than the number
+    /// of values or the lookup table will waste space.
+    pub fn with_value_index(capacity: usize) -> CircularList<T> {
+        let mut list = CircularList::with_capacity(capacity);
+        list.key = Some(T::dense_key);
+        list.index = Vec::with_capacity(capacity + 1);
+        list
+    }
+
+    pub fn indexed_from<I: IntoIterator<Item = T>>(values: I) -> CircularList<T> {
+        let values = values.into_iter();
+        let mut list = CircularList::with_value_index(values.size_hint().0);
+        list.extend(values);
+        list
+    }
+
+    /// Finds the node holding `value`. This is only fast for lists made with
+    /// `with_value_index`; otherwise it searches the circle. Detached nodes are still found
+    /// through the index, though the list treats them as missing until they're spliced back.
+    pub fn find(&self, value: &T) -> Option<NodeRef> {
+        let key = value.dense_key();
+        if self.key.is_some() {
+            match self.index.get(key) {
+                Some(slot) if *slot != NONE => Some(NodeRef(*slot)),
+                _ => None,
+            }
+        } else {
+            let mut node = self.cursor;
+            for _ in 0..self.len {
+                if self.nodes[node].value.as_ref().unwrap().dense_key() == key {
+                    return Some(NodeRef(node));
+                }
+                node = self.nodes[node].next;
+            }
+            None
+        }
+    }
+}
+
+impl<T> Default for CircularList<T> {
+    fn default() -> Self {
+        CircularList::new()
+    }
+}
+
+impl<T> Extend<T> for CircularList<T> {
+    /// Adds values before the cursor - that is, at the "end" of the circle.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            if self.is_empty() {
+                self.insert_after_node(NodeRef(NONE), value);
+            } else {
+                self.insert_before(value);
+            }
+        }
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut list = CircularList::new();
+        list.extend(values);
+        list
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a CircularList<T>,
+    current: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let node = &self.list.nodes[self.current];
+        self.current = node.next;
+        node.value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CircularList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+fn values<T: Clone>(list: &CircularList<T>) -> Vec<T> {
+    list.iter().cloned().collect()
+}
+
+#[test]
+fn test_from_iter_and_iterate() {
+    let list = (1..=5).collect::<CircularList<u32>>();
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.current(), Some(&1));
+    assert_eq!(values(&list), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_move_by() {
+    let mut list = (0..5).collect::<CircularList<u32>>();
+    list.move_by(2);
+    assert_eq!(list.current(), Some(&2));
+    list.move_by(-3);
+    assert_eq!(list.current(), Some(&4));
+    list.move_by(11);
+    assert_eq!(list.current(), Some(&0));
+    list.move_by(-7);
+    assert_eq!(list.current(), Some(&3));
+}
+
+#[test]
+fn test_insert_and_remove() {
+    let mut list = CircularList::new();
+    list.extend(vec![0]);
+    list.insert_after(1);
+    list.insert_before(2);
+    assert_eq!(values(&list), vec![0, 1, 2]);
+    list.move_next();
+    assert_eq!(list.remove_current(), Some(1));
+    assert_eq!(list.current(), Some(&2));
+    assert_eq!(values(&list), vec![2, 0]);
+    assert_eq!(list.remove_current(), Some(2));
+    assert_eq!(list.remove_current(), Some(0));
+    assert!(list.is_empty());
+    assert_eq!(list.current(), None);
+    list.extend(vec![7]);
+    assert_eq!(values(&list), vec![7]);
+}
+
+#[test]
+fn test_removed_slots_are_reused() {
+    let mut list = (0..3).collect::<CircularList<u32>>();
+    let removed = list.cursor().unwrap();
+    list.remove_current();
+    assert_eq!(list.get(removed), None);
+    let reused = list.insert_after(9);
+    assert_eq!(reused, removed);
+    assert_eq!(list.nodes.len(), 3);
+}
+
+#[test]
+fn test_detach_and_splice() {
+    let mut list = CircularList::indexed_from(vec![1u32, 2, 3, 4, 5, 6]);
+    let one = list.find(&1).unwrap();
+    let three = list.detach_after(one, 3).unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(values(&list), vec![1, 5, 6]);
+    assert_eq!(
+        list.detached_values(&three).cloned().collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+    let five = list.find(&5).unwrap();
+    list.splice_after(five, three).unwrap();
+    assert_eq!(values(&list), vec![1, 5, 2, 3, 4, 6]);
+    assert!(list.detach_after(one, 6).is_none());
+}
+
+#[test]
+fn test_detached_nodes_are_missing() {
+    let mut list = CircularList::indexed_from(vec![1u32, 2, 3, 4, 5, 6]);
+    let one = list.find(&1).unwrap();
+    let run = list.detach_after(one, 3).unwrap();
+    let three = list.find(&3).unwrap();
+
+    assert_eq!(list.get(three), None);
+    assert_eq!(list.next(three), None);
+    assert_eq!(list.prev(three), None);
+    assert!(!list.seek(three));
+    assert_eq!(list.iter_from(three).count(), 0);
+    assert_eq!(list.insert_after_node(three, 7), None);
+    assert_eq!(list.remove(three), None);
+    assert!(list.detach_after(three, 1).is_none());
+
+    // nor can the run go back in after one of its own nodes
+    let run = list.splice_after(three, run).unwrap_err();
+    assert_eq!(values(&list), vec![1, 5, 6]);
+    assert_eq!(list.len(), 3);
+
+    list.splice_after(one, run).unwrap();
+    assert_eq!(list.get(three), Some(&3));
+    assert_eq!(values(&list), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_find() {
+    let mut indexed = CircularList::indexed_from(vec![3u8, 8, 9, 1]);
+    let mut unindexed = vec![3u8, 8, 9, 1].into_iter().collect::<CircularList<_>>();
+    for list in [&mut indexed, &mut unindexed].iter_mut() {
+        let nine = list.find(&9).unwrap();
+        assert_eq!(list.get(nine), Some(&9));
+        assert_eq!(list.next(nine).and_then(|n| list.get(n)), Some(&1));
+        assert_eq!(list.find(&2), None);
+        list.remove(nine);
+        assert_eq!(list.find(&9), None);
+        list.insert_after(20);
+        let twenty = list.find(&20).unwrap();
+        assert_eq!(list.prev(twenty).and_then(|n| list.get(n)), Some(&3));
+    }
+}
@@ -0,0 +1,320 @@
+//! Finding where a sequence of states starts repeating itself, and using that to skip ahead.
+//!
+//! A sequence which repeats is described by a [`Cycle`]: the first `prefix` states are never
+//! seen again, and from then on every `period` states it comes back round to the same place.
+//!
+//! There are three ways to find one. [`floyd`] and [`brent`] only ever hold on to a couple of
+//! states, but have to run the sequence more than once, so they want an iterator they can
+//! clone (`std::iter::successors` is handy). [`hashed`] runs the sequence just once but
+//! remembers everything it's seen.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// How many states come before the loop starts.
+    pub prefix: usize,
+    /// How many states are in the loop.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The index of the first state which has been seen before.
+    pub fn first_repeat(&self) -> usize {
+        self.prefix + self.period
+    }
+
+    /// The earliest index holding the same state as index `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Floyd's tortoise and hare. Returns `None` if the sequence ends without repeating.
+pub fn floyd<I>(states: I) -> Option<Cycle>
+where
+    I: Iterator + Clone,
+    I::Item: PartialEq,
+{
+    // the hare runs at twice the tortoise's speed until they land on the same state
+    let mut tortoise = states.clone();
+    let mut hare = states.clone();
+    tortoise.next()?;
+    hare.next()?;
+    let mut hare_state = loop {
+        let tortoise_state = tortoise.next()?;
+        hare.next()?;
+        let hare_state = hare.next()?;
+        if tortoise_state == hare_state {
+            break hare_state;
+        }
+    };
+
+    // the hare is now a whole number of periods ahead of a fresh tortoise, so moving them
+    // together finds the start of the loop
+    let mut tortoise = states;
+    let mut tortoise_state = tortoise.next()?;
+    let mut prefix = 0;
+    while tortoise_state != hare_state {
+        tortoise_state = tortoise.next()?;
+        hare_state = hare.next()?;
+        prefix += 1;
+    }
+
+    let mut period = 1;
+    while hare.next()? != tortoise_state {
+        period += 1;
+    }
+
+    Some(Cycle { prefix, period })
+}
+
+/// Brent's algorithm, which usually needs fewer steps than Floyd's.
+/// Returns `None` if the sequence ends without repeating.
+pub fn brent<I>(states: I) -> Option<Cycle>
+where
+    I: Iterator + Clone,
+    I::Item: PartialEq + Clone,
+{
+    // find the period by having the tortoise jump to the hare at each power of two
+    let mut hare = states.clone();
+    let mut tortoise_state = hare.next()?;
+    let mut hare_state = hare.next()?;
+    let mut power = 1;
+    let mut period = 1;
+    while tortoise_state != hare_state {
+        if power == period {
+            tortoise_state = hare_state.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare_state = hare.next()?;
+        period += 1;
+    }
+
+    // then start the hare a period ahead and move them together to find the prefix
+    let mut tortoise = states.clone();
+    let mut hare = states;
+    let mut tortoise_state = tortoise.next()?;
+    let mut hare_state = hare.nth(period)?;
+    let mut prefix = 0;
+    while tortoise_state != hare_state {
+        tortoise_state = tortoise.next()?;
+        hare_state = hare.next()?;
+        prefix += 1;
+    }
+
+    Some(Cycle { prefix, period })
+}
+
+/// Finds the first repeated state by remembering all of them.
+/// Returns `None` if the sequence ends without repeating.
+pub fn hashed<I>(states: I) -> Option<Cycle>
+where
+    I: IntoIterator,
+    I::Item: Hash + Eq,
+{
+    hashed_by(states, |s| s)
+}
+
+/// As `hashed`, but states are compared by the key `key` makes from them, which is useful
+/// when the states themselves are expensive to keep or don't implement `Hash`.
+pub fn hashed_by<I, K>(states: I, mut key: impl FnMut(I::Item) -> K) -> Option<Cycle>
+where
+    I: IntoIterator,
+    K: Hash + Eq,
+{
+    let mut seen = HashMap::new();
+    for (index, state) in states.into_iter().enumerate() {
+        if let Some(previous) = seen.insert(key(state), index) {
+            return Some(Cycle {
+                prefix: previous,
+                period: index - previous,
+            });
+        }
+    }
+    None
+}
+
+/// The state after `n` applications of `step` to `start`, skipping the work once the states
+/// start repeating.
+pub fn nth_state<S>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+{
+    let mut history = vec![start];
+    let mut seen = HashMap::new();
+    seen.insert(history[0].clone(), 0);
+
+    while history.len() <= n {
+        let next = step(history.last().unwrap());
+        if let Some(&prefix) = seen.get(&next) {
+            let cycle = Cycle {
+                prefix,
+                period: history.len() - prefix,
+            };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+        seen.insert(next.clone(), history.len());
+        history.push(next);
+    }
+
+    history.swap_remove(n)
+}
+
+/// As `nth_state`, for states which come back to the same shape but moved along, such as a
+/// pattern drifting steadily across an infinite line.
+///
+/// `normalise` splits a state into a key describing its shape and its current offset. Once a
+/// shape repeats, the offset is assumed to drift by the same amount every time round the
+/// loop, and `translate` is used to move an earlier state along by the total drift.
+pub fn nth_state_translated<S, K>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+    normalise: impl Fn(&S) -> (K, i64),
+    translate: impl Fn(&S, i64) -> S,
+) -> S
+where
+    K: Hash + Eq,
+{
+    let (key, offset) = normalise(&start);
+    let mut history = vec![(start, offset)];
+    let mut seen = HashMap::new();
+    seen.insert(key, 0);
+
+    while history.len() <= n {
+        let next = step(&history.last().unwrap().0);
+        let (key, offset) = normalise(&next);
+        if let Some(&prefix) = seen.get(&key) {
+            let cycle = Cycle {
+                prefix,
+                period: history.len() - prefix,
+            };
+            let drift = offset - history[prefix].1;
+            let loops = ((n - prefix) / cycle.period) as i64;
+            return translate(&history[cycle.equivalent_step(n)].0, drift * loops);
+        }
+        seen.insert(key, history.len());
+        history.push((next, offset));
+    }
+
+    history.swap_remove(n).0
+}
+
+#[cfg(test)]
+fn rho(prefix: usize, period: usize) -> impl Iterator<Item = usize> + Clone {
+    // 0, 1, ... prefix - 1, then loops round prefix .. prefix + period - 1
+    std::iter::successors(Some(0), move |n| {
+        Some(if n + 1 == prefix + period {
+            prefix
+        } else {
+            n + 1
+        })
+    })
+}
+
+#[test]
+fn test_detectors_agree() {
+    for prefix in 0..6 {
+        for period in 1..9 {
+            let expected = Some(Cycle { prefix, period });
+            assert_eq!(floyd(rho(prefix, period)), expected, "floyd");
+            assert_eq!(brent(rho(prefix, period)), expected, "brent");
+            assert_eq!(hashed(rho(prefix, period)), expected, "hashed");
+        }
+    }
+}
+
+#[test]
+fn test_sequence_without_cycle() {
+    assert_eq!(floyd(0..10), None);
+    assert_eq!(brent(0..10), None);
+    assert_eq!(hashed(0..10), None);
+}
+
+#[test]
+fn test_memory_banks() {
+    // the example from 2017 day 6
+    fn redistribute(banks: &[u32]) -> Vec<u32> {
+        let mut banks = banks.to_vec();
+        let (source, _) = banks
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, b)| **b)
+            .unwrap();
+        let blocks = banks[source];
+        banks[source] = 0;
+        for i in 1..=blocks as usize {
+            let len = banks.len();
+            banks[(source + i) % len] += 1;
+        }
+        banks
+    }
+
+    let states = std::iter::successors(Some(vec![0, 2, 7, 0]), |b| Some(redistribute(b)));
+    let cycle = brent(states.clone()).unwrap();
+    assert_eq!(cycle.first_repeat(), 5);
+    assert_eq!(cycle.period, 4);
+    assert_eq!(hashed(states), Some(cycle));
+}
+
+#[test]
+fn test_equivalent_step() {
+    let cycle = Cycle {
+        prefix: 3,
+        period: 4,
+    };
+    assert_eq!(cycle.equivalent_step(2), 2);
+    assert_eq!(cycle.equivalent_step(3), 3);
+    assert_eq!(cycle.equivalent_step(7), 3);
+    assert_eq!(cycle.equivalent_step(1_000_000_002), 6);
+}
+
+#[test]
+fn test_nth_state() {
+    let step = |n: &u64| (n * n + 1) % 255;
+    let mut expected = 3;
+    for _ in 0..1000 {
+        expected = step(&expected);
+    }
+    assert_eq!(nth_state(3, step, 1000), expected);
+    assert_eq!(nth_state(3, step, 0), 3);
+    assert_eq!(nth_state(3, step, 2), 10 * 10 + 1);
+}
+
+#[test]
+fn test_nth_state_translated() {
+    // a glider on a line: ## -> .## -> ..## moving right one each step, after a wobble
+    // at the start
+    let step = |cells: &Vec<i64>| -> Vec<i64> {
+        if cells.len() == 3 {
+            vec![cells[0], cells[1]]
+        } else {
+            cells.iter().map(|c| c + 1).collect()
+        }
+    };
+    let normalise = |cells: &Vec<i64>| {
+        let offset = cells[0];
+        (cells.iter().map(|c| c - offset).collect::<Vec<_>>(), offset)
+    };
+    let translate = |cells: &Vec<i64>, by: i64| cells.iter().map(|c| c + by).collect();
+
+    let start = vec![5, 6, 7];
+    let result = nth_state_translated(start.clone(), step, 50_000_000_000, normalise, translate);
+    assert_eq!(result, vec![5 + 49_999_999_999, 6 + 49_999_999_999]);
+    assert_eq!(
+        nth_state_translated(start.clone(), step, 0, normalise, translate),
+        start
+    );
+    assert_eq!(
+        nth_state_translated(start, step, 3, normalise, translate),
+        vec![7, 8]
+    );
+}
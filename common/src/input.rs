@@ -0,0 +1,20 @@
+//! Puzzle inputs which aren't checked in, read from the day's directory when the day runs
+//! rather than built in with `include_str!`.
+
+use std::fs;
+
+/// Reads `<crate_dir>/src/<day_dir>/input.txt`. `crate_dir` should be the calling crate's
+/// `env!("CARGO_MANIFEST_DIR")`, so that it works from wherever the binary is run.
+pub fn read_input(crate_dir: &str, day_dir: &str) -> Result<String, String> {
+    let path = format!("{}/src/{}/input.txt", crate_dir, day_dir);
+    fs::read_to_string(&path).map_err(|e| format!("Couldn't read {}: {}", path, e))
+}
+
+#[test]
+fn test_missing_input() {
+    let error = read_input(env!("CARGO_MANIFEST_DIR"), "day0").unwrap_err();
+    assert!(error.starts_with(&format!(
+        "Couldn't read {}/src/day0/input.txt: ",
+        env!("CARGO_MANIFEST_DIR")
+    )));
+}
@@ -0,0 +1,11 @@
+//! Code shared between more than one year's solutions.
+
+pub mod circular;
+pub mod cycle;
+pub mod input;
+pub mod numtheory;
+pub mod ocr;
+pub mod schedule;
+pub mod summed_area;
+pub mod transform;
+pub mod turing;
@@ -0,0 +1,340 @@
+//! Number theory for the puzzles which are secretly about modular arithmetic.
+//!
+//! Everything works on any of the primitive integer types through the [`Integer`] trait.
+//! Functions which need negative numbers along the way (the extended GCD and the things built
+//! on it) are only available for the signed types.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+
+    /// The remainder after dividing by `modulus`, which is never negative.
+    fn modulo(self, modulus: Self) -> Self;
+
+    /// `self * other` modulo `modulus`, without overflowing even when the product wouldn't fit.
+    fn mul_mod(self, other: Self, modulus: Self) -> Self;
+}
+
+pub trait SignedInteger: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    self
+                }
+
+                fn modulo(self, modulus: Self) -> Self {
+                    self % modulus
+                }
+
+                fn mul_mod(self, other: Self, modulus: Self) -> Self {
+                    ((self as u128 * other as u128) % modulus as u128) as Self
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn modulo(self, modulus: Self) -> Self {
+                    self.rem_euclid(modulus)
+                }
+
+                fn mul_mod(self, other: Self, modulus: Self) -> Self {
+                    (self as i128 * other as i128).rem_euclid(modulus as i128) as Self
+                }
+            }
+
+            impl SignedInteger for $t {}
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+// there's nothing wider to multiply these in, so they double and add instead, which works
+// as long as the modulus is less than half the type's maximum
+
+impl Integer for u128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn abs(self) -> Self {
+        self
+    }
+
+    fn modulo(self, modulus: Self) -> Self {
+        self % modulus
+    }
+
+    fn mul_mod(self, other: Self, modulus: Self) -> Self {
+        double_and_add(self % modulus, other % modulus, modulus)
+    }
+}
+
+impl Integer for i128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn abs(self) -> Self {
+        i128::abs(self)
+    }
+
+    fn modulo(self, modulus: Self) -> Self {
+        self.rem_euclid(modulus)
+    }
+
+    fn mul_mod(self, other: Self, modulus: Self) -> Self {
+        double_and_add(self.rem_euclid(modulus), other.rem_euclid(modulus), modulus)
+    }
+}
+
+impl SignedInteger for i128 {}
+
+fn double_and_add<T: Integer>(mut a: T, mut b: T, modulus: T) -> T {
+    let two = T::ONE + T::ONE;
+    let mut result = T::ZERO;
+    while b > T::ZERO {
+        if b % two == T::ONE {
+            result = (result + a) % modulus;
+        }
+        a = (a + a) % modulus;
+        b = b / two;
+    }
+    result
+}
+
+/// The greatest common divisor, which is never negative.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// The least common multiple, which is never negative.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// The least common multiple of any number of values, or one if there aren't any.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` where `g` is the greatest common divisor of `a` and `b`, and
+/// `a * x + b * y == g`.
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        let next_r = old_r - q * r;
+        old_r = r;
+        r = next_r;
+        let next_x = old_x - q * x;
+        old_x = x;
+        x = next_x;
+        let next_y = old_y - q * y;
+        old_y = y;
+        y = next_y;
+    }
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..modulus` for which `a * x` is 1 modulo `modulus`, if there is one
+/// (there is exactly when `a` and `modulus` have no common factor).
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.modulo(modulus), modulus);
+    if g == T::ONE {
+        Some(x.modulo(modulus))
+    } else {
+        None
+    }
+}
+
+/// Solves a set of congruences `x ≡ residue (mod modulus)` with the Chinese Remainder
+/// Theorem. The moduli don't have to be coprime. Returns the smallest non-negative solution
+/// and the modulus it repeats with (the LCM of all the moduli), or `None` if the congruences
+/// contradict each other.
+pub fn crt<T: SignedInteger>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let mut solution = T::ZERO;
+    let mut combined_modulus = T::ONE;
+    for (residue, modulus) in congruences {
+        let residue = residue.modulo(modulus);
+        let (g, p, _) = extended_gcd(combined_modulus, modulus);
+        let difference = residue - solution;
+        if difference.modulo(g) != T::ZERO {
+            return None;
+        }
+        // solution + combined_modulus * k ≡ residue (mod modulus)
+        let step = modulus / g;
+        let k = (difference / g).mul_mod(p, step);
+        let new_modulus = combined_modulus * step;
+        solution = (solution + combined_modulus * k).modulo(new_modulus);
+        combined_modulus = new_modulus;
+    }
+    Some((solution, combined_modulus))
+}
+
+/// `base` to the power `exponent`, modulo `modulus`.
+pub fn mod_pow<T: Integer>(base: T, exponent: T, modulus: T) -> T {
+    let two = T::ONE + T::ONE;
+    let mut result = T::ONE.modulo(modulus);
+    let mut base = base.modulo(modulus);
+    let mut exponent = exponent;
+    while exponent > T::ZERO {
+        if exponent % two == T::ONE {
+            result = result.mul_mod(base, modulus);
+        }
+        base = base.mul_mod(base, modulus);
+        exponent = exponent / two;
+    }
+    result
+}
+
+/// The smallest non-negative `x` with `base` to the power `x` equal to `target` modulo
+/// `modulus`, found with the baby-step giant-step algorithm in about `sqrt(modulus)` steps.
+/// `base` should have no factor in common with `modulus`, otherwise some solutions may be
+/// missed.
+pub fn discrete_log<T: Integer>(base: T, target: T, modulus: T) -> Option<T> {
+    let target = target.modulo(modulus);
+    if T::ONE.modulo(modulus) == target {
+        return Some(T::ZERO);
+    }
+
+    // smallest m with m * m >= modulus
+    let mut m = T::ONE;
+    while m < modulus / m || (m == modulus / m && modulus % m != T::ZERO) {
+        m = m + T::ONE;
+    }
+
+    // baby steps: target * base^j for each j below m, keeping the largest j for each value
+    // as that gives the smallest answer
+    let mut baby_steps = HashMap::new();
+    let mut value = target;
+    let mut j = T::ZERO;
+    while j < m {
+        baby_steps.insert(value, j);
+        value = value.mul_mod(base, modulus);
+        j = j + T::ONE;
+    }
+
+    // giant steps: base^(i * m), looking for a match so that base^(i * m - j) == target
+    let giant_step = mod_pow(base, m, modulus);
+    let mut value = T::ONE.modulo(modulus);
+    let mut i = T::ONE;
+    while i <= m {
+        value = value.mul_mod(giant_step, modulus);
+        if let Some(&j) = baby_steps.get(&value) {
+            let candidate = i * m - j;
+            if mod_pow(base, candidate, modulus) == target {
+                return Some(candidate);
+            }
+        }
+        i = i + T::ONE;
+    }
+    None
+}
+
+#[test]
+fn test_gcd_and_lcm() {
+    assert_eq!(gcd(12u32, 18), 6);
+    assert_eq!(gcd(-12i64, 18), 6);
+    assert_eq!(gcd(0u8, 5), 5);
+    assert_eq!(lcm(4i32, 6), 12);
+    assert_eq!(lcm_all(vec![1u64, 1, 1]), 1);
+    assert_eq!(lcm_all(vec![2u64, 4, 1]), 4);
+    assert_eq!(lcm_all(vec![10u64, 20, 30]), 60);
+    assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+}
+
+#[test]
+fn test_extended_gcd_and_inverse() {
+    let (g, x, y) = extended_gcd(240i64, 46);
+    assert_eq!(g, 2);
+    assert_eq!(240 * x + 46 * y, 2);
+    assert_eq!(mod_inverse(3i32, 11), Some(4));
+    assert_eq!(mod_inverse(-3i32, 11), Some(7));
+    assert_eq!(mod_inverse(6i32, 9), None);
+}
+
+#[test]
+fn test_crt() {
+    // the bus example from 2020 day 13: bus b must leave i minutes after the answer
+    let buses = [(7i64, 0i64), (13, 1), (59, 4), (31, 6), (19, 7)];
+    let congruences = buses.iter().map(|&(b, i)| (-i, b));
+    assert_eq!(crt(congruences), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+
+    // moduli sharing factors
+    assert_eq!(crt(vec![(2i32, 6), (4, 8)]), Some((20, 24)));
+    assert_eq!(crt(vec![(1i32, 6), (2, 4)]), None);
+    assert_eq!(crt(Vec::<(i32, i32)>::new()), Some((0, 1)));
+}
+
+#[test]
+fn test_mod_pow() {
+    assert_eq!(mod_pow(7u64, 8, 20201227), 5764801);
+    assert_eq!(mod_pow(2i32, 10, 1000), 24);
+    assert_eq!(mod_pow(5u8, 0, 7), 1);
+    let big = 170141183460469231731687303715884105727u128 / 4;
+    assert_eq!(mod_pow(big - 1, 2, big), 1);
+}
+
+#[test]
+fn test_discrete_log() {
+    // the card and door public keys from the 2020 day 25 example
+    assert_eq!(discrete_log(7u64, 5764801, 20201227), Some(8));
+    assert_eq!(discrete_log(7u64, 17807724, 20201227), Some(11));
+    assert_eq!(discrete_log(3i32, 1, 7), Some(0));
+    assert_eq!(discrete_log(2u32, 3, 7), None);
+    for x in 0..30u32 {
+        let target = mod_pow(3, x, 31);
+        assert_eq!(mod_pow(3, discrete_log(3, target, 31).unwrap(), 31), target);
+        assert!(discrete_log(3, target, 31).unwrap() <= x);
+    }
+}
@@ -0,0 +1,319 @@
+//! Reads the block capital letters some puzzles draw their answers in.
+//!
+//! There are two fonts: a small one six pixels high (letters four wide, with a gap of one
+//! between them) and a large one ten pixels high (six wide, gap of two). The font is picked
+//! by the height of the picture once blank rows are trimmed off, and letters are split apart
+//! at blank columns, so the picture doesn't need to be lined up exactly.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Nothing was lit at all.
+    Empty,
+    /// The lit area isn't the height of any font we know.
+    UnknownHeight(usize),
+    /// The letter at `position` (counting from zero) didn't match anything in the font.
+    UnknownGlyph { position: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "There are no letters to read"),
+            OcrError::UnknownHeight(h) => write!(f, "No font is {} pixels high", h),
+            OcrError::UnknownGlyph { position, glyph } => write!(
+                f,
+                "Letter {} isn't one I recognise:\n{}",
+                position + 1,
+                glyph
+            ),
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+const SMALL_FONT: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_FONT: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Reads the letters from a picture `width` by `height` pixels, where `is_lit` says whether
+/// the pixel at `(x, y)` is part of a letter.
+pub fn recognise(
+    width: usize,
+    height: usize,
+    is_lit: impl Fn(usize, usize) -> bool,
+) -> Result<String, OcrError> {
+    let lit_rows = (0..height)
+        .filter(|y| (0..width).any(|x| is_lit(x, *y)))
+        .collect::<Vec<_>>();
+    let (top, bottom) = match (lit_rows.first(), lit_rows.last()) {
+        (Some(top), Some(bottom)) => (*top, *bottom),
+        _ => return Err(OcrError::Empty),
+    };
+    let font = match bottom - top + 1 {
+        6 => SMALL_FONT,
+        10 => LARGE_FONT,
+        h => return Err(OcrError::UnknownHeight(h)),
+    };
+
+    let column_lit = |x: usize| (top..=bottom).any(|y| is_lit(x, y));
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < width {
+        if !column_lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && column_lit(x) {
+            x += 1;
+        }
+        let glyph = (top..=bottom)
+            .map(|y| {
+                (start..x)
+                    .map(|gx| if is_lit(gx, y) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        match font
+            .iter()
+            .find(|(_, pattern)| trim_blank_columns(pattern) == glyph)
+        {
+            Some((letter, _)) => letters.push(*letter),
+            None => {
+                return Err(OcrError::UnknownGlyph {
+                    position: letters.len(),
+                    glyph,
+                })
+            }
+        }
+    }
+
+    Ok(letters)
+}
+
+/// Reads the letters from a picture given as rows of pixels.
+pub fn recognise_rows<R: AsRef<[bool]>>(rows: &[R]) -> Result<String, OcrError> {
+    let width = rows.iter().map(|r| r.as_ref().len()).max().unwrap_or(0);
+    recognise(width, rows.len(), |x, y| {
+        rows[y].as_ref().get(x).cloned().unwrap_or(false)
+    })
+}
+
+/// Reads the letters drawn by a set of lit points, which can be anywhere on the plane.
+/// `y` increases downwards.
+pub fn recognise_points<I: IntoIterator<Item = (i64, i64)>>(points: I) -> Result<String, OcrError> {
+    let points = points.into_iter().collect::<HashSet<_>>();
+    let min_x = points
+        .iter()
+        .map(|(x, _)| *x)
+        .min()
+        .ok_or(OcrError::Empty)?;
+    let max_x = points
+        .iter()
+        .map(|(x, _)| *x)
+        .max()
+        .ok_or(OcrError::Empty)?;
+    let min_y = points
+        .iter()
+        .map(|(_, y)| *y)
+        .min()
+        .ok_or(OcrError::Empty)?;
+    let max_y = points
+        .iter()
+        .map(|(_, y)| *y)
+        .max()
+        .ok_or(OcrError::Empty)?;
+    recognise(
+        (max_x - min_x + 1) as usize,
+        (max_y - min_y + 1) as usize,
+        |x, y| points.contains(&(min_x + x as i64, min_y + y as i64)),
+    )
+}
+
+fn trim_blank_columns(pattern: &str) -> String {
+    let rows = pattern.lines().collect::<Vec<_>>();
+    let width = rows[0].len();
+    let lit = |x: usize| rows.iter().any(|r| r.as_bytes()[x] == b'#');
+    let first = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let last = (0..width).rev().find(|x| lit(*x)).unwrap_or(0);
+    rows.iter()
+        .map(|r| &r[first..=last])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+fn picture(s: &str) -> Vec<Vec<bool>> {
+    s.lines()
+        .map(|l| l.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+#[test]
+fn test_small_font() {
+    // the answer to somebody's 2021 day 13
+    let rows = picture(
+        "###..####.#..#.####.#....###...##..#..#.
+#..#....#.#.#.....#.#....#..#.#..#.#..#.
+#..#...#..##.....#..#....#..#.#....####.
+###...#...#.#...#...#....###..#.##.#..#.
+#.#..#....#.#..#....#....#....#..#.#..#.
+#..#.####.#..#.####.####.#.....###.#..#.",
+    );
+    assert_eq!(recognise_rows(&rows), Ok("RZKZLPGH".to_owned()));
+}
+
+#[test]
+fn test_small_font_with_narrow_and_wide_letters() {
+    let rows = picture(
+        "..........................
+.##..####.#..#...##.#...#.
+#..#....#.#.#.....#.#...#.
+#......#..##......#..#.#..
+#.##..#...#.#.....#...#...
+#..#.#....#.#..#..#...#...
+.###.####.#..#..##....#...
+..........................",
+    );
+    assert_eq!(recognise_rows(&rows), Ok("GZKJY".to_owned()));
+    let rows = picture(
+        ".###.
+..#..
+..#..
+..#..
+..#..
+.###.",
+    );
+    assert_eq!(recognise_rows(&rows), Ok("I".to_owned()));
+}
+
+#[test]
+fn test_large_font_from_points() {
+    let rows = picture(
+        "######..#####...#....#...####.
+#.......#....#..#...#...#....#
+#.......#....#..#..#....#.....
+#.......#....#..#.#.....#.....
+#####...#####...##......#.....
+#.......#..#....##......#.....
+#.......#...#...#.#.....#.....
+#.......#...#...#..#....#.....
+#.......#....#..#...#...#....#
+######..#....#..#....#...####.",
+    );
+    let points = rows.iter().enumerate().flat_map(|(y, row)| {
+        row.iter()
+            .enumerate()
+            .filter(|(_, lit)| **lit)
+            .map(move |(x, _)| (x as i64 - 100, y as i64 + 50))
+    });
+    assert_eq!(recognise_points(points), Ok("ERKC".to_owned()));
+}
+
+#[test]
+fn test_failures() {
+    assert_eq!(recognise_rows(&picture("....\n....")), Err(OcrError::Empty));
+    assert_eq!(
+        recognise_rows(&picture("#\n#\n#")),
+        Err(OcrError::UnknownHeight(3))
+    );
+    let square = picture("#####\n#...#\n#...#\n#...#\n#...#\n#####");
+    assert_eq!(
+        recognise_rows(&square),
+        Err(OcrError::UnknownGlyph {
+            position: 0,
+            glyph: "#####\n#...#\n#...#\n#...#\n#...#\n#####".to_owned()
+        })
+    );
+    let ho = picture("#..#..##..\n#..#.#..#.\n####.#..#.\n#..#.#..#.\n#..#.#..#.\n#..#..##.#");
+    match recognise_rows(&ho) {
+        Err(OcrError::UnknownGlyph { position, .. }) => assert_eq!(position, 1),
+        other => panic!("Expected the second letter to fail, got {:?}", other),
+    }
+}
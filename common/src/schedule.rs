@@ -0,0 +1,460 @@
+//! Scheduling tasks which depend on each other across a number of workers.
+//!
+//! A [`Scheduler`] holds the tasks and which must finish before which. Running it with a
+//! number of workers and a function giving each task's duration produces a [`Schedule`] of
+//! who did what when. Whenever a worker is free it takes the smallest available task (by the
+//! task's `Ord`), and free workers are handed tasks lowest-numbered first, so the same input
+//! always gives the same schedule.
+//!
+//! [`Scheduler::critical_path`] ignores the workers altogether and finds the longest chain of
+//! dependencies, which is the fastest the tasks could ever be done.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError<T> {
+    /// There has to be at least one worker to do anything.
+    NoWorkers,
+    /// These tasks depend on each other in a loop, or on something in a loop, so can never
+    /// start.
+    Cycle(Vec<T>),
+}
+
+impl<T: Debug> Display for ScheduleError<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ScheduleError::NoWorkers => write!(fmt, "there are no workers to do the tasks"),
+            ScheduleError::Cycle(tasks) => {
+                write!(fmt, "the tasks {:?} depend on each other in a loop", tasks)
+            }
+        }
+    }
+}
+
+impl<T: Debug> Error for ScheduleError<T> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheduler<T> {
+    // every task, with the tasks which must finish before it can start
+    prerequisites: BTreeMap<T, BTreeSet<T>>,
+}
+
+impl<T: Ord + Clone> Default for Scheduler<T> {
+    fn default() -> Self {
+        Scheduler {
+            prerequisites: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Ord + Clone> Scheduler<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a scheduler from `(before, after)` pairs, where `before` must finish before
+    /// `after` can start.
+    pub fn from_dependencies(dependencies: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut scheduler = Self::new();
+        for (before, after) in dependencies {
+            scheduler.add_dependency(before, after);
+        }
+        scheduler
+    }
+
+    /// Adds a task, which is harmless if it's already there.
+    pub fn add_task(&mut self, task: T) {
+        self.prerequisites.entry(task).or_default();
+    }
+
+    /// Says that `before` must finish before `after` can start, adding either if they're new.
+    pub fn add_dependency(&mut self, before: T, after: T) {
+        self.add_task(before.clone());
+        self.prerequisites.entry(after).or_default().insert(before);
+    }
+
+    /// All the tasks, smallest first.
+    pub fn tasks(&self) -> impl Iterator<Item = &T> {
+        self.prerequisites.keys()
+    }
+
+    /// Runs the tasks on `workers` workers, with `duration` saying how long each one takes.
+    pub fn run(
+        &self,
+        workers: usize,
+        duration: impl Fn(&T) -> u64,
+    ) -> Result<Schedule<T>, ScheduleError<T>> {
+        if workers == 0 {
+            return Err(ScheduleError::NoWorkers);
+        }
+
+        let mut waiting_on = self.waiting_on();
+        let dependents = self.dependents();
+        let mut available: BTreeSet<T> = waiting_on
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(task, _)| (*task).clone())
+            .collect();
+        // what each worker is doing and when it'll finish
+        let mut running: Vec<Option<(T, u64)>> = vec![None; workers];
+        let mut entries = Vec::with_capacity(self.prerequisites.len());
+        let mut time = 0;
+
+        loop {
+            for slot in running.iter_mut() {
+                if let Some((task, end)) = slot {
+                    if *end <= time {
+                        for dependent in &dependents[&*task] {
+                            let count = waiting_on.get_mut(dependent).unwrap();
+                            *count -= 1;
+                            if *count == 0 {
+                                available.insert((*dependent).clone());
+                            }
+                        }
+                        *slot = None;
+                    }
+                }
+            }
+
+            for (worker, slot) in running.iter_mut().enumerate() {
+                if slot.is_none() {
+                    if let Some(task) = available.iter().next().cloned() {
+                        available.remove(&task);
+                        let end = time + duration(&task);
+                        entries.push(Scheduled {
+                            task: task.clone(),
+                            worker,
+                            start: time,
+                            end,
+                        });
+                        *slot = Some((task, end));
+                    }
+                }
+            }
+
+            match running.iter().flatten().map(|(_, end)| *end).min() {
+                Some(end) => time = end,
+                None => break,
+            }
+        }
+
+        if entries.len() < self.prerequisites.len() {
+            let started = entries.iter().map(|e| &e.task).collect::<BTreeSet<_>>();
+            let stuck = self
+                .tasks()
+                .filter(|task| !started.contains(task))
+                .cloned()
+                .collect();
+            return Err(ScheduleError::Cycle(stuck));
+        }
+
+        Ok(Schedule { workers, entries })
+    }
+
+    /// The longest chain of tasks, each depending on the one before, with `duration` saying
+    /// how long each one takes. When there's a choice of equally long chains, the one made of
+    /// the smaller tasks wins.
+    pub fn critical_path(
+        &self,
+        duration: impl Fn(&T) -> u64,
+    ) -> Result<CriticalPath<T>, ScheduleError<T>> {
+        let order = self.topological_order()?;
+
+        // the earliest each task could finish, and the prerequisite holding it up
+        let mut finish: BTreeMap<&T, (u64, Option<&T>)> = BTreeMap::new();
+        for &task in &order {
+            let mut start = 0;
+            let mut via = None;
+            for before in &self.prerequisites[task] {
+                let (time, _) = finish[before];
+                if via.is_none() || time > start {
+                    start = time;
+                    via = Some(before);
+                }
+            }
+            finish.insert(task, (start + duration(task), via));
+        }
+
+        // the path ends at whichever task finishes last, and is followed back from there
+        let mut length = 0;
+        let mut last = None;
+        for (&task, &(time, _)) in &finish {
+            if last.is_none() || time > length {
+                length = time;
+                last = Some(task);
+            }
+        }
+
+        let mut tasks = Vec::new();
+        while let Some(task) = last {
+            tasks.push(task.clone());
+            last = finish[task].1;
+        }
+        tasks.reverse();
+
+        let steps = tasks
+            .iter()
+            .map(|task| {
+                let (end, _) = finish[task];
+                let start = end - duration(task);
+                (task.clone(), start, end)
+            })
+            .collect();
+
+        Ok(CriticalPath { steps, length })
+    }
+
+    /// The tasks in an order where everything comes after its prerequisites, taking the
+    /// smallest available task at each point.
+    pub fn topological_order(&self) -> Result<Vec<&T>, ScheduleError<T>> {
+        let mut waiting_on = self.waiting_on();
+        let dependents = self.dependents();
+        let mut available: BTreeSet<&T> = waiting_on
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(task, _)| *task)
+            .collect();
+
+        let mut order = Vec::with_capacity(self.prerequisites.len());
+        while let Some(task) = available.iter().next().cloned() {
+            available.remove(task);
+            order.push(task);
+            for dependent in &dependents[task] {
+                let count = waiting_on.get_mut(dependent).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    available.insert(dependent);
+                }
+            }
+        }
+
+        if order.len() < self.prerequisites.len() {
+            let stuck = waiting_on
+                .into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(task, _)| task.clone())
+                .collect();
+            return Err(ScheduleError::Cycle(stuck));
+        }
+
+        Ok(order)
+    }
+
+    fn waiting_on(&self) -> BTreeMap<&T, usize> {
+        self.prerequisites
+            .iter()
+            .map(|(task, before)| (task, before.len()))
+            .collect()
+    }
+
+    fn dependents(&self) -> BTreeMap<&T, Vec<&T>> {
+        let mut dependents: BTreeMap<&T, Vec<&T>> =
+            self.tasks().map(|task| (task, Vec::new())).collect();
+        for (task, before) in &self.prerequisites {
+            for b in before {
+                dependents.get_mut(b).unwrap().push(task);
+            }
+        }
+        dependents
+    }
+}
+
+/// One task's place in a [`Schedule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheduled<T> {
+    pub task: T,
+    pub worker: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule<T> {
+    workers: usize,
+    // in the order they were started
+    entries: Vec<Scheduled<T>>,
+}
+
+impl<T> Schedule<T> {
+    /// Every task with who did it and when, in the order they were started.
+    pub fn entries(&self) -> &[Scheduled<T>] {
+        &self.entries
+    }
+
+    /// The tasks in the order they were started.
+    pub fn order(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|e| &e.task)
+    }
+
+    /// When the last task finishes.
+    pub fn total_time(&self) -> u64 {
+        self.entries.iter().map(|e| e.end).max().unwrap_or(0)
+    }
+
+    /// How long each worker spent on tasks.
+    pub fn busy_time(&self) -> Vec<u64> {
+        let mut busy = vec![0; self.workers];
+        for entry in &self.entries {
+            busy[entry.worker] += entry.end - entry.start;
+        }
+        busy
+    }
+}
+
+/// A Gantt chart with a row for each worker and a column for each unit of time, showing the
+/// task being worked on or `.` when the worker is idle. Columns are as wide as the widest
+/// task, and the header gives the last digit of the time.
+impl<T: Display> Display for Schedule<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let labels = self
+            .entries
+            .iter()
+            .map(|e| e.task.to_string())
+            .collect::<Vec<_>>();
+        let width = labels.iter().map(|l| l.len()).max().unwrap_or(1);
+        let total = self.total_time() as usize;
+        let mut rows = vec![vec![format!("{:width$}", ".", width = width); total]; self.workers];
+        for (entry, label) in self.entries.iter().zip(labels.iter()) {
+            for cell in &mut rows[entry.worker][entry.start as usize..entry.end as usize] {
+                *cell = format!("{:width$}", label, width = width);
+            }
+        }
+
+        let name_width = format!("worker {}", self.workers).len();
+        let header = (0..total)
+            .map(|time| format!("{:<width$}", time % 10, width = width))
+            .collect::<String>();
+        let line = format!("{:name_width$} {}", "time", header, name_width = name_width);
+        writeln!(fmt, "{}", line.trim_end())?;
+        for (worker, row) in rows.iter().enumerate() {
+            let name = format!("worker {}", worker + 1);
+            let line = format!(
+                "{:name_width$} {}",
+                name,
+                row.concat(),
+                name_width = name_width
+            );
+            writeln!(fmt, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The longest chain through the tasks, from [`Scheduler::critical_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalPath<T> {
+    /// Each task on the path with when it would start and finish given unlimited workers.
+    pub steps: Vec<(T, u64, u64)>,
+    /// The total duration of the path.
+    pub length: u64,
+}
+
+impl<T> CriticalPath<T> {
+    pub fn tasks(&self) -> impl Iterator<Item = &T> {
+        self.steps.iter().map(|(task, _, _)| task)
+    }
+}
+
+impl<T: Display> Display for CriticalPath<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        writeln!(fmt, "Critical path taking {}:", self.length)?;
+        for (task, start, end) in &self.steps {
+            writeln!(fmt, "  {:>6} - {:<6} {}", start, end, task)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn example() -> Scheduler<char> {
+    // from 2018 day 7
+    Scheduler::from_dependencies(vec![
+        ('C', 'A'),
+        ('C', 'F'),
+        ('A', 'B'),
+        ('A', 'D'),
+        ('B', 'E'),
+        ('D', 'E'),
+        ('F', 'E'),
+    ])
+}
+
+#[cfg(test)]
+fn letter_duration(c: &char) -> u64 {
+    (*c as u8 - b'A' + 1) as u64
+}
+
+#[test]
+fn test_order_with_one_worker() {
+    let schedule = example().run(1, |_| 1).unwrap();
+    assert_eq!(schedule.order().collect::<String>(), "CABDFE");
+    assert_eq!(schedule.total_time(), 6);
+    let scheduler = example();
+    let order = scheduler.topological_order().unwrap();
+    assert_eq!(order.into_iter().collect::<String>(), "CABDFE");
+}
+
+#[test]
+fn test_example_with_two_workers() {
+    let schedule = example().run(2, letter_duration).unwrap();
+    assert_eq!(schedule.order().collect::<String>(), "CAFBDE");
+    assert_eq!(schedule.total_time(), 15);
+    assert_eq!(schedule.busy_time(), vec![15, 6]);
+    assert_eq!(
+        schedule.to_string(),
+        "time     012345678901234
+worker 1 CCCABBDDDDEEEEE
+worker 2 ...FFFFFF......
+"
+    );
+}
+
+#[test]
+fn test_wide_labels_and_zero_durations() {
+    let mut scheduler = Scheduler::from_dependencies(vec![(1, 10), (10, 2)]);
+    scheduler.add_task(3);
+    let schedule = scheduler.run(2, |&t| if t == 10 { 0 } else { 2 }).unwrap();
+    assert_eq!(
+        schedule.order().copied().collect::<Vec<_>>(),
+        vec![1, 3, 10, 2]
+    );
+    assert_eq!(schedule.total_time(), 4);
+    assert_eq!(
+        schedule.to_string(),
+        "time     0 1 2 3
+worker 1 1 1 2 2
+worker 2 3 3 . .
+"
+    );
+}
+
+#[test]
+fn test_critical_path() {
+    let path = example().critical_path(letter_duration).unwrap();
+    assert_eq!(path.tasks().collect::<String>(), "CFE");
+    assert_eq!(path.length, 14);
+    assert_eq!(path.steps[1], ('F', 3, 9));
+
+    // all the same length, so the smallest tasks win
+    let path = example().critical_path(|_| 1).unwrap();
+    assert_eq!(path.tasks().collect::<String>(), "CABE");
+    assert_eq!(path.length, 4);
+
+    let empty = Scheduler::<char>::new().critical_path(|_| 1).unwrap();
+    assert_eq!(empty.length, 0);
+    assert!(empty.steps.is_empty());
+}
+
+#[test]
+fn test_errors() {
+    let mut scheduler = example();
+    scheduler.add_dependency('E', 'C');
+    scheduler.add_dependency('X', 'Y');
+    let stuck = ScheduleError::Cycle(vec!['A', 'B', 'C', 'D', 'E', 'F']);
+    assert_eq!(scheduler.run(3, |_| 1), Err(stuck.clone()));
+    assert_eq!(scheduler.critical_path(|_| 1), Err(stuck.clone()));
+    assert_eq!(scheduler.topological_order(), Err(stuck));
+    assert_eq!(example().run(0, |_| 1), Err(ScheduleError::NoWorkers));
+}
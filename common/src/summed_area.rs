@@ -0,0 +1,178 @@
+//! A summed-area table answers "what do the values in this rectangle add up to?" in constant
+//! time, after a single pass over the grid to build it.
+
+use std::ops::{Add, Sub};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummedAreaTable<T> {
+    width: usize,
+    height: usize,
+    // one bigger than the grid in each direction, so the top row and left column are all
+    // zero and we never need to special-case the edges
+    sums: Vec<T>,
+}
+
+impl<T> SummedAreaTable<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Builds the table for a `width` by `height` grid whose value at `(x, y)` is `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> T) -> Self {
+        let mut sums = vec![T::default(); (width + 1) * (height + 1)];
+        for y in 0..height {
+            let mut row_sum = T::default();
+            for x in 0..width {
+                row_sum = row_sum + f(x, y);
+                sums[(y + 1) * (width + 1) + x + 1] = sums[y * (width + 1) + x + 1] + row_sum;
+            }
+        }
+        SummedAreaTable {
+            width,
+            height,
+            sums,
+        }
+    }
+
+    /// Builds the table from rows of values, which must all be the same length.
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Self {
+        let width = rows.first().map(|r| r.as_ref().len()).unwrap_or(0);
+        Self::from_fn(width, rows.len(), |x, y| rows[y].as_ref()[x])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The sum of everything in the `width` by `height` rectangle with its top left at
+    /// `(x, y)`, or `None` if the rectangle doesn't fit in the grid.
+    pub fn sum(&self, x: usize, y: usize, width: usize, height: usize) -> Option<T> {
+        if x + width > self.width || y + height > self.height {
+            return None;
+        }
+        Some(
+            self.corner(x + width, y + height) + self.corner(x, y)
+                - self.corner(x + width, y)
+                - self.corner(x, y + height),
+        )
+    }
+
+    /// The original value at `(x, y)`.
+    pub fn value(&self, x: usize, y: usize) -> Option<T> {
+        self.sum(x, y, 1, 1)
+    }
+
+    /// The sum of everything above and to the left of `(x, y)`, including `(x, y)` itself.
+    /// Building a table from a grid of differences and reading this gives back the values
+    /// the differences describe.
+    pub fn prefix_sum(&self, x: usize, y: usize) -> Option<T> {
+        self.sum(0, 0, x + 1, y + 1)
+    }
+
+    /// The position and total of the `width` by `height` window with the largest total.
+    /// Ties go to the first in reading order.
+    pub fn best_window(&self, width: usize, height: usize) -> Option<(usize, usize, T)>
+    where
+        T: Ord,
+    {
+        if width > self.width || height > self.height {
+            return None;
+        }
+        let mut best: Option<(usize, usize, T)> = None;
+        for y in 0..=self.height - height {
+            for x in 0..=self.width - width {
+                let total = self.sum(x, y, width, height)?;
+                let better = match best {
+                    Some((_, _, best_total)) => total > best_total,
+                    None => true,
+                };
+                if better {
+                    best = Some((x, y, total));
+                }
+            }
+        }
+        best
+    }
+
+    /// As `best_window`, but tries squares of each of the given sizes and also returns the
+    /// size of the winner. Ties go to the size tried first.
+    pub fn best_square(
+        &self,
+        sizes: impl IntoIterator<Item = usize>,
+    ) -> Option<(usize, usize, usize, T)>
+    where
+        T: Ord,
+    {
+        let mut best: Option<(usize, usize, usize, T)> = None;
+        for size in sizes {
+            if let Some((x, y, total)) = self.best_window(size, size) {
+                let better = match best {
+                    Some((_, _, _, best_total)) => total > best_total,
+                    None => true,
+                };
+                if better {
+                    best = Some((x, y, size, total));
+                }
+            }
+        }
+        best
+    }
+
+    fn corner(&self, x: usize, y: usize) -> T {
+        self.sums[y * (self.width + 1) + x]
+    }
+}
+
+#[test]
+fn test_sums() {
+    let table = SummedAreaTable::from_rows(&[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+    assert_eq!(table.sum(0, 0, 3, 3), Some(45));
+    assert_eq!(table.sum(1, 1, 2, 2), Some(28));
+    assert_eq!(table.sum(2, 0, 1, 3), Some(18));
+    assert_eq!(table.sum(0, 0, 0, 0), Some(0));
+    assert_eq!(table.sum(2, 2, 2, 1), None);
+    assert_eq!(table.value(1, 2), Some(8));
+    assert_eq!(table.prefix_sum(1, 1), Some(12));
+}
+
+#[test]
+fn test_prefix_sums_undo_differences() {
+    // two overlapping rectangles marked out by their corners
+    let mut differences = vec![vec![0i32; 5]; 4];
+    for &(left, top, right, bottom) in &[(0, 0, 3, 2), (1, 1, 4, 3)] {
+        differences[top][left] += 1;
+        differences[top][right] -= 1;
+        differences[bottom][left] -= 1;
+        differences[bottom][right] += 1;
+    }
+    let table = SummedAreaTable::from_rows(&differences);
+    let counts = (0..3)
+        .map(|y| {
+            (0..5)
+                .map(|x| table.prefix_sum(x, y).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        counts,
+        vec![
+            vec![1, 1, 1, 0, 0],
+            vec![1, 2, 2, 1, 0],
+            vec![0, 1, 1, 1, 0]
+        ]
+    );
+}
+
+#[test]
+fn test_best_window() {
+    let table =
+        SummedAreaTable::from_rows(&[vec![1, -5, 2, 2], vec![0, -5, 2, 2], vec![9, 9, -1, -9]]);
+    assert_eq!(table.best_window(2, 2), Some((0, 1, 13)));
+    assert_eq!(table.best_window(1, 1), Some((0, 2, 9)));
+    assert_eq!(table.best_window(5, 1), None);
+    assert_eq!(table.best_window(2, 1), Some((0, 2, 18)));
+    assert_eq!(table.best_square(1..=3), Some((0, 1, 2, 13)));
+}
@@ -0,0 +1,251 @@
+//! Rotating, reflecting, tiling and cutting up rectangular grids. Anything which can say how
+//! big it is, look up a cell, and build itself from a function gets the lot.
+
+/// A rectangle of cells which can be rearranged into new rectangles of the same kind.
+pub trait Transform: Sized {
+    type Cell: Clone;
+
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+
+    /// The cell at `(x, y)`, which is always inside the grid.
+    fn cell(&self, x: usize, y: usize) -> &Self::Cell;
+
+    fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> Self::Cell) -> Self;
+
+    /// Rotates a quarter turn clockwise.
+    fn rotate_90(&self) -> Self {
+        Self::from_fn(self.height(), self.width(), |x, y| {
+            self.cell(y, self.height() - 1 - x).clone()
+        })
+    }
+
+    fn rotate_180(&self) -> Self {
+        Self::from_fn(self.width(), self.height(), |x, y| {
+            self.cell(self.width() - 1 - x, self.height() - 1 - y)
+                .clone()
+        })
+    }
+
+    /// Rotates a quarter turn anticlockwise.
+    fn rotate_270(&self) -> Self {
+        Self::from_fn(self.height(), self.width(), |x, y| {
+            self.cell(self.width() - 1 - y, x).clone()
+        })
+    }
+
+    /// Mirrors left to right.
+    fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width(), self.height(), |x, y| {
+            self.cell(self.width() - 1 - x, y).clone()
+        })
+    }
+
+    /// Mirrors top to bottom.
+    fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width(), self.height(), |x, y| {
+            self.cell(x, self.height() - 1 - y).clone()
+        })
+    }
+
+    fn transpose(&self) -> Self {
+        Self::from_fn(self.height(), self.width(), |x, y| self.cell(y, x).clone())
+    }
+
+    /// All eight rotations and reflections of the grid, starting with the grid itself.
+    /// Symmetric grids will produce duplicates.
+    fn symmetries(&self) -> Vec<Self> {
+        let flipped = self.flip_horizontal();
+        vec![
+            Self::from_fn(self.width(), self.height(), |x, y| self.cell(x, y).clone()),
+            self.rotate_90(),
+            self.rotate_180(),
+            self.rotate_270(),
+            flipped.rotate_90(),
+            flipped.rotate_180(),
+            flipped.rotate_270(),
+            flipped,
+        ]
+    }
+
+    /// Repeats the grid `across` times horizontally and `down` times vertically.
+    /// `f` is given each value along with the column and row of the copy it's being
+    /// placed in, and returns the value to put there.
+    fn tile(
+        &self,
+        across: usize,
+        down: usize,
+        f: impl Fn(&Self::Cell, usize, usize) -> Self::Cell,
+    ) -> Self {
+        Self::from_fn(self.width() * across, self.height() * down, |x, y| {
+            f(
+                self.cell(x % self.width(), y % self.height()),
+                x / self.width(),
+                y / self.height(),
+            )
+        })
+    }
+
+    /// Copies out the `width` by `height` rectangle with its top left at `(x, y)`,
+    /// or `None` if it doesn't fit inside the grid.
+    fn sub_grid(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Self> {
+        if x + width > self.width() || y + height > self.height() {
+            return None;
+        }
+        Some(Self::from_fn(width, height, |dx, dy| {
+            self.cell(x + dx, y + dy).clone()
+        }))
+    }
+
+    /// Splits a grid into square blocks of side `size`, returned as rows of blocks.
+    /// Fails if the grid doesn't divide evenly.
+    fn split_blocks(&self, size: usize) -> Option<Vec<Vec<Self>>> {
+        if size == 0 || !self.width().is_multiple_of(size) || !self.height().is_multiple_of(size) {
+            return None;
+        }
+
+        (0..self.height() / size)
+            .map(|by| {
+                (0..self.width() / size)
+                    .map(|bx| self.sub_grid(bx * size, by * size, size, size))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The reverse of `split_blocks`. All blocks must be the same size, and every row
+    /// must have the same number of blocks.
+    fn join_blocks(blocks: &[Vec<Self>]) -> Option<Self> {
+        let first = blocks.first()?.first()?;
+        let (block_width, block_height) = (first.width(), first.height());
+        let across = blocks[0].len();
+        if blocks.iter().any(|row| {
+            row.len() != across
+                || row
+                    .iter()
+                    .any(|b| b.width() != block_width || b.height() != block_height)
+        }) {
+            return None;
+        }
+
+        Some(Self::from_fn(
+            block_width * across,
+            block_height * blocks.len(),
+            |x, y| {
+                blocks[y / block_height][x / block_width]
+                    .cell(x % block_width, y % block_height)
+                    .clone()
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Pattern(Vec<Vec<char>>);
+
+#[cfg(test)]
+impl Transform for Pattern {
+    type Cell = char;
+
+    fn width(&self) -> usize {
+        self.0.first().map_or(0, |row| row.len())
+    }
+
+    fn height(&self) -> usize {
+        self.0.len()
+    }
+
+    fn cell(&self, x: usize, y: usize) -> &char {
+        &self.0[y][x]
+    }
+
+    fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> char) -> Pattern {
+        Pattern(
+            (0..height)
+                .map(|y| (0..width).map(|x| f(x, y)).collect())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+fn parse_pattern(input: &str) -> Pattern {
+    Pattern(input.split('/').map(|row| row.chars().collect()).collect())
+}
+
+#[cfg(test)]
+fn render_pattern(pattern: &Pattern) -> String {
+    pattern
+        .0
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[test]
+fn test_rotations() {
+    let grid = parse_pattern("abc/def");
+    assert_eq!(render_pattern(&grid.rotate_90()), "da/eb/fc");
+    assert_eq!(render_pattern(&grid.rotate_180()), "fed/cba");
+    assert_eq!(render_pattern(&grid.rotate_270()), "cf/be/ad");
+    assert_eq!(grid.rotate_90().rotate_270(), grid);
+}
+
+#[test]
+fn test_flips_and_transpose() {
+    let grid = parse_pattern("abc/def");
+    assert_eq!(render_pattern(&grid.flip_horizontal()), "cba/fed");
+    assert_eq!(render_pattern(&grid.flip_vertical()), "def/abc");
+    assert_eq!(render_pattern(&grid.transpose()), "ad/be/cf");
+}
+
+#[test]
+fn test_symmetries() {
+    // the starting pattern and its variants from 2017 day 21
+    let grid = parse_pattern(".#./..#/###");
+    let symmetries = grid
+        .symmetries()
+        .iter()
+        .map(render_pattern)
+        .collect::<Vec<_>>();
+    assert_eq!(symmetries.len(), 8);
+    assert_eq!(symmetries[0], ".#./..#/###");
+    assert!(symmetries.contains(&".#./#../###".to_owned()));
+    assert!(symmetries.contains(&"#../#.#/##.".to_owned()));
+    assert!(symmetries.contains(&"###/..#/.#.".to_owned()));
+    let unique = symmetries.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(unique.len(), 8);
+}
+
+#[test]
+fn test_tile() {
+    let grid = parse_pattern("8/9").tile(3, 1, |c, tx, ty| {
+        let value = c.to_digit(10).unwrap() as usize + tx + ty;
+        std::char::from_digit(((value - 1) % 9 + 1) as u32, 10).unwrap()
+    });
+    assert_eq!(render_pattern(&grid), "891/912");
+}
+
+#[test]
+fn test_sub_grid() {
+    let grid = parse_pattern("abcd/efgh/ijkl");
+    assert_eq!(render_pattern(&grid.sub_grid(1, 1, 2, 2).unwrap()), "fg/jk");
+    assert_eq!(
+        render_pattern(&grid.sub_grid(2, 0, 2, 3).unwrap()),
+        "cd/gh/kl"
+    );
+    assert!(grid.sub_grid(3, 0, 2, 1).is_none());
+}
+
+#[test]
+fn test_split_and_join_blocks() {
+    let grid = parse_pattern("#..#/..../..../#..#");
+    let blocks = grid.split_blocks(2).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].len(), 2);
+    assert_eq!(render_pattern(&blocks[1][1]), "../.#");
+    assert!(grid.split_blocks(3).is_none());
+    assert_eq!(Pattern::join_blocks(&blocks), Some(grid));
+}
@@ -0,0 +1,636 @@
+//! Turing machines, as described by Advent of Code's blueprints (2017 day 25).
+//!
+//! A [`Blueprint`] is parsed from text like this:
+//!
+//! ```text
+//! Begin in state A.
+//! Perform a diagnostic checksum after 6 steps.
+//!
+//! In state A:
+//!   If the current value is 0:
+//!     - Write the value 1.
+//!     - Move one slot to the right.
+//!     - Continue with state B.
+//!   ...
+//! ```
+//!
+//! and run by a [`Machine`]. States are referred to by their index in the order the blueprint
+//! defines them, so running doesn't involve any name lookups. The [`Tape`] only stores
+//! about as much as the head has visited, which is always one contiguous run of cells, so it's
+//! kept in a single `Vec` that grows in whichever direction it's needed.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// What's written on the tape. Every cell starts out as 0.
+pub type Symbol = u8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Left,
+    Right,
+}
+
+/// What to do on reading one symbol in one state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub write: Symbol,
+    pub movement: Move,
+    /// The index of the state to go to.
+    pub next: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The blueprint stopped part way through.
+    UnexpectedEnd,
+    /// Line `line` (counting from one) isn't what should come next.
+    Unexpected {
+        line: usize,
+        expected: &'static str,
+        found: String,
+    },
+    /// A rule continues with a state that's never defined.
+    UnknownState { line: usize, name: String },
+    /// A state is defined twice, or says what to do with one symbol twice.
+    Duplicate { line: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "The blueprint ends too soon"),
+            ParseError::Unexpected {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {} should be \"{}\" but is \"{}\"",
+                line, expected, found
+            ),
+            ParseError::UnknownState { line, name } => {
+                write!(
+                    f,
+                    "Line {} refers to state {}, which doesn't exist",
+                    line, name
+                )
+            }
+            ParseError::Duplicate { line } => write!(f, "Line {} repeats an earlier rule", line),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blueprint {
+    /// The index of the state to begin in.
+    pub start: usize,
+    /// How many steps to run before taking the checksum.
+    pub steps: usize,
+    names: Vec<String>,
+    /// Indexed by state, then by the symbol under the head.
+    rules: Vec<Vec<Option<Rule>>>,
+}
+
+impl Blueprint {
+    /// The state's name as the blueprint gave it.
+    pub fn name(&self, state: usize) -> &str {
+        &self.names[state]
+    }
+
+    /// The index of the state with this name.
+    pub fn state(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// What to do in `state` on reading `symbol`, or `None` if the blueprint doesn't say, in
+    /// which case the machine halts.
+    pub fn rule(&self, state: usize, symbol: Symbol) -> Option<Rule> {
+        self.rules[state].get(symbol as usize).cloned().flatten()
+    }
+}
+
+/// The non-blank lines of a blueprint with their line numbers, trimmed.
+struct Lines<'a> {
+    lines: std::iter::Peekable<Box<dyn Iterator<Item = (usize, &'a str)> + 'a>>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(s: &'a str) -> Lines<'a> {
+        let lines: Box<dyn Iterator<Item = (usize, &'a str)>> = Box::new(
+            s.lines()
+                .enumerate()
+                .map(|(i, l)| (i + 1, l.trim()))
+                .filter(|(_, l)| !l.is_empty()),
+        );
+        Lines {
+            lines: lines.peekable(),
+        }
+    }
+
+    fn is_empty(&mut self) -> bool {
+        self.lines.peek().is_none()
+    }
+
+    fn peek_starts_with(&mut self, prefix: &str) -> bool {
+        self.lines
+            .peek()
+            .is_some_and(|(_, l)| l.starts_with(prefix))
+    }
+
+    fn next_line_number(&mut self) -> Option<usize> {
+        self.lines.peek().map(|&(line, _)| line)
+    }
+
+    /// Reads a line of the form `<prefix><word><terminator>`, giving back the line number and
+    /// the word.
+    fn expect(
+        &mut self,
+        prefix: &'static str,
+        terminator: &'static str,
+    ) -> Result<(usize, &'a str), ParseError> {
+        let (line, text) = self.lines.next().ok_or(ParseError::UnexpectedEnd)?;
+        text.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(terminator))
+            .map(|word| (line, word.trim()))
+            .ok_or_else(|| ParseError::Unexpected {
+                line,
+                expected: prefix,
+                found: text.to_owned(),
+            })
+    }
+
+    fn expect_number<T: FromStr>(
+        &mut self,
+        prefix: &'static str,
+        terminator: &'static str,
+    ) -> Result<T, ParseError> {
+        let (line, word) = self.expect(prefix, terminator)?;
+        T::from_str(word).map_err(|_| ParseError::Unexpected {
+            line,
+            expected: prefix,
+            found: word.to_owned(),
+        })
+    }
+}
+
+/// A rule as the blueprint gives it: the next state is still a name, along with the line it's
+/// on in case there's no such state.
+type WrittenRule<'a> = (Symbol, Move, usize, &'a str);
+
+impl FromStr for Blueprint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Blueprint, ParseError> {
+        let mut lines = Lines::new(s);
+
+        let (start_line, start) = lines.expect("Begin in state ", ".")?;
+        let steps = lines.expect_number("Perform a diagnostic checksum after ", " steps.")?;
+
+        let mut written: Vec<Vec<Option<WrittenRule>>> = vec![];
+        let mut names: Vec<&str> = vec![];
+
+        while !lines.is_empty() {
+            let (line, name) = lines.expect("In state ", ":")?;
+            if names.contains(&name) {
+                return Err(ParseError::Duplicate { line });
+            }
+            names.push(name);
+
+            let mut rules = vec![];
+            while lines.peek_starts_with("If the current value is ") {
+                let line = lines.next_line_number().unwrap();
+                let symbol: Symbol = lines.expect_number("If the current value is ", ":")?;
+                let write = lines.expect_number("- Write the value ", ".")?;
+                let (move_line, direction) = lines.expect("- Move one slot to the ", ".")?;
+                let movement = match direction {
+                    "left" => Move::Left,
+                    "right" => Move::Right,
+                    _ => {
+                        return Err(ParseError::Unexpected {
+                            line: move_line,
+                            expected: "left or right",
+                            found: direction.to_owned(),
+                        })
+                    }
+                };
+                let (next_line, next) = lines.expect("- Continue with state ", ".")?;
+
+                let symbol = symbol as usize;
+                if rules.len() <= symbol {
+                    rules.resize(symbol + 1, None);
+                }
+                if rules[symbol].is_some() {
+                    return Err(ParseError::Duplicate { line });
+                }
+                rules[symbol] = Some((write, movement, next_line, next));
+            }
+            written.push(rules);
+        }
+
+        let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let lookup = |line: usize, name: &str| {
+            index
+                .get(name)
+                .cloned()
+                .ok_or_else(|| ParseError::UnknownState {
+                    line,
+                    name: name.to_owned(),
+                })
+        };
+
+        let rules = written
+            .into_iter()
+            .map(|rules| {
+                rules
+                    .into_iter()
+                    .map(|rule| match rule {
+                        Some((write, movement, line, next)) => Ok(Some(Rule {
+                            write,
+                            movement,
+                            next: lookup(line, next)?,
+                        })),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Blueprint {
+            start: lookup(start_line, start)?,
+            steps,
+            names: names.into_iter().map(|n| n.to_owned()).collect(),
+            rules,
+        })
+    }
+}
+
+/// A tape which goes on forever both ways, blank (0) wherever it hasn't been written.
+///
+/// Tapes are equal if they've been written over the same stretch and hold the same symbols
+/// there, however much room each has set aside.
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+    cells: Vec<Symbol>,
+    /// Where position 0 is in `cells`.
+    origin: usize,
+    /// How many cells aren't blank, kept up to date so the checksum is free.
+    written: usize,
+    /// The leftmost and rightmost positions written, which `cells` may go beyond.
+    extent: Option<(i64, i64)>,
+}
+
+impl Tape {
+    pub fn new() -> Tape {
+        Tape::default()
+    }
+
+    pub fn get(&self, position: i64) -> Symbol {
+        let index = position + self.origin as i64;
+        if index < 0 {
+            0
+        } else {
+            self.cells.get(index as usize).cloned().unwrap_or(0)
+        }
+    }
+
+    pub fn set(&mut self, position: i64, symbol: Symbol) {
+        let index = self.make_room(position);
+        let cell = &mut self.cells[index];
+        match (*cell != 0, symbol != 0) {
+            (false, true) => self.written += 1,
+            (true, false) => self.written -= 1,
+            _ => {}
+        }
+        *cell = symbol;
+
+        self.extent = Some(match self.extent {
+            Some((left, right)) => (left.min(position), right.max(position)),
+            None => (position, position),
+        });
+    }
+
+    /// How many cells hold something other than a blank.
+    pub fn checksum(&self) -> usize {
+        self.written
+    }
+
+    /// The leftmost and rightmost positions which have ever been written, or `None` for a tape
+    /// which has never been touched.
+    pub fn extent(&self) -> Option<(i64, i64)> {
+        self.extent
+    }
+
+    /// Makes sure `position` is stored, growing the tape if it isn't, and gives back its index.
+    fn make_room(&mut self, position: i64) -> usize {
+        let index = position + self.origin as i64;
+        if index < 0 {
+            // double up on the left, so walking steadily leftwards doesn't keep copying
+            let extra = ((-index) as usize).max(self.cells.len());
+            let mut cells = vec![0; extra + self.cells.len()];
+            cells[extra..].copy_from_slice(&self.cells);
+            self.cells = cells;
+            self.origin += extra;
+            (position + self.origin as i64) as usize
+        } else {
+            let index = index as usize;
+            if index >= self.cells.len() {
+                self.cells.resize(index + 1, 0);
+            }
+            index
+        }
+    }
+}
+
+impl PartialEq for Tape {
+    fn eq(&self, other: &Tape) -> bool {
+        if self.extent != other.extent {
+            return false;
+        }
+        match self.extent {
+            Some((left, right)) => (left..=right).all(|p| self.get(p) == other.get(p)),
+            None => true,
+        }
+    }
+}
+
+impl Eq for Tape {}
+
+/// Part of the tape around the head, for showing what a machine is up to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The position of the first cell in `cells`.
+    pub first: i64,
+    pub cells: Vec<Symbol>,
+    pub head: i64,
+    pub state: String,
+    pub steps: usize,
+}
+
+impl fmt::Display for Snapshot {
+    /// Shows the cells the way the puzzle does: `... 0  1 [0] 1 ...`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "...")?;
+        for (i, symbol) in self.cells.iter().enumerate() {
+            if self.first + i as i64 == self.head {
+                write!(f, "[{}]", symbol)?;
+            } else {
+                write!(f, " {} ", symbol)?;
+            }
+        }
+        write!(f, "...")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    blueprint: &'a Blueprint,
+    tape: Tape,
+    head: i64,
+    state: usize,
+    steps: usize,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(blueprint: &'a Blueprint) -> Machine<'a> {
+        Machine {
+            blueprint,
+            tape: Tape::new(),
+            head: 0,
+            state: blueprint.start,
+            steps: 0,
+        }
+    }
+
+    pub fn state(&self) -> usize {
+        self.state
+    }
+
+    pub fn head(&self) -> i64 {
+        self.head
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn tape(&self) -> &Tape {
+        &self.tape
+    }
+
+    pub fn checksum(&self) -> usize {
+        self.tape.checksum()
+    }
+
+    /// Carries out one rule. Returns `false`, leaving everything as it was, if the blueprint
+    /// doesn't say what to do next.
+    pub fn step(&mut self) -> bool {
+        let rule = match self.blueprint.rule(self.state, self.tape.get(self.head)) {
+            Some(rule) => rule,
+            None => return false,
+        };
+
+        self.tape.set(self.head, rule.write);
+        self.head += match rule.movement {
+            Move::Left => -1,
+            Move::Right => 1,
+        };
+        self.state = rule.next;
+        self.steps += 1;
+        true
+    }
+
+    /// Runs up to `steps` steps, stopping early if the machine halts, and returns how many it
+    /// actually ran.
+    pub fn run(&mut self, steps: usize) -> usize {
+        (0..steps).take_while(|_| self.step()).count()
+    }
+
+    /// Runs for as many steps as the blueprint asks, then takes the checksum.
+    pub fn diagnose(&mut self) -> usize {
+        let remaining = self.blueprint.steps.saturating_sub(self.steps);
+        self.run(remaining);
+        self.checksum()
+    }
+
+    /// The cells within `radius` of the head.
+    pub fn snapshot(&self, radius: usize) -> Snapshot {
+        let first = self.head - radius as i64;
+        Snapshot {
+            first,
+            cells: (first..=self.head + radius as i64)
+                .map(|p| self.tape.get(p))
+                .collect(),
+            head: self.head,
+            state: self.blueprint.name(self.state).to_owned(),
+            steps: self.steps,
+        }
+    }
+}
+
+#[cfg(test)]
+const SAMPLE: &str = "Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+
+#[test]
+fn test_parse_blueprint() {
+    let blueprint = Blueprint::from_str(SAMPLE).unwrap();
+
+    assert_eq!(blueprint.start, 0);
+    assert_eq!(blueprint.steps, 6);
+    assert_eq!(blueprint.state_count(), 2);
+    assert_eq!(blueprint.state("B"), Some(1));
+    assert_eq!(
+        blueprint.rule(0, 1),
+        Some(Rule {
+            write: 0,
+            movement: Move::Left,
+            next: 1
+        })
+    );
+    assert_eq!(blueprint.rule(1, 2), None);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Blueprint::from_str("Begin in state A."),
+        Err(ParseError::UnexpectedEnd)
+    );
+    assert_eq!(
+        Blueprint::from_str(&SAMPLE.replacen("right", "up", 1)),
+        Err(ParseError::Unexpected {
+            line: 7,
+            expected: "left or right",
+            found: "up".to_owned()
+        })
+    );
+    assert_eq!(
+        Blueprint::from_str(&SAMPLE.replace("state B.", "state C.")),
+        Err(ParseError::UnknownState {
+            line: 8,
+            name: "C".to_owned()
+        })
+    );
+    assert_eq!(
+        Blueprint::from_str(&SAMPLE.replace("value is 1:", "value is 0:")),
+        Err(ParseError::Duplicate { line: 9 })
+    );
+}
+
+#[test]
+fn test_tape_grows_both_ways() {
+    let mut tape = Tape::new();
+    assert_eq!(tape.extent(), None);
+
+    tape.set(2, 1);
+    tape.set(-5, 2);
+    tape.set(-1, 1);
+    assert_eq!(tape.extent(), Some((-5, 2)));
+    assert_eq!(
+        (-6..=3).map(|p| tape.get(p)).collect::<Vec<_>>(),
+        vec![0, 2, 0, 0, 0, 1, 0, 0, 1, 0]
+    );
+    assert_eq!(tape.checksum(), 3);
+
+    tape.set(-5, 0);
+    tape.set(2, 1);
+    assert_eq!(tape.checksum(), 2);
+}
+
+#[test]
+fn test_extent_ignores_spare_room() {
+    let mut tape = Tape::new();
+    tape.set(2, 1);
+    tape.set(-5, 2);
+    tape.set(-6, 1);
+    assert_eq!(tape.extent(), Some((-6, 2)));
+
+    // the same writes in a different order leave a different amount of spare room
+    let mut other = Tape::new();
+    other.set(-6, 1);
+    other.set(-5, 2);
+    other.set(2, 1);
+    assert_eq!(other.extent(), Some((-6, 2)));
+    assert_ne!(tape.cells.len(), other.cells.len());
+    assert_eq!(tape, other);
+
+    other.set(3, 0);
+    assert_ne!(tape, other);
+    tape.set(3, 0);
+    assert_eq!(tape, other);
+    tape.set(0, 1);
+    assert_ne!(tape, other);
+}
+
+#[test]
+fn test_run_sample() {
+    let blueprint = Blueprint::from_str(SAMPLE).unwrap();
+    let mut machine = Machine::new(&blueprint);
+
+    machine.run(2);
+    let snapshot = machine.snapshot(3);
+    assert_eq!(snapshot.to_string(), "... 0  0  0 [1] 1  0  0 ...");
+    assert_eq!(snapshot.state, "A");
+
+    assert_eq!(machine.diagnose(), 3);
+    assert_eq!(machine.steps(), 6);
+    assert_eq!(
+        machine.snapshot(3).to_string(),
+        "... 0  1  1 [0] 1  0  0 ..."
+    );
+}
+
+#[test]
+fn test_halts_without_a_rule() {
+    let blueprint = Blueprint::from_str(
+        "Begin in state A.
+Perform a diagnostic checksum after 10 steps.
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+  If the current value is 2:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state A.",
+    )
+    .unwrap();
+    let mut machine = Machine::new(&blueprint);
+    assert_eq!(machine.diagnose(), 10);
+
+    let mut machine = Machine::new(&blueprint);
+    machine.tape.set(3, 1);
+    assert_eq!(machine.run(10), 3);
+    assert_eq!(machine.head(), 3);
+}